@@ -0,0 +1,209 @@
+//! Rules choosing the bin edges of a histogram from summarized data.
+//!
+//! All rules return [`BinEdges`] of constant width, covering the range from the
+//! minimum to the maximum of the data. The edges can be passed to
+//! `from_ranges`. The upper edge is the smallest float larger than the
+//! maximum, so that the maximum falls into the last bin.
+//!
+//! The rules choosing a bin width use at most [`MAX_BINS`] bins, because a
+//! single outlier can otherwise make the number of bins arbitrarily large. If
+//! the range is too large for the number of bins to be finite, one bin is used.
+//!
+//! [`BinEdges`]: ./struct.BinEdges.html
+//! [`MAX_BINS`]: ./constant.MAX_BINS.html
+//!
+//!
+//! ## Example
+//!
+//! ```
+//! use average::{Histogram, Max, Min, Variance, define_histogram};
+//! use average::bin_edges::scott;
+//!
+//! define_histogram!(hist, 2);
+//! let data = [1., 2., 2., 3., 3., 3., 4., 4., 5.];
+//! let min: Min = data.iter().collect();
+//! let max: Max = data.iter().collect();
+//! let variance: Variance = data.iter().collect();
+//! let edges = scott(min.min(), max.max(), &variance);
+//! assert_eq!(edges.bins(), 2);
+//! let mut h = hist::Histogram::from_ranges(edges).unwrap();
+//! for &x in &data {
+//!     h.add(x).unwrap();
+//! }
+//! assert_eq!(h.bins(), &[6, 3]);
+//! ```
+
+use num_traits::Float;
+
+use super::{Histogram, Quantile, Variance};
+use super::goodness_of_fit::ln_gamma;
+
+/// The largest number of bins chosen by the rules based on a bin width.
+pub const MAX_BINS: usize = 10_000;
+
+/// Iterate over the edges of bins of constant width.
+#[derive(Debug, Clone)]
+pub struct BinEdges {
+    start: f64,
+    end: f64,
+    len: usize,
+    next: usize,
+}
+
+impl BinEdges {
+    /// Divide the range from `start` to `end` into `len` bins of constant
+    /// width.
+    ///
+    /// Panics if `len` is zero or `usize::MAX`, because the number of edges
+    /// must fit into a `usize`.
+    #[inline]
+    pub fn new(start: f64, end: f64, len: usize) -> BinEdges {
+        assert!(len > 0, "At least one bin is required");
+        assert!(len < usize::MAX, "Too many bins");
+        BinEdges { start, end, len, next: 0 }
+    }
+
+    /// Cover the range from `min` to `max` inclusively by bins of the given
+    /// width, using at most `MAX_BINS` bins.
+    ///
+    /// One bin is used if the width is not positive or if the number of bins
+    /// is not finite.
+    fn with_width(min: f64, max: f64, width: f64) -> BinEdges {
+        let end = next_up(max);
+        let bins = Float::ceil((end - min) / width);
+        let len = if width > 0. && bins.is_finite() {
+            bins.max(1.).min(MAX_BINS as f64) as usize
+        } else {
+            1
+        };
+        BinEdges::new(min, end, len)
+    }
+
+    /// Return the number of bins, i.e. one less than the number of edges.
+    #[inline]
+    pub fn bins(&self) -> usize {
+        self.len
+    }
+
+    /// Return the width of the bins.
+    #[inline]
+    pub fn width(&self) -> f64 {
+        (self.end - self.start) / self.len as f64
+    }
+}
+
+impl Iterator for BinEdges {
+    type Item = f64;
+
+    fn next(&mut self) -> Option<f64> {
+        let i = self.next;
+        if i > self.len {
+            return None;
+        }
+        self.next += 1;
+        if i == 0 {
+            Some(self.start)
+        } else if i == self.len {
+            Some(self.end)
+        } else {
+            Some(self.start + self.width() * i as f64)
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let edges = self.len.saturating_add(1);
+        let remaining = edges - self.next.min(edges);
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for BinEdges {}
+
+/// Return the smallest float larger than `x`, or `x` if there is none.
+fn next_up(x: f64) -> f64 {
+    if x.is_nan() || x == f64::infinity() {
+        return x;
+    }
+    if x == 0. {
+        return f64::from_bits(1);
+    }
+    let bits = x.to_bits();
+    f64::from_bits(if x > 0. { bits + 1 } else { bits - 1 })
+}
+
+/// Choose `ceil(log2(n)) + 1` bins for `n` samples between `min` and `max`.
+///
+/// Sturges' rule assumes approximately normal data and chooses too few bins
+/// for large samples.
+pub fn sturges(min: f64, max: f64, n: u64) -> BinEdges {
+    let len = if n > 1 {
+        Float::ceil(Float::log2(n as f64)) as usize + 1
+    } else {
+        1
+    };
+    BinEdges::new(min, next_up(max), len)
+}
+
+/// Choose bins of width `3.49 σ / n^(1/3)` for samples between `min` and
+/// `max`, where `σ` is the sample standard deviation.
+///
+/// Scott's rule minimizes the integrated mean squared error of the density
+/// estimate for normal data. One bin is used if the variance vanishes, and at
+/// most `MAX_BINS` bins are used.
+pub fn scott(min: f64, max: f64, variance: &Variance) -> BinEdges {
+    let n = variance.len() as f64;
+    let width = 3.49 * Float::sqrt(variance.sample_variance()) / Float::cbrt(n);
+    BinEdges::with_width(min, max, width)
+}
+
+/// Choose bins of width `2 IQR / n^(1/3)` for samples between `min` and
+/// `max`, where the interquartile range `IQR` is estimated by the given lower
+/// and upper quartiles.
+///
+/// The Freedman-Diaconis rule is less sensitive to outliers than Scott's rule.
+/// The number of samples is taken from `lower`. One bin is used if the
+/// interquartile range vanishes, and at most `MAX_BINS` bins are used.
+///
+/// Panics if `lower` estimates a larger quantile than `upper`.
+pub fn freedman_diaconis(min: f64, max: f64, lower: &Quantile, upper: &Quantile) -> BinEdges {
+    assert!(lower.p() <= upper.p(), "The lower quantile must not exceed the upper one");
+    let n = lower.len() as f64;
+    let width = 2. * (upper.quantile() - lower.quantile()) / Float::cbrt(n);
+    BinEdges::with_width(min, max, width)
+}
+
+/// Choose the number of bins maximizing the posterior probability of Knuth's
+/// piecewise-constant density model, given a histogram with finely spaced bins
+/// of equal width.
+///
+/// The candidates are the bin counts dividing the number of bins of
+/// `histogram`, so that the fine bins can be merged exactly. The returned edges
+/// cover the range of `histogram`.
+///
+/// Panics if the range of `histogram` is not finite.
+pub fn knuth<H>(histogram: &H) -> BinEdges
+    where H: Histogram,
+          for<'a> &'a H: IntoIterator<Item = ((f64, f64), u64)>,
+{
+    let bins = histogram.bins();
+    let start = histogram.into_iter().next().map_or(0., |((a, _), _)| a);
+    let end = histogram.into_iter().last().map_or(0., |((_, b), _)| b);
+    assert!(start.is_finite() && end.is_finite(), "The range must be finite");
+    let n = histogram.total() as f64;
+
+    let mut best = (f64::neg_infinity(), 1);
+    for m in (1..=bins.len()).filter(|m| bins.len() % m == 0) {
+        let m_f = m as f64;
+        let group = bins.len() / m;
+        let counts: f64 = bins.chunks(group)
+            .map(|chunk| ln_gamma(chunk.iter().sum::<u64>() as f64 + 0.5))
+            .sum();
+        let log_posterior = n * m_f.ln() + ln_gamma(0.5 * m_f)
+            - m_f * ln_gamma(0.5) - ln_gamma(n + 0.5 * m_f) + counts;
+        if log_posterior > best.0 {
+            best = (log_posterior, m);
+        }
+    }
+    BinEdges::new(start, end, best.1)
+}
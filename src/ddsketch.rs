@@ -0,0 +1,333 @@
+use alloc::collections::BTreeMap;
+
+use num_traits::Float;
+#[cfg(feature = "serde1")] use serde::{Serialize, Deserialize};
+
+use super::{Estimate, Merge};
+
+/// Counts of the samples in the nonempty buckets of a `DdSketch`.
+///
+/// The buckets are stored sparsely, so that the memory usage does not depend
+/// on how far apart the buckets are.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+struct Store {
+    /// Number of samples for each nonempty bucket, by index.
+    counts: BTreeMap<i64, u64>,
+}
+
+impl Store {
+    /// Add `count` samples to the bucket with the given index.
+    ///
+    /// If there are more than `max_buckets` buckets afterwards, the lowest
+    /// ones are collapsed into one.
+    fn add(&mut self, index: i64, count: u64, max_buckets: Option<usize>) {
+        *self.counts.entry(index).or_insert(0) += count;
+        if let Some(max_buckets) = max_buckets {
+            while self.counts.len() > max_buckets {
+                let (&lowest, &collapsed) = self.counts.iter().next().unwrap();
+                self.counts.remove(&lowest);
+                *self.counts.values_mut().next().unwrap() += collapsed;
+            }
+        }
+    }
+
+    /// Return the number of nonempty buckets.
+    fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    /// Iterate over the indices and counts of the nonempty buckets.
+    fn buckets(&self) -> impl DoubleEndedIterator<Item = (i64, u64)> + '_ {
+        self.counts.iter().map(|(&i, &c)| (i, c))
+    }
+}
+
+/// Estimate quantiles of a sequence of numbers ("population") with bounded
+/// relative error, using a DDSketch.
+///
+/// The [DDSketch][1] counts the samples in buckets whose boundaries grow
+/// geometrically, such that every bucket can be represented by a value whose
+/// relative error is at most the chosen relative accuracy `alpha`. The
+/// estimated quantiles have the same relative error, which makes the sketch
+/// well suited for data spanning several orders of magnitude, like latencies.
+/// Positive and negative samples are counted separately, and zero has its own
+/// bucket.
+///
+/// Only nonempty buckets are stored. Their number is at most the sample size,
+/// and it grows logarithmically with the range of the samples: samples between
+/// `a` and `b` fall into at most `ln(b / a) / ln(gamma) + 1` buckets, where
+/// `gamma = (1 + alpha) / (1 - alpha)`. For an accuracy of 1 %, all positive
+/// `f64` values fit into about 73,000 buckets. The number of buckets can be
+/// bounded with [`with_max_buckets`], in which case the buckets for the
+/// samples closest to zero are collapsed, and only the quantiles of samples
+/// with a larger magnitude keep their accuracy. Non-finite samples are
+/// ignored.
+///
+/// Collecting an iterator into a `DdSketch` uses a relative accuracy of 1 %,
+/// and [`Estimate::estimate`] estimates the median.
+///
+/// [1]: https://arxiv.org/abs/1908.10693
+/// [`with_max_buckets`]: #method.with_max_buckets
+/// [`Estimate::estimate`]: ./trait.Estimate.html#tymethod.estimate
+///
+///
+/// ## Example
+///
+/// ```
+/// use average::{DdSketch, Estimate};
+///
+/// let mut s = DdSketch::with_relative_accuracy(0.01);
+/// for i in 0..1000 {
+///     s.add(1.01f64.powi(i));
+/// }
+/// let p99 = 1.01f64.powi(989);
+/// assert!((s.quantile(0.99) - p99).abs() <= 0.01 * p99);
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(doc_cfg, doc(cfg(all(feature = "alloc", any(feature = "std", feature = "libm")))))]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+pub struct DdSketch<T = f64> {
+    /// Relative accuracy.
+    alpha: T,
+    /// Ratio of the boundaries of adjacent buckets.
+    gamma: T,
+    /// Largest number of buckets for each of the stores.
+    max_buckets: Option<usize>,
+    /// Buckets of the positive samples.
+    positive: Store,
+    /// Buckets of the magnitudes of the negative samples.
+    negative: Store,
+    /// Number of samples equal to zero.
+    zero: u64,
+    /// Smallest sample.
+    min: T,
+    /// Largest sample.
+    max: T,
+    /// Sample size.
+    len: u64,
+}
+
+impl DdSketch<f64> {
+    /// Create a new sketch with a relative accuracy of 1 %.
+    ///
+    /// Use `new_generic` for other floating point types than `f64`.
+    #[inline]
+    pub fn new() -> DdSketch<f64> {
+        DdSketch::new_generic()
+    }
+}
+
+impl<T: Float> DdSketch<T> {
+    /// Create a new sketch with a relative accuracy of 1 %.
+    #[inline]
+    pub fn new_generic() -> DdSketch<T> {
+        DdSketch::with_relative_accuracy(T::from(0.01).unwrap())
+    }
+
+    /// Create a new sketch with the given relative accuracy and an unbounded
+    /// number of buckets.
+    ///
+    /// Panics if `alpha` is not between 0 and 1 (exclusive), or if it is so
+    /// small that the bucket boundaries cannot be distinguished with the
+    /// precision of `T`.
+    #[inline]
+    pub fn with_relative_accuracy(alpha: T) -> DdSketch<T> {
+        assert!(T::zero() < alpha && alpha < T::one(),
+            "The relative accuracy must be between 0 and 1");
+        let gamma = (T::one() + alpha) / (T::one() - alpha);
+        assert!(gamma.ln() > T::zero(),
+            "The relative accuracy is too small for the floating point type");
+        DdSketch {
+            alpha,
+            gamma,
+            max_buckets: None,
+            positive: Store::default(),
+            negative: Store::default(),
+            zero: 0,
+            min: T::infinity(),
+            max: T::neg_infinity(),
+            len: 0,
+        }
+    }
+
+    /// Create a new sketch with the given relative accuracy, keeping at most
+    /// `max_buckets` buckets each for the positive and the negative samples.
+    ///
+    /// Panics if `alpha` is not between 0 and 1 (exclusive) or if
+    /// `max_buckets` is zero.
+    #[inline]
+    pub fn with_max_buckets(alpha: T, max_buckets: usize) -> DdSketch<T> {
+        assert!(max_buckets > 0, "At least one bucket is required");
+        DdSketch {
+            max_buckets: Some(max_buckets),
+            ..DdSketch::with_relative_accuracy(alpha)
+        }
+    }
+
+    /// Return the relative accuracy.
+    #[inline]
+    pub fn relative_accuracy(&self) -> T {
+        self.alpha
+    }
+
+    /// Return the largest number of buckets for each sign, if bounded.
+    #[inline]
+    pub fn max_buckets(&self) -> Option<usize> {
+        self.max_buckets
+    }
+
+    /// Return the number of nonempty buckets.
+    #[inline]
+    pub fn buckets(&self) -> usize {
+        self.positive.len() + self.negative.len() + if self.zero > 0 { 1 } else { 0 }
+    }
+
+    /// Return the sample size.
+    #[inline]
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Determine whether the sample is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Return the index of the bucket of the positive number `x`.
+    ///
+    /// The index saturates at the bounds of `i64`. This is only reached for
+    /// floating point types with a larger range than `f64`.
+    #[inline]
+    fn index(&self, x: T) -> i64 {
+        let index = (x.ln() / self.gamma.ln()).ceil();
+        index.to_i64().unwrap_or(if index > T::zero() { i64::MAX } else { i64::MIN })
+    }
+
+    /// Return the value representing the bucket with the given index.
+    #[inline]
+    fn value(&self, index: i64) -> T {
+        let two = T::from(2).unwrap();
+        (self.gamma.ln() * T::from(index).unwrap()).exp() * two / (self.gamma + T::one())
+    }
+
+    /// Estimate the p-quantile of the population.
+    ///
+    /// Returns 0 for an empty sample. Panics if `p` is not between 0 and 1.
+    pub fn quantile(&self, p: T) -> T {
+        assert!(T::zero() <= p && p <= T::one());
+        if self.is_empty() {
+            return T::zero();
+        }
+        if p == T::zero() {
+            return self.min;
+        }
+        if p == T::one() {
+            return self.max;
+        }
+
+        let rank = p * T::from(self.len - 1).unwrap();
+        let mut cumulative = 0;
+        let mut estimate = None;
+        for (index, count) in self.negative.buckets().rev() {
+            cumulative += count;
+            if T::from(cumulative).unwrap() > rank {
+                estimate = Some(-self.value(index));
+                break;
+            }
+        }
+        if estimate.is_none() {
+            cumulative += self.zero;
+            if T::from(cumulative).unwrap() > rank {
+                estimate = Some(T::zero());
+            }
+        }
+        if estimate.is_none() {
+            for (index, count) in self.positive.buckets() {
+                cumulative += count;
+                if T::from(cumulative).unwrap() > rank {
+                    estimate = Some(self.value(index));
+                    break;
+                }
+            }
+        }
+        // The extreme samples are known exactly.
+        estimate.unwrap_or(self.max).max(self.min).min(self.max)
+    }
+}
+
+impl<T: Float> core::default::Default for DdSketch<T> {
+    fn default() -> DdSketch<T> {
+        DdSketch::new_generic()
+    }
+}
+
+impl<T: Float> Estimate<T> for DdSketch<T> {
+    #[inline]
+    fn add(&mut self, x: T) {
+        if !x.is_finite() {
+            return;
+        }
+        self.min = self.min.min(x);
+        self.max = self.max.max(x);
+        self.len += 1;
+        if x > T::zero() {
+            let index = self.index(x);
+            self.positive.add(index, 1, self.max_buckets);
+        } else if x < T::zero() {
+            let index = self.index(-x);
+            self.negative.add(index, 1, self.max_buckets);
+        } else {
+            self.zero += 1;
+        }
+    }
+
+    #[inline]
+    fn estimate(&self) -> T {
+        self.quantile(T::from(0.5).unwrap())
+    }
+}
+
+impl<T: Float> Merge for DdSketch<T> {
+    /// Merge another sample into this one.
+    ///
+    /// Panics if the sketches have different relative accuracies. The bound
+    /// on the number of buckets of this sketch is kept.
+    ///
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use average::{DdSketch, Merge};
+    ///
+    /// let sequence: &[f64] = &[1., 2., 3., 4., 5., 6., 7., 8., 9.];
+    /// let (left, right) = sequence.split_at(3);
+    /// let mut s_left: DdSketch = left.iter().collect();
+    /// let s_right: DdSketch = right.iter().collect();
+    /// s_left.merge(&s_right);
+    /// assert_eq!(s_left.len(), 9);
+    /// assert!((s_left.quantile(0.5) - 5.).abs() <= 0.05);
+    /// ```
+    fn merge(&mut self, other: &DdSketch<T>) {
+        assert!(self.alpha == other.alpha,
+            "Both sketches must have the same relative accuracy");
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.len += other.len;
+        self.zero += other.zero;
+        for (index, count) in other.positive.buckets() {
+            if count > 0 {
+                self.positive.add(index, count, self.max_buckets);
+            }
+        }
+        for (index, count) in other.negative.buckets() {
+            if count > 0 {
+                self.negative.add(index, count, self.max_buckets);
+            }
+        }
+    }
+}
+
+impl_from_iterator!(DdSketch<T>);
+impl_from_par_iterator!(DdSketch<T>);
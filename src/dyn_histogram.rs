@@ -0,0 +1,406 @@
+use alloc::vec;
+use alloc::vec::Vec;
+#[cfg(feature = "serde1")] use core::convert::TryFrom;
+
+#[cfg(feature = "serde1")] use serde::{Serialize, Deserialize};
+
+use super::{Histogram, Merge, InvalidRangeError, SampleOutOfRangeError, OutlierCounts};
+use super::IncompatibleRangesError;
+use super::histogram::{self, IterHistogram, Spacing};
+
+/// A histogram with a number of bins chosen at runtime.
+///
+/// This behaves like the histograms defined by [`define_histogram`], but the
+/// ranges and bins are stored on the heap, so the number of bins can be read
+/// from a configuration file, for example.
+///
+/// [`define_histogram`]: ./macro.define_histogram.html
+///
+///
+/// ## Example
+///
+/// ```
+/// use average::{DynHistogram, Histogram};
+///
+/// let bins = 4;
+/// let mut h = DynHistogram::with_const_width(0., 100., bins);
+/// for i in 0..100 {
+///     h.add(f64::from(i)).unwrap();
+/// }
+/// assert_eq!(h.bins(), &[25, 25, 25, 25]);
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde1", serde(try_from = "DynHistogramData"))]
+pub struct DynHistogram {
+    /// The ranges defining the bins of the histogram.
+    range: Vec<f64>,
+    /// The bins of the histogram.
+    bin: Vec<u64>,
+    /// The counts of the samples outside of the bins, if counted.
+    #[cfg_attr(feature = "serde1", serde(default, skip_serializing_if = "Option::is_none"))]
+    outliers: Option<OutlierCounts>,
+    /// The spacing of the ranges.
+    #[cfg_attr(feature = "serde1", serde(skip))]
+    spacing: Spacing,
+}
+
+/// The serialized fields of a `DynHistogram`, which are validated before
+/// deserializing the histogram.
+#[cfg(feature = "serde1")]
+#[derive(Deserialize)]
+struct DynHistogramData {
+    range: Vec<f64>,
+    bin: Vec<u64>,
+    #[serde(default)]
+    outliers: Option<OutlierCounts>,
+}
+
+#[cfg(feature = "serde1")]
+impl TryFrom<DynHistogramData> for DynHistogram {
+    type Error = InvalidRangeError;
+
+    fn try_from(data: DynHistogramData) -> Result<DynHistogram, InvalidRangeError> {
+        if data.range.len() < 2 || data.range.len() != data.bin.len() + 1 {
+            return Err(InvalidRangeError::NotEnoughRanges);
+        }
+        let mut range = vec![0.; data.range.len()];
+        histogram::fill_ranges(&mut range, data.range)?;
+        Ok(DynHistogram {
+            range,
+            bin: data.bin,
+            outliers: data.outliers,
+            spacing: Spacing::Arbitrary,
+        })
+    }
+}
+
+impl DynHistogram {
+    /// Construct a histogram with `len` bins of constant width.
+    ///
+    /// Panics if `len` is zero.
+    #[inline]
+    pub fn with_const_width(start: f64, end: f64, len: usize) -> DynHistogram {
+        assert!(len > 0, "At least one bin is required");
+        let mut range = vec![0.; len + 1];
+        histogram::fill_const_width(&mut range, start, end);
+        DynHistogram {
+            range,
+            bin: vec![0; len],
+            outliers: None,
+            spacing: Spacing::linear(start, end),
+        }
+    }
+
+    /// Construct a histogram with `len` bins of constant width on a
+    /// logarithmic scale.
+    ///
+    /// Panics if `len` is zero or unless `0 < start < end` and both are
+    /// finite.
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[cfg_attr(doc_cfg, doc(cfg(any(feature = "std", feature = "libm"))))]
+    #[inline]
+    pub fn with_log_width(start: f64, end: f64, len: usize) -> DynHistogram {
+        assert!(len > 0, "At least one bin is required");
+        let mut range = vec![0.; len + 1];
+        histogram::fill_log_width(&mut range, start, end);
+        DynHistogram {
+            range,
+            bin: vec![0; len],
+            outliers: None,
+            spacing: Spacing::Logarithmic,
+        }
+    }
+
+    /// Construct a histogram with `len` bins starting at `start`, where the
+    /// upper edge of each bin is `ratio` times its lower edge.
+    ///
+    /// Panics if `len` is zero, if `start` is not positive and finite or if
+    /// `ratio` is not finite and larger than 1.
+    #[inline]
+    pub fn with_geometric_ratio(start: f64, ratio: f64, len: usize) -> DynHistogram {
+        assert!(len > 0, "At least one bin is required");
+        let mut range = vec![0.; len + 1];
+        histogram::fill_geometric(&mut range, start, ratio);
+        DynHistogram {
+            range,
+            bin: vec![0; len],
+            outliers: None,
+            spacing: Spacing::Logarithmic,
+        }
+    }
+
+    /// Construct a histogram from given ranges.
+    ///
+    /// The ranges are given by an iterator of floats where neighboring
+    /// pairs `(a, b)` define a bin for all `x` where `a <= x < b`. The number
+    /// of bins is one less than the number of ranges.
+    ///
+    /// Fails if the iterator is too short (less than 2 ranges), is not sorted
+    /// or contains `nan`. `inf` and empty ranges are allowed.
+    #[inline]
+    pub fn from_ranges<T>(ranges: T) -> Result<DynHistogram, InvalidRangeError>
+        where T: IntoIterator<Item = f64>
+    {
+        let ranges: Vec<f64> = ranges.into_iter().collect();
+        if ranges.len() < 2 {
+            return Err(InvalidRangeError::NotEnoughRanges);
+        }
+        let mut range = vec![0.; ranges.len()];
+        histogram::fill_ranges(&mut range, ranges)?;
+        Ok(DynHistogram {
+            bin: vec![0; range.len() - 1],
+            range,
+            outliers: None,
+            spacing: Spacing::Arbitrary,
+        })
+    }
+
+    /// Construct a histogram by copying the ranges and bins of another one,
+    /// along with its outlier counts and the spacing of the ranges.
+    #[doc(hidden)]
+    #[inline]
+    pub fn from_parts(range: &[f64], bin: &[u64], outliers: Option<OutlierCounts>,
+        spacing: Spacing) -> DynHistogram
+    {
+        DynHistogram { range: range.to_vec(), bin: bin.to_vec(), outliers, spacing }
+    }
+
+    /// Construct a histogram with `len` bins containing the same fraction of
+    /// the samples, given a function estimating the p-quantile, for example
+    /// `|p| digest.quantile(p)`.
+    ///
+    /// The largest sample is at the upper range limit and thus out of range,
+    /// unless outliers are counted. Fails if `len` is zero or if the quantiles
+    /// are not sorted or contain `nan`.
+    #[inline]
+    pub fn from_quantile_edges<F>(mut quantile: F, len: usize)
+        -> Result<DynHistogram, InvalidRangeError>
+        where F: FnMut(f64) -> f64
+    {
+        if len == 0 {
+            return Err(InvalidRangeError::NotEnoughRanges);
+        }
+        DynHistogram::from_ranges((0..=len).map(|i| quantile(i as f64 / len as f64)))
+    }
+
+    /// Count the samples that are out of range or `nan` instead of rejecting
+    /// them.
+    ///
+    /// The counts are included in the normalization of the variances, when
+    /// merging and when iterating with `iter_with_outliers`.
+    #[inline]
+    pub fn count_outliers(mut self) -> DynHistogram {
+        self.outliers = Some(OutlierCounts::default());
+        self
+    }
+
+    /// Return the counts of the samples that did not fall into any bin, if
+    /// they are counted.
+    #[inline]
+    pub fn outlier_counts(&self) -> Option<OutlierCounts> {
+        self.outliers
+    }
+
+    /// Return the number of bins.
+    #[inline]
+    pub fn num_bins(&self) -> usize {
+        self.bin.len()
+    }
+
+    /// Determine whether any bin is nonempty.
+    #[inline]
+    pub fn has_samples(&self) -> bool {
+        self.bin.iter().any(|&b| b > 0)
+    }
+
+    /// Find the index of the bin corresponding to the given sample.
+    ///
+    /// Fails if the sample is out of range of the histogram.
+    #[inline]
+    pub fn find(&self, x: f64) -> Result<usize, SampleOutOfRangeError> {
+        histogram::find(&self.range, self.spacing, x)
+    }
+
+    /// Add a sample to the histogram.
+    ///
+    /// Fails if the sample is out of range of the histogram, unless outliers
+    /// are counted.
+    #[inline]
+    pub fn add(&mut self, x: f64) -> Result<(), SampleOutOfRangeError> {
+        match (self.find(x), &mut self.outliers) {
+            (Ok(i), _) => self.bin[i] += 1,
+            (Err(_), Some(outliers)) => outliers.add(x, self.range[0]),
+            (Err(e), None) => return Err(e),
+        }
+        Ok(())
+    }
+
+    /// Return the ranges of the histogram.
+    #[inline]
+    pub fn ranges(&self) -> &[f64] {
+        &self.range[..]
+    }
+
+    /// Return an iterator over the bins and corresponding ranges:
+    /// `((lower, upper), count)`
+    #[inline]
+    pub fn iter(&self) -> IterHistogram<'_> {
+        self.into_iter()
+    }
+
+    /// Return an iterator over the bins like `iter`, preceded by the underflow
+    /// `((-inf, lower), count)` and followed by the overflow
+    /// `((upper, inf), count)` if outliers are counted.
+    #[inline]
+    pub fn iter_with_outliers(&self) -> IterHistogram<'_> {
+        IterHistogram::with_outliers(self.bins(), self.ranges(), self.outliers)
+    }
+
+    /// Reset all bins and outlier counts to zero.
+    #[inline]
+    pub fn reset(&mut self) {
+        for b in &mut self.bin {
+            *b = 0;
+        }
+        if let Some(ref mut outliers) = self.outliers {
+            outliers.reset();
+        }
+    }
+
+    /// Return the lower range limit.
+    ///
+    /// (The corresponding bin might be empty.)
+    #[inline]
+    pub fn range_min(&self) -> f64 {
+        self.range[0]
+    }
+
+    /// Return the upper range limit.
+    ///
+    /// (The corresponding bin might be empty.)
+    #[inline]
+    pub fn range_max(&self) -> f64 {
+        self.range[self.bin.len()]
+    }
+
+    /// Return a histogram with `len` bins, merging groups of adjacent bins.
+    ///
+    /// Panics if `len` is zero or the number of bins is not a multiple of
+    /// `len`.
+    pub fn rebin(&self, len: usize) -> DynHistogram {
+        let mut range = vec![0.; len + 1];
+        let mut bin = vec![0; len];
+        histogram::rebin(&self.range, &self.bin, &mut range, &mut bin);
+        DynHistogram { range, bin, outliers: self.outliers, spacing: self.spacing }
+    }
+
+    /// Return a histogram with the bins from the one containing `lo` to the
+    /// one containing `hi`, inclusive. Values out of range are clamped to the
+    /// range of the histogram.
+    ///
+    /// If outliers are counted, the samples in the other bins are added to
+    /// the underflow and overflow. Fails if `lo > hi`, if either is `nan` or if
+    /// no bin overlaps the range.
+    pub fn slice(&self, lo: f64, hi: f64) -> Result<DynHistogram, InvalidRangeError> {
+        if lo.is_nan() || hi.is_nan() {
+            return Err(InvalidRangeError::NaN);
+        }
+        if lo > hi {
+            return Err(InvalidRangeError::NotSorted);
+        }
+        let len = self.bin.len();
+        let start = self.range[1..].iter().take_while(|&&b| b <= lo).count();
+        let end = len - self.range[..len].iter().rev().take_while(|&&a| a > hi).count();
+        if start >= end {
+            return Err(InvalidRangeError::NotEnoughRanges);
+        }
+        let mut outliers = self.outliers;
+        if let Some(ref mut outliers) = outliers {
+            outliers.underflow += self.bin[..start].iter().sum::<u64>();
+            outliers.overflow += self.bin[end..].iter().sum::<u64>();
+        }
+        Ok(DynHistogram {
+            range: self.range[start..=end].to_vec(),
+            bin: self.bin[start..end].to_vec(),
+            outliers,
+            spacing: self.spacing,
+        })
+    }
+
+    /// Merge another histogram into this one, if their ranges are compatible.
+    ///
+    /// In contrast to `merge`, this does not panic, but fails without
+    /// modifying this histogram. The ranges are compatible if every bin of
+    /// one histogram lies within a bin of the other one. The merged histogram
+    /// has the coarser ranges. Both or none of the histograms must count
+    /// outliers, and if they do, they must have the same range limits.
+    pub fn try_merge(&mut self, other: &Self) -> Result<(), IncompatibleRangesError> {
+        histogram::check_outlier_ranges(&self.outliers, &self.range,
+            &other.outliers, &other.range)?;
+        let mut bin = self.bin.clone();
+        if histogram::add_refined_bins(&self.range, &mut bin, &other.range, &other.bin).is_ok() {
+            self.bin = bin;
+        } else {
+            let mut coarse = DynHistogram { outliers: self.outliers, ..other.clone() };
+            histogram::add_refined_bins(&coarse.range, &mut coarse.bin, &self.range, &self.bin)?;
+            *self = coarse;
+        }
+        histogram::try_merge_outliers(&mut self.outliers, &other.outliers)
+    }
+}
+
+impl<'a> IntoIterator for &'a DynHistogram {
+    type Item = ((f64, f64), u64);
+    type IntoIter = IterHistogram<'a>;
+    fn into_iter(self) -> IterHistogram<'a> {
+        IterHistogram::new(self.bins(), self.ranges())
+    }
+}
+
+impl Histogram for DynHistogram {
+    #[inline]
+    fn bins(&self) -> &[u64] {
+        &self.bin[..]
+    }
+
+    #[inline]
+    fn outliers(&self) -> u64 {
+        self.outliers.map_or(0, |o| o.total())
+    }
+}
+
+impl core::ops::AddAssign<&Self> for DynHistogram {
+    /// Add the bins of another histogram with the same ranges.
+    ///
+    /// Panics if the ranges are different or if only one of the histograms
+    /// counts outliers.
+    #[inline]
+    fn add_assign(&mut self, other: &Self) {
+        self.merge(other);
+    }
+}
+
+impl core::ops::MulAssign<u64> for DynHistogram {
+    #[inline]
+    fn mul_assign(&mut self, other: u64) {
+        for x in &mut self.bin {
+            *x *= other;
+        }
+        if let Some(ref mut outliers) = self.outliers {
+            *outliers *= other;
+        }
+    }
+}
+
+impl Merge for DynHistogram {
+    /// Merge another histogram into this one.
+    ///
+    /// Panics if the ranges are different or if only one of the histograms
+    /// counts outliers.
+    fn merge(&mut self, other: &Self) {
+        histogram::add_bins(&self.range, &mut self.bin, &other.range, &other.bin);
+        histogram::merge_outliers(&mut self.outliers, &other.outliers);
+    }
+}
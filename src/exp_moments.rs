@@ -0,0 +1,264 @@
+use num_traits::float::FloatCore;
+#[cfg(any(feature = "std", feature = "libm"))]
+use num_traits::Float;
+#[cfg(feature = "serde1")] use serde::{Serialize, Deserialize};
+
+use super::Estimate;
+
+/// Calculate the decay factor corresponding to a half-life.
+#[cfg(any(feature = "std", feature = "libm"))]
+#[inline]
+fn alpha_from_half_life<T: FloatCore + Float>(half_life: T) -> T {
+    assert!(half_life > T::zero(), "The half-life must be positive");
+    let half = T::from(0.5).unwrap();
+    T::one() - Float::powf(half, T::one() / half_life)
+}
+
+/// Estimate the exponentially weighted moving average of a sequence of numbers
+/// ("population").
+///
+/// The weight of a sample decays by the factor `1 - alpha` with every sample
+/// added after it, so recent samples matter more than old ones. The first
+/// sample initializes the average.
+///
+///
+/// ## Example
+///
+/// ```
+/// use average::{ExpMean, Estimate};
+///
+/// let mut a = ExpMean::new(0.5);
+/// a.add(1.);
+/// a.add(3.);
+/// assert_eq!(a.mean(), 2.);
+/// a.add(6.);
+/// assert_eq!(a.mean(), 4.);
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+pub struct ExpMean<T = f64> {
+    /// Decay factor.
+    alpha: T,
+    /// Moving average.
+    avg: T,
+    /// Sample size.
+    n: u64,
+}
+
+impl ExpMean<f64> {
+    /// Create a new exponentially weighted moving average estimator with the
+    /// decay factor `alpha`.
+    ///
+    /// Panics if `alpha` is not in the interval (0, 1].
+    ///
+    /// Use `new_generic` for other floating point types than `f64`.
+    #[inline]
+    pub fn new(alpha: f64) -> ExpMean<f64> {
+        ExpMean::new_generic(alpha)
+    }
+}
+
+impl<T: FloatCore> ExpMean<T> {
+    /// Create a new exponentially weighted moving average estimator with the
+    /// decay factor `alpha`.
+    ///
+    /// Panics if `alpha` is not in the interval (0, 1].
+    #[inline]
+    pub fn new_generic(alpha: T) -> ExpMean<T> {
+        assert!(T::zero() < alpha && alpha <= T::one(),
+            "The decay factor must be in the interval (0, 1]");
+        ExpMean { alpha, avg: T::zero(), n: 0 }
+    }
+
+    /// Return the decay factor.
+    #[inline]
+    pub fn alpha(&self) -> T {
+        self.alpha
+    }
+
+    /// Add an observation given its difference from the current average,
+    /// assuming the sample size was already updated.
+    #[inline]
+    fn add_inner(&mut self, delta: T) {
+        if self.n == 1 {
+            self.avg = self.avg + delta;
+        } else {
+            self.avg = self.avg + self.alpha * delta;
+        }
+    }
+
+    /// Determine whether the sample is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    /// Estimate the moving average of the population.
+    ///
+    /// Returns 0 for an empty sample.
+    #[inline]
+    pub fn mean(&self) -> T {
+        self.avg
+    }
+
+    /// Return the sample size.
+    #[inline]
+    pub fn len(&self) -> u64 {
+        self.n
+    }
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+impl<T: FloatCore + Float> ExpMean<T> {
+    /// Create a new exponentially weighted moving average estimator, such that
+    /// the weight of a sample halves after `half_life` further samples.
+    ///
+    /// Panics if `half_life` is not positive.
+    #[cfg_attr(doc_cfg, doc(cfg(any(feature = "std", feature = "libm"))))]
+    #[inline]
+    pub fn with_half_life(half_life: T) -> ExpMean<T> {
+        ExpMean::new_generic(alpha_from_half_life(half_life))
+    }
+}
+
+impl<T: FloatCore> Estimate<T> for ExpMean<T> {
+    #[inline]
+    fn add(&mut self, sample: T) {
+        self.n += 1;
+        let delta = sample - self.avg;
+        self.add_inner(delta);
+    }
+
+    #[inline]
+    fn estimate(&self) -> T {
+        self.mean()
+    }
+}
+
+/// Estimate the exponentially weighted moving average and variance of a
+/// sequence of numbers ("population").
+///
+/// The weight of a sample decays by the factor `1 - alpha` with every sample
+/// added after it, so recent samples matter more than old ones. See [Finch
+/// (2009)][1] for the algorithm.
+///
+/// [1]: https://fanf2.user.srcf.net/hermes/doc/antiforgery/stats.pdf
+///
+///
+/// ## Example
+///
+/// ```
+/// use average::{ExpVariance, Estimate};
+///
+/// let mut a = ExpVariance::new(0.5);
+/// a.add(1.);
+/// a.add(3.);
+/// assert_eq!(a.mean(), 2.);
+/// assert_eq!(a.variance(), 1.);
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+pub struct ExpVariance<T = f64> {
+    /// Estimator of the moving average.
+    avg: ExpMean<T>,
+    /// Moving variance.
+    var: T,
+}
+
+impl ExpVariance<f64> {
+    /// Create a new exponentially weighted moving variance estimator with the
+    /// decay factor `alpha`.
+    ///
+    /// Panics if `alpha` is not in the interval (0, 1].
+    ///
+    /// Use `new_generic` for other floating point types than `f64`.
+    #[inline]
+    pub fn new(alpha: f64) -> ExpVariance<f64> {
+        ExpVariance::new_generic(alpha)
+    }
+}
+
+impl<T: FloatCore> ExpVariance<T> {
+    /// Create a new exponentially weighted moving variance estimator with the
+    /// decay factor `alpha`.
+    ///
+    /// Panics if `alpha` is not in the interval (0, 1].
+    #[inline]
+    pub fn new_generic(alpha: T) -> ExpVariance<T> {
+        ExpVariance { avg: ExpMean::new_generic(alpha), var: T::zero() }
+    }
+
+    /// Return the decay factor.
+    #[inline]
+    pub fn alpha(&self) -> T {
+        self.avg.alpha()
+    }
+
+    /// Determine whether the sample is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.avg.is_empty()
+    }
+
+    /// Estimate the moving average of the population.
+    ///
+    /// Returns 0 for an empty sample.
+    #[inline]
+    pub fn mean(&self) -> T {
+        self.avg.mean()
+    }
+
+    /// Return the sample size.
+    #[inline]
+    pub fn len(&self) -> u64 {
+        self.avg.len()
+    }
+
+    /// Estimate the moving variance of the population.
+    ///
+    /// Returns 0 for less than 2 samples.
+    #[inline]
+    pub fn variance(&self) -> T {
+        self.var
+    }
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+impl<T: FloatCore + Float> ExpVariance<T> {
+    /// Create a new exponentially weighted moving variance estimator, such
+    /// that the weight of a sample halves after `half_life` further samples.
+    ///
+    /// Panics if `half_life` is not positive.
+    #[cfg_attr(doc_cfg, doc(cfg(any(feature = "std", feature = "libm"))))]
+    #[inline]
+    pub fn with_half_life(half_life: T) -> ExpVariance<T> {
+        ExpVariance::new_generic(alpha_from_half_life(half_life))
+    }
+
+    /// Estimate the moving standard deviation of the population.
+    ///
+    /// Returns 0 for less than 2 samples.
+    #[cfg_attr(doc_cfg, doc(cfg(any(feature = "std", feature = "libm"))))]
+    #[inline]
+    pub fn standard_deviation(&self) -> T {
+        Float::sqrt(self.var)
+    }
+}
+
+impl<T: FloatCore> Estimate<T> for ExpVariance<T> {
+    #[inline]
+    fn add(&mut self, sample: T) {
+        self.avg.n += 1;
+        let delta = sample - self.avg.avg;
+        self.avg.add_inner(delta);
+        if self.avg.n > 1 {
+            let alpha = self.alpha();
+            self.var = (T::one() - alpha) * (self.var + alpha * delta * delta);
+        }
+    }
+
+    #[inline]
+    fn estimate(&self) -> T {
+        self.variance()
+    }
+}
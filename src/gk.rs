@@ -0,0 +1,203 @@
+use alloc::vec::Vec;
+
+use num_traits::float::FloatCore;
+#[cfg(feature = "serde1")] use serde::{Serialize, Deserialize};
+
+use super::Estimate;
+
+/// A retained sample of the Greenwald-Khanna summary.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+struct Tuple<T> {
+    /// Sample.
+    v: T,
+    /// Difference between the minimal rank of this sample and the minimal rank
+    /// of the previous one.
+    g: u64,
+    /// Difference between the maximal and the minimal rank of this sample.
+    delta: u64,
+}
+
+/// Estimate arbitrary quantiles of a sequence of numbers ("population") with
+/// bounded rank error.
+///
+/// The [Greenwald-Khanna algorithm][1] retains a subset of the samples
+/// together with bounds on their ranks. Any p-quantile can be queried after
+/// the samples were added, in contrast to [`Quantile`], where `p` is chosen on
+/// construction. The rank of the estimate differs from `p` times the sample
+/// size by at most `epsilon` times the sample size. The summary retains
+/// `O(log(epsilon n) / epsilon)` samples, and `nan` samples are ignored.
+///
+/// Collecting an iterator into `GkQuantiles` uses `epsilon = 0.01`, and
+/// [`Estimate::estimate`] estimates the median.
+///
+/// [1]: https://doi.org/10.1145/375663.375670
+/// [`Quantile`]: ./struct.Quantile.html
+/// [`Estimate::estimate`]: ./trait.Estimate.html#tymethod.estimate
+///
+///
+/// ## Example
+///
+/// ```
+/// use average::{GkQuantiles, Estimate};
+///
+/// let mut q = GkQuantiles::with_epsilon(0.001);
+/// for i in 1..1001 {
+///     q.add(f64::from(i));
+/// }
+/// // Decide which quantiles to look at after adding the samples.
+/// for &p in &[0.5, 0.9, 0.99] {
+///     assert!((q.quantile(p) - 1000. * p).abs() <= 1.);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+pub struct GkQuantiles<T = f64> {
+    /// Maximal rank error relative to the sample size.
+    epsilon: T,
+    /// Retained samples, sorted.
+    tuples: Vec<Tuple<T>>,
+    /// Sample size.
+    len: u64,
+}
+
+impl GkQuantiles<f64> {
+    /// Create a new summary with `epsilon = 0.01`.
+    ///
+    /// Use `new_generic` for other floating point types than `f64`.
+    #[inline]
+    pub fn new() -> GkQuantiles<f64> {
+        GkQuantiles::new_generic()
+    }
+}
+
+impl<T: FloatCore> GkQuantiles<T> {
+    /// Create a new summary with `epsilon = 0.01`.
+    #[inline]
+    pub fn new_generic() -> GkQuantiles<T> {
+        GkQuantiles::with_epsilon(T::from(0.01).unwrap())
+    }
+
+    /// Create a new summary with the given maximal rank error relative to the
+    /// sample size.
+    ///
+    /// Panics if `epsilon` is not between 0 and 1 (exclusive).
+    #[inline]
+    pub fn with_epsilon(epsilon: T) -> GkQuantiles<T> {
+        assert!(T::zero() < epsilon && epsilon < T::one(),
+            "epsilon must be between 0 and 1");
+        GkQuantiles { epsilon, tuples: Vec::new(), len: 0 }
+    }
+
+    /// Return the maximal rank error relative to the sample size.
+    #[inline]
+    pub fn epsilon(&self) -> T {
+        self.epsilon
+    }
+
+    /// Return the sample size.
+    #[inline]
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Determine whether the sample is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Return the number of retained samples.
+    #[inline]
+    pub fn retained(&self) -> usize {
+        self.tuples.len()
+    }
+
+    /// Return the largest allowed uncertainty of the rank of a retained
+    /// sample, `floor(2 epsilon n)`.
+    #[inline]
+    fn max_delta(&self) -> u64 {
+        let two = T::from(2).unwrap();
+        (two * self.epsilon * T::from(self.len).unwrap()).floor().to_u64().unwrap()
+    }
+
+    /// Merge adjacent tuples as long as the rank uncertainty stays within the
+    /// bound.
+    ///
+    /// The first and the last tuple are kept, so the minimum and the maximum
+    /// are always known exactly.
+    fn compress(&mut self) {
+        let max_delta = self.max_delta();
+        let mut i = self.tuples.len().saturating_sub(2);
+        while i >= 1 {
+            let (current, next) = (self.tuples[i], self.tuples[i + 1]);
+            if current.g + next.g + next.delta <= max_delta {
+                self.tuples[i + 1].g += current.g;
+                self.tuples.remove(i);
+            }
+            i -= 1;
+        }
+    }
+
+    /// Estimate the p-quantile of the population.
+    ///
+    /// Returns 0 for an empty sample. Panics if `p` is not between 0 and 1.
+    pub fn quantile(&self, p: T) -> T {
+        assert!(T::zero() <= p && p <= T::one());
+        if self.is_empty() {
+            return T::zero();
+        }
+        // Find the sample whose rank bounds deviate the least from the
+        // desired rank. This deviation is at most `epsilon n`.
+        let rank = (p * T::from(self.len).unwrap()).ceil().max(T::one());
+        let mut r_min = 0;
+        let mut best = (T::infinity(), self.tuples[0].v);
+        for t in &self.tuples {
+            r_min += t.g;
+            let deviation = (rank - T::from(r_min).unwrap())
+                .max(T::from(r_min + t.delta).unwrap() - rank);
+            if deviation < best.0 {
+                best = (deviation, t.v);
+            }
+        }
+        best.1
+    }
+}
+
+impl<T: FloatCore> core::default::Default for GkQuantiles<T> {
+    fn default() -> GkQuantiles<T> {
+        GkQuantiles::new_generic()
+    }
+}
+
+impl<T: FloatCore> Estimate<T> for GkQuantiles<T> {
+    #[inline]
+    fn add(&mut self, x: T) {
+        if x.is_nan() {
+            return;
+        }
+        let i = self.tuples.partition_point(|t| t.v <= x);
+        // The rank of a new minimum or maximum is known exactly.
+        let delta = if i == 0 || i == self.tuples.len() {
+            0
+        } else {
+            self.max_delta()
+        };
+        self.tuples.insert(i, Tuple { v: x, g: 1, delta });
+        self.len += 1;
+
+        let two = T::from(2).unwrap();
+        let period = (T::one() / (two * self.epsilon)).floor().to_u64().unwrap().max(1);
+        if self.len % period == 0 {
+            self.compress();
+        }
+    }
+
+    #[inline]
+    fn estimate(&self) -> T {
+        self.quantile(T::from(0.5).unwrap())
+    }
+}
+
+impl_from_iterator!(GkQuantiles<T>);
@@ -0,0 +1,312 @@
+//! Goodness-of-fit tests comparing the distributions of two histograms.
+//!
+//! All functions take two histograms with equal ranges and fail with
+//! [`IncompatibleRangesError`] otherwise. They return the test statistic and
+//! the p-value, i.e. the probability of a statistic at least as extreme if
+//! both histograms were sampled from the same distribution. The p-values are
+//! asymptotic and require sufficiently many samples per bin.
+//!
+//! [`IncompatibleRangesError`]: ../struct.IncompatibleRangesError.html
+//!
+//!
+//! ## Example
+//!
+//! ```
+//! use average::{Histogram, define_histogram};
+//! use average::goodness_of_fit::chi_square;
+//!
+//! define_histogram!(hist, 10);
+//! let mut a = hist::Histogram::with_const_width(0., 100.);
+//! let mut b = hist::Histogram::with_const_width(0., 100.);
+//! for i in 0..1000 {
+//!     a.add(f64::from(i % 100)).unwrap();
+//!     b.add(f64::from(i * 7 % 100)).unwrap();
+//! }
+//! let result = chi_square(&a, &b).unwrap();
+//! assert!(result.p_value > 0.05);
+//! ```
+
+use num_traits::Float;
+
+use super::{Histogram, IncompatibleRangesError};
+
+/// The result of a statistical test.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TestResult {
+    /// The test statistic.
+    pub statistic: f64,
+    /// The probability of a statistic at least as extreme under the null
+    /// hypothesis that both histograms follow the same distribution.
+    pub p_value: f64,
+}
+
+/// Check that both histograms have the same ranges and return the total
+/// counts of their bins.
+fn totals<H1, H2>(a: &H1, b: &H2) -> Result<(f64, f64), IncompatibleRangesError>
+    where H1: Histogram, H2: Histogram,
+          for<'a> &'a H1: IntoIterator<Item = ((f64, f64), u64)>,
+          for<'a> &'a H2: IntoIterator<Item = ((f64, f64), u64)>,
+{
+    if a.bins().len() != b.bins().len()
+        || !a.into_iter().zip(b).all(|((ra, _), (rb, _))| ra == rb)
+    {
+        return Err(IncompatibleRangesError);
+    }
+    Ok((a.total() as f64, b.total() as f64))
+}
+
+/// Calculate the chi-square test statistic for the frequencies of the bins,
+/// using the multinomial variances of the frequencies as errors.
+///
+/// The frequencies and their variances are both relative to the total count
+/// of the bins, so outliers are ignored. For a bin with count `c` out of `n`,
+/// the variance of the frequency is `c * (1 - c / n) / n^2`. Bins where both
+/// variances vanish are ignored. Returns a statistic of 0 and
+/// a p-value of 1 if a histogram is empty.
+pub fn chi_square<H1, H2>(a: &H1, b: &H2) -> Result<TestResult, IncompatibleRangesError>
+    where H1: Histogram, H2: Histogram,
+          for<'a> &'a H1: IntoIterator<Item = ((f64, f64), u64)>,
+          for<'a> &'a H2: IntoIterator<Item = ((f64, f64), u64)>,
+{
+    let (n_a, n_b) = totals(a, b)?;
+    if n_a == 0. || n_b == 0. {
+        return Ok(TestResult { statistic: 0., p_value: 1. });
+    }
+    let mut statistic = 0.;
+    let mut bins = 0;
+    for (&c_a, &c_b) in a.bins().iter().zip(b.bins().iter()) {
+        let (f_a, f_b) = (c_a as f64 / n_a, c_b as f64 / n_b);
+        let error = f_a * (1. - f_a) / n_a + f_b * (1. - f_b) / n_b;
+        if error > 0. {
+            let delta = f_a - f_b;
+            statistic += delta * delta / error;
+            bins += 1;
+        }
+    }
+    Ok(TestResult { statistic, p_value: chi_square_survival(statistic, bins) })
+}
+
+/// Calculate the G-test statistic for the homogeneity of the bin counts, i.e.
+/// the likelihood ratio test of the contingency table.
+///
+/// Bins that are empty in both histograms are ignored. Returns a statistic of
+/// 0 and a p-value of 1 if a histogram is empty.
+pub fn g_test<H1, H2>(a: &H1, b: &H2) -> Result<TestResult, IncompatibleRangesError>
+    where H1: Histogram, H2: Histogram,
+          for<'a> &'a H1: IntoIterator<Item = ((f64, f64), u64)>,
+          for<'a> &'a H2: IntoIterator<Item = ((f64, f64), u64)>,
+{
+    let (n_a, n_b) = totals(a, b)?;
+    if n_a == 0. || n_b == 0. {
+        return Ok(TestResult { statistic: 0., p_value: 1. });
+    }
+    let n = n_a + n_b;
+    let mut statistic = 0.;
+    let mut bins = 0;
+    for (&c_a, &c_b) in a.bins().iter().zip(b.bins().iter()) {
+        let column = (c_a + c_b) as f64;
+        if column == 0. {
+            continue;
+        }
+        bins += 1;
+        for &(observed, row) in &[(c_a as f64, n_a), (c_b as f64, n_b)] {
+            if observed > 0. {
+                statistic += 2. * observed * (observed * n / (row * column)).ln();
+            }
+        }
+    }
+    Ok(TestResult { statistic, p_value: chi_square_survival(statistic, bins) })
+}
+
+/// Calculate the Kolmogorov-Smirnov statistic, i.e. the largest difference of
+/// the cumulative frequencies at the bin edges.
+///
+/// Because the samples are binned, the p-value is conservative. Returns a
+/// statistic of 0 and a p-value of 1 if a histogram is empty.
+pub fn kolmogorov_smirnov<H1, H2>(a: &H1, b: &H2) -> Result<TestResult, IncompatibleRangesError>
+    where H1: Histogram, H2: Histogram,
+          for<'a> &'a H1: IntoIterator<Item = ((f64, f64), u64)>,
+          for<'a> &'a H2: IntoIterator<Item = ((f64, f64), u64)>,
+{
+    let (n_a, n_b) = totals(a, b)?;
+    if n_a == 0. || n_b == 0. {
+        return Ok(TestResult { statistic: 0., p_value: 1. });
+    }
+    let (mut cdf_a, mut cdf_b) = (0., 0.);
+    let mut statistic: f64 = 0.;
+    for (&c_a, &c_b) in a.bins().iter().zip(b.bins().iter()) {
+        cdf_a += c_a as f64 / n_a;
+        cdf_b += c_b as f64 / n_b;
+        statistic = statistic.max((cdf_a - cdf_b).abs());
+    }
+    let n = (n_a * n_b / (n_a + n_b)).sqrt();
+    let p_value = kolmogorov_survival((n + 0.12 + 0.11 / n) * statistic);
+    Ok(TestResult { statistic, p_value })
+}
+
+/// Calculate the Kullback-Leibler divergence of the bin frequencies of `a`
+/// from the ones of the reference `b`.
+///
+/// The p-value is the one of the G-test assuming that `b` is the exact
+/// distribution. The divergence is infinite if `a` has samples in a bin
+/// where `b` has none. Returns a divergence of 0 and a p-value of 1 if a
+/// histogram is empty.
+pub fn kullback_leibler<H1, H2>(a: &H1, b: &H2) -> Result<TestResult, IncompatibleRangesError>
+    where H1: Histogram, H2: Histogram,
+          for<'a> &'a H1: IntoIterator<Item = ((f64, f64), u64)>,
+          for<'a> &'a H2: IntoIterator<Item = ((f64, f64), u64)>,
+{
+    let (n_a, n_b) = totals(a, b)?;
+    if n_a == 0. || n_b == 0. {
+        return Ok(TestResult { statistic: 0., p_value: 1. });
+    }
+    let mut statistic = 0.;
+    let mut bins = 0;
+    for (&c_a, &c_b) in a.bins().iter().zip(b.bins().iter()) {
+        let (p, q) = (c_a as f64 / n_a, c_b as f64 / n_b);
+        if q > 0. {
+            bins += 1;
+            if p > 0. {
+                statistic += p * (p / q).ln();
+            }
+        } else if p > 0. {
+            return Ok(TestResult { statistic: f64::infinity(), p_value: 0. });
+        }
+    }
+    let p_value = chi_square_survival(2. * n_a * statistic, bins);
+    Ok(TestResult { statistic, p_value })
+}
+
+/// Calculate the Jensen-Shannon divergence of the bin frequencies, i.e. the
+/// mean Kullback-Leibler divergence from their average.
+///
+/// The divergence is between 0 and `ln 2`. The p-value is the one of the
+/// G-test. Returns a divergence of 0 and a p-value of 1 if a histogram is
+/// empty.
+pub fn jensen_shannon<H1, H2>(a: &H1, b: &H2) -> Result<TestResult, IncompatibleRangesError>
+    where H1: Histogram, H2: Histogram,
+          for<'a> &'a H1: IntoIterator<Item = ((f64, f64), u64)>,
+          for<'a> &'a H2: IntoIterator<Item = ((f64, f64), u64)>,
+{
+    let (n_a, n_b) = totals(a, b)?;
+    if n_a == 0. || n_b == 0. {
+        return Ok(TestResult { statistic: 0., p_value: 1. });
+    }
+    let mut statistic = 0.;
+    for (&c_a, &c_b) in a.bins().iter().zip(b.bins().iter()) {
+        let (p, q) = (c_a as f64 / n_a, c_b as f64 / n_b);
+        let m = 0.5 * (p + q);
+        for &x in &[p, q] {
+            if x > 0. {
+                statistic += 0.5 * x * (x / m).ln();
+            }
+        }
+    }
+    let p_value = g_test(a, b)?.p_value;
+    Ok(TestResult { statistic, p_value })
+}
+
+/// Calculate the probability that a chi-square distributed variable with
+/// `bins - 1` degrees of freedom is at least `x`.
+fn chi_square_survival(x: f64, bins: usize) -> f64 {
+    if bins < 2 {
+        return 1.;
+    }
+    gamma_q(0.5 * (bins - 1) as f64, 0.5 * x)
+}
+
+/// Calculate the regularized upper incomplete gamma function `Q(a, x)`.
+fn gamma_q(a: f64, x: f64) -> f64 {
+    if x <= 0. {
+        return 1.;
+    }
+    if !x.is_finite() {
+        return 0.;
+    }
+    const EPSILON: f64 = 1e-15;
+    const MAX_ITERATIONS: usize = 1000;
+    let prefactor = (-x + a * x.ln() - ln_gamma(a)).exp();
+    if x < a + 1. {
+        // Use the series of the lower incomplete gamma function.
+        let mut term = 1. / a;
+        let mut sum = term;
+        for n in 1..MAX_ITERATIONS {
+            term *= x / (a + n as f64);
+            sum += term;
+            if term.abs() < sum.abs() * EPSILON {
+                break;
+            }
+        }
+        (1. - sum * prefactor).max(0.)
+    } else {
+        // Use the continued fraction, evaluated with the modified Lentz method.
+        let tiny = 1e-300;
+        let mut b = x + 1. - a;
+        let mut c = 1. / tiny;
+        let mut d = 1. / b;
+        let mut h = d;
+        for n in 1..MAX_ITERATIONS {
+            let an = -(n as f64) * (n as f64 - a);
+            b += 2.;
+            d = an * d + b;
+            if d.abs() < tiny {
+                d = tiny;
+            }
+            c = b + an / c;
+            if c.abs() < tiny {
+                c = tiny;
+            }
+            d = 1. / d;
+            let delta = d * c;
+            h *= delta;
+            if (delta - 1.).abs() < EPSILON {
+                break;
+            }
+        }
+        prefactor * h
+    }
+}
+
+/// Calculate the logarithm of the gamma function for positive `x`, using the
+/// Lanczos approximation.
+pub(crate) fn ln_gamma(x: f64) -> f64 {
+    const G: f64 = 7.;
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    let x = x - 1.;
+    let mut sum = COEFFICIENTS[0];
+    for (i, &c) in COEFFICIENTS.iter().enumerate().skip(1) {
+        sum += c / (x + i as f64);
+    }
+    let t = x + G + 0.5;
+    0.5 * (2. * core::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + sum.ln()
+}
+
+/// Calculate the probability that a variable following the Kolmogorov
+/// distribution is at least `x`.
+fn kolmogorov_survival(x: f64) -> f64 {
+    if x < 0.2 {
+        return 1.;
+    }
+    let mut sum = 0.;
+    let mut sign = 1.;
+    for k in 1..101 {
+        let k = f64::from(k);
+        let term = (-2. * k * k * x * x).exp();
+        sum += sign * term;
+        if term < 1e-16 {
+            break;
+        }
+        sign = -sign;
+    }
+    (2. * sum).clamp(0., 1.)
+}
@@ -0,0 +1,339 @@
+use alloc::vec;
+use alloc::vec::Vec;
+#[cfg(feature = "serde1")] use core::convert::TryFrom;
+
+#[cfg(feature = "serde1")] use serde::{Serialize, Deserialize};
+
+use super::{Histogram, Merge, SampleOutOfRangeError};
+
+/// A histogram with log-linear bins derived from a value range and a number of
+/// significant digits, in the style of the [HdrHistogram][1].
+///
+/// The bins cover all values from 0 to at least `highest`. Values below
+/// `lowest * 2^m`, where `2^m` is the smallest power of two of at least
+/// `2 * 10^significant_digits`, are counted in bins of width `lowest`. Above,
+/// the bin width doubles every time the values double. For values of at least
+/// `lowest * 10^significant_digits`, the width of the bins relative to their
+/// lower edge never exceeds `10^-significant_digits`. This is suitable for
+/// recording latencies without choosing the bin edges by hand.
+///
+/// [1]: http://hdrhistogram.org/
+///
+///
+/// ## Example
+///
+/// ```
+/// use average::HdrHistogram;
+///
+/// // Record latencies between 1 µs and 1 h in seconds with three significant
+/// // digits.
+/// let mut h = HdrHistogram::new(1e-6, 3600., 3);
+/// for i in 1..1001 {
+///     h.add(f64::from(i) * 1e-3).unwrap();
+/// }
+/// let p99 = h.value_at_quantile(0.99);
+/// assert!((p99 - 0.99).abs() <= 1e-3 * 0.99);
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde1", serde(try_from = "HdrHistogramData"))]
+pub struct HdrHistogram {
+    /// Width of the narrowest bins.
+    lowest: f64,
+    /// Highest value that can be recorded.
+    highest: f64,
+    /// Number of significant digits.
+    significant_digits: u8,
+    /// Logarithm to base 2 of the number of bins for values below
+    /// `lowest * 2^m`.
+    sub_bucket_magnitude: u32,
+    /// The bins of the histogram.
+    bin: Vec<u64>,
+}
+
+/// The serialized fields of a `HdrHistogram`, which are validated before
+/// deserializing the histogram.
+#[cfg(feature = "serde1")]
+#[derive(Deserialize)]
+struct HdrHistogramData {
+    lowest: f64,
+    highest: f64,
+    significant_digits: u8,
+    sub_bucket_magnitude: u32,
+    bin: Vec<u64>,
+}
+
+#[cfg(feature = "serde1")]
+impl TryFrom<HdrHistogramData> for HdrHistogram {
+    type Error = &'static str;
+
+    fn try_from(data: HdrHistogramData) -> Result<HdrHistogram, &'static str> {
+        // The derived fields must match the ones computed from the parameters.
+        let (sub_bucket_magnitude, len) =
+            layout(data.lowest, data.highest, data.significant_digits)?;
+        if data.sub_bucket_magnitude != sub_bucket_magnitude || data.bin.len() != len {
+            return Err("The bins do not match the parameters of the histogram");
+        }
+        Ok(HdrHistogram {
+            lowest: data.lowest,
+            highest: data.highest,
+            significant_digits: data.significant_digits,
+            sub_bucket_magnitude,
+            bin: data.bin,
+        })
+    }
+}
+
+/// Calculate the logarithm to base 2 of the number of bins per bucket and the
+/// total number of bins for the given parameters.
+///
+/// Fails if the parameters are invalid.
+fn layout(lowest: f64, highest: f64, significant_digits: u8)
+    -> Result<(u32, usize), &'static str>
+{
+    if !(lowest > 0. && lowest.is_finite()) {
+        return Err("The lowest value must be positive and finite");
+    }
+    if !(highest >= 2. * lowest && highest.is_finite()) {
+        return Err("The highest value must be finite and at least twice the lowest");
+    }
+    if !(1..=5).contains(&significant_digits) {
+        return Err("The number of significant digits must be between 1 and 5");
+    }
+
+    // Values below this can be recorded with a resolution of one unit.
+    let single_unit_resolution = 2 * 10u64.pow(u32::from(significant_digits));
+    let sub_bucket_magnitude = 64 - (single_unit_resolution - 1).leading_zeros();
+    let sub_bucket_count = 1u64 << sub_bucket_magnitude;
+
+    // Every bucket doubles the range.
+    let mut buckets = 1;
+    let mut smallest_untrackable = sub_bucket_count as f64 * lowest;
+    while smallest_untrackable <= highest {
+        smallest_untrackable *= 2.;
+        buckets += 1;
+    }
+    // The upper range limit in units of `lowest` must fit into a `u64`.
+    if sub_bucket_magnitude + buckets > 64 {
+        return Err("The highest value is too large relative to the lowest");
+    }
+    let len = (buckets as usize + 1) * (sub_bucket_count as usize / 2);
+    Ok((sub_bucket_magnitude, len))
+}
+
+impl HdrHistogram {
+    /// Construct a histogram for values from 0 to `highest` in units of
+    /// `lowest`, with the given number of significant digits.
+    ///
+    /// Panics if `lowest` is not positive, if `highest` is smaller than
+    /// `2 * lowest` or not finite, if `highest / lowest` is too large to count
+    /// the values in units of `lowest`, or if `significant_digits` is not
+    /// between 1 and 5.
+    pub fn new(lowest: f64, highest: f64, significant_digits: u8) -> HdrHistogram {
+        let (sub_bucket_magnitude, len) = match layout(lowest, highest, significant_digits) {
+            Ok(layout) => layout,
+            Err(e) => panic!("{}", e),
+        };
+        HdrHistogram {
+            lowest,
+            highest,
+            significant_digits,
+            sub_bucket_magnitude,
+            bin: vec![0; len],
+        }
+    }
+
+    /// Return the width of the narrowest bins.
+    #[inline]
+    pub fn lowest(&self) -> f64 {
+        self.lowest
+    }
+
+    /// Return the highest value the histogram was constructed for.
+    #[inline]
+    pub fn highest(&self) -> f64 {
+        self.highest
+    }
+
+    /// Return the number of significant digits.
+    #[inline]
+    pub fn significant_digits(&self) -> u8 {
+        self.significant_digits
+    }
+
+    /// Return the number of bins for values below `lowest * 2^m`, where `2^m`
+    /// is the number of bins in each of the other buckets.
+    #[inline]
+    fn half_count(&self) -> u64 {
+        1 << (self.sub_bucket_magnitude - 1)
+    }
+
+    /// Return the lower edge of the bin with the given index in units of
+    /// `lowest`.
+    #[inline]
+    fn lower_edge(&self, index: usize) -> u64 {
+        let half = self.half_count();
+        let index = index as u64;
+        let bucket = if index < 2 * half { 0 } else { index / half - 1 };
+        (index - bucket * half) << bucket
+    }
+
+    /// Return the lower range limit.
+    #[inline]
+    pub fn range_min(&self) -> f64 {
+        0.
+    }
+
+    /// Return the upper range limit.
+    ///
+    /// This is at least the highest value the histogram was constructed for.
+    #[inline]
+    pub fn range_max(&self) -> f64 {
+        self.lower_edge(self.bin.len()) as f64 * self.lowest
+    }
+
+    /// Find the index of the bin corresponding to the given sample.
+    ///
+    /// Fails if the sample is out of range of the histogram.
+    #[inline]
+    pub fn find(&self, x: f64) -> Result<usize, SampleOutOfRangeError> {
+        if !(x >= 0. && x < self.range_max()) {
+            return Err(SampleOutOfRangeError);
+        }
+        let units = (x / self.lowest) as u64;
+        let mask = (1 << self.sub_bucket_magnitude) - 1;
+        let bucket = (64 - (units | mask).leading_zeros()) - self.sub_bucket_magnitude;
+        let sub_bucket = units >> bucket;
+        Ok((u64::from(bucket) * self.half_count() + sub_bucket) as usize)
+    }
+
+    /// Add a sample to the histogram.
+    ///
+    /// Fails if the sample is out of range of the histogram.
+    #[inline]
+    pub fn add(&mut self, x: f64) -> Result<(), SampleOutOfRangeError> {
+        let i = self.find(x)?;
+        self.bin[i] += 1;
+        Ok(())
+    }
+
+    /// Return an iterator over the bins and corresponding ranges:
+    /// `((lower, upper), count)`
+    #[inline]
+    pub fn iter(&self) -> IterHdrHistogram<'_> {
+        self.into_iter()
+    }
+
+    /// Reset all bins to zero.
+    #[inline]
+    pub fn reset(&mut self) {
+        for b in &mut self.bin {
+            *b = 0;
+        }
+    }
+
+    /// Return the number of samples.
+    #[inline]
+    pub fn len(&self) -> u64 {
+        self.bin.iter().sum()
+    }
+
+    /// Determine whether the histogram is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.bin.iter().all(|&b| b == 0)
+    }
+
+    /// Estimate the p-quantile of the samples.
+    ///
+    /// This is the center of the first bin where the cumulative count reaches
+    /// `p` times the number of samples. The error is at most half the bin
+    /// width, which is the larger one of `lowest / 2` and a fraction
+    /// `10^-significant_digits / 2` of the value. Returns 0 for an empty
+    /// histogram. Panics if `p` is not between 0 and 1.
+    pub fn value_at_quantile(&self, p: f64) -> f64 {
+        assert!((0. ..=1.).contains(&p));
+        let len = self.len();
+        if len == 0 {
+            return 0.;
+        }
+        let target = ((p * len as f64).ceil() as u64).max(1);
+        let mut cumulative = 0;
+        for ((lower, upper), count) in self.iter() {
+            cumulative += count;
+            if cumulative >= target {
+                return 0.5 * (lower + upper);
+            }
+        }
+        unreachable!()
+    }
+
+    /// Return the number of samples in the bins from the one containing `low`
+    /// to the one containing `high`, inclusive.
+    ///
+    /// Values out of range are clamped to the range of the histogram.
+    pub fn count_between(&self, low: f64, high: f64) -> u64 {
+        let clamp = |x: f64| -> usize {
+            match self.find(x) {
+                Ok(i) => i,
+                Err(_) if x >= self.range_max() => self.bin.len() - 1,
+                Err(_) => 0,
+            }
+        };
+        let (low, high) = (clamp(low), clamp(high));
+        if low > high {
+            return 0;
+        }
+        self.bin[low..=high].iter().sum()
+    }
+}
+
+/// Iterate over all `(range, count)` pairs in the histogram.
+#[derive(Debug, Clone)]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+pub struct IterHdrHistogram<'a> {
+    histogram: &'a HdrHistogram,
+    index: usize,
+}
+
+impl<'a> Iterator for IterHdrHistogram<'a> {
+    type Item = ((f64, f64), u64);
+    fn next(&mut self) -> Option<((f64, f64), u64)> {
+        let h = self.histogram;
+        let &count = h.bin.get(self.index)?;
+        let lower = h.lower_edge(self.index) as f64 * h.lowest;
+        let upper = h.lower_edge(self.index + 1) as f64 * h.lowest;
+        self.index += 1;
+        Some(((lower, upper), count))
+    }
+}
+
+impl<'a> IntoIterator for &'a HdrHistogram {
+    type Item = ((f64, f64), u64);
+    type IntoIter = IterHdrHistogram<'a>;
+    fn into_iter(self) -> IterHdrHistogram<'a> {
+        IterHdrHistogram { histogram: self, index: 0 }
+    }
+}
+
+impl Histogram for HdrHistogram {
+    #[inline]
+    fn bins(&self) -> &[u64] {
+        &self.bin[..]
+    }
+}
+
+impl Merge for HdrHistogram {
+    /// Merge another histogram into this one.
+    ///
+    /// Panics if the histograms were constructed with different parameters.
+    fn merge(&mut self, other: &Self) {
+        assert!(self.lowest == other.lowest && self.highest == other.highest
+            && self.significant_digits == other.significant_digits,
+            "Both histograms must have the same parameters");
+        for (a, b) in self.bin.iter_mut().zip(other.bin.iter()) {
+            *a += *b;
+        }
+    }
+}
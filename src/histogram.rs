@@ -1,3 +1,12 @@
+use core::fmt;
+use core::ops::MulAssign;
+
+#[cfg(any(feature = "std", feature = "libm"))] use num_traits::Float;
+use num_traits::float::FloatCore;
+#[cfg(feature = "serde1")] use serde::{Serialize, Deserialize};
+
+use crate::Merge;
+
 /// Invalid ranges were specified for constructing the histogram.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum InvalidRangeError {
@@ -10,10 +19,443 @@ pub enum InvalidRangeError {
     NaN,
 }
 
+impl fmt::Display for InvalidRangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            InvalidRangeError::NotEnoughRanges =>
+                "the number of ranges does not match the number of bins",
+            InvalidRangeError::NotSorted => "the ranges are not sorted",
+            InvalidRangeError::NaN => "a range is nan",
+        })
+    }
+}
+
 /// A sample is out of range of the histogram.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct SampleOutOfRangeError;
 
+/// The ranges of two histograms are incompatible for merging.
+///
+/// This happens if no histogram's ranges are a refinement of the other's, if
+/// only one of the histograms counts outliers, or if both count outliers but
+/// have different range limits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IncompatibleRangesError;
+
+/// Counts of the samples that did not fall into any bin of a histogram.
+///
+/// Histograms only keep these counts if they were constructed with
+/// `count_outliers()`. In that case, adding a sample never fails.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+pub struct OutlierCounts {
+    /// Number of samples below the lower range limit.
+    pub(crate) underflow: u64,
+    /// Number of samples at or above the upper range limit.
+    pub(crate) overflow: u64,
+    /// Number of `nan` samples.
+    pub(crate) nan: u64,
+}
+
+impl OutlierCounts {
+    /// Return the number of samples below the lower range limit.
+    #[inline]
+    pub fn underflow(&self) -> u64 {
+        self.underflow
+    }
+
+    /// Return the number of samples at or above the upper range limit.
+    #[inline]
+    pub fn overflow(&self) -> u64 {
+        self.overflow
+    }
+
+    /// Return the number of `nan` samples.
+    #[inline]
+    pub fn nan(&self) -> u64 {
+        self.nan
+    }
+
+    /// Return the total number of samples that did not fall into any bin.
+    #[inline]
+    pub fn total(&self) -> u64 {
+        self.underflow + self.overflow + self.nan
+    }
+
+    /// Count a sample that is out of range of a histogram with the given
+    /// lower range limit.
+    #[doc(hidden)]
+    #[inline]
+    pub fn add(&mut self, x: f64, range_min: f64) {
+        if x.is_nan() {
+            self.nan += 1;
+        } else if x < range_min {
+            self.underflow += 1;
+        } else {
+            self.overflow += 1;
+        }
+    }
+
+    /// Reset all counts to zero.
+    #[doc(hidden)]
+    #[inline]
+    pub fn reset(&mut self) {
+        *self = OutlierCounts::default();
+    }
+}
+
+impl Merge for OutlierCounts {
+    #[inline]
+    fn merge(&mut self, other: &Self) {
+        self.underflow += other.underflow;
+        self.overflow += other.overflow;
+        self.nan += other.nan;
+    }
+}
+
+impl MulAssign<u64> for OutlierCounts {
+    #[inline]
+    fn mul_assign(&mut self, other: u64) {
+        self.underflow *= other;
+        self.overflow *= other;
+        self.nan *= other;
+    }
+}
+
+/// Merge the outlier counts of another histogram.
+///
+/// Panics if only one of the histograms counts outliers.
+#[doc(hidden)]
+#[inline]
+pub fn merge_outliers(outliers: &mut Option<OutlierCounts>, other: &Option<OutlierCounts>) {
+    try_merge_outliers(outliers, other)
+        .expect("Either both or none of the histograms must count outliers");
+}
+
+/// Check whether the outlier counts of two histograms can be merged.
+///
+/// Fails if only one of the histograms counts outliers, or if both do but the
+/// histograms have different range limits. In the latter case, some outliers
+/// of one histogram would fall into the bins of the other.
+#[doc(hidden)]
+#[inline]
+pub fn check_outlier_ranges(outliers: &Option<OutlierCounts>, range: &[f64],
+    other_outliers: &Option<OutlierCounts>, other_range: &[f64])
+    -> Result<(), IncompatibleRangesError>
+{
+    match (outliers, other_outliers) {
+        (Some(_), Some(_)) => {
+            if range.first() != other_range.first() || range.last() != other_range.last() {
+                return Err(IncompatibleRangesError);
+            }
+        },
+        (None, None) => {},
+        _ => return Err(IncompatibleRangesError),
+    }
+    Ok(())
+}
+
+/// Merge the outlier counts of another histogram.
+///
+/// Fails if only one of the histograms counts outliers.
+#[doc(hidden)]
+#[inline]
+pub fn try_merge_outliers(outliers: &mut Option<OutlierCounts>, other: &Option<OutlierCounts>)
+    -> Result<(), IncompatibleRangesError>
+{
+    match (outliers, other) {
+        (Some(a), Some(b)) => a.merge(b),
+        (None, None) => {},
+        _ => return Err(IncompatibleRangesError),
+    }
+    Ok(())
+}
+
+/// Set the ranges to bins of constant width between `start` and `end`.
+#[doc(hidden)]
+#[inline]
+pub fn fill_const_width(range: &mut [f64], start: f64, end: f64) {
+    let step = (end - start) / ((range.len() - 1) as f64);
+    for (i, r) in range.iter_mut().enumerate() {
+        *r = start + step * (i as f64);
+    }
+}
+
+/// Set the ranges from an iterator, returning how many were set.
+///
+/// Fails if the iterator is not sorted or contains `nan`. Does not check
+/// whether there are enough ranges.
+#[doc(hidden)]
+#[inline]
+pub fn fill_ranges<T>(range: &mut [f64], ranges: T) -> Result<usize, InvalidRangeError>
+    where T: IntoIterator<Item = f64>
+{
+    let mut len = 0;
+    for (i, r) in ranges.into_iter().enumerate() {
+        if i >= range.len() {
+            break;
+        }
+        if r.is_nan() {
+            return Err(InvalidRangeError::NaN);
+        }
+        if i > 0 && range[i - 1] > r {
+            return Err(InvalidRangeError::NotSorted);
+        }
+        range[i] = r;
+        len = i + 1;
+    }
+    Ok(len)
+}
+
+/// How the ranges of a histogram are spaced.
+///
+/// This allows finding the bin of a sample without a binary search.
+#[doc(hidden)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Spacing {
+    /// The ranges are arbitrary.
+    Arbitrary,
+    /// The ranges have a constant width.
+    Linear,
+    /// The ratio of neighboring ranges is constant.
+    Logarithmic,
+}
+
+impl Spacing {
+    /// Return `Linear` if constant-width ranges from `start` to `end` can be
+    /// found analytically, `Arbitrary` otherwise.
+    #[inline]
+    pub fn linear(start: f64, end: f64) -> Spacing {
+        if start < end && start.is_finite() && end.is_finite() {
+            Spacing::Linear
+        } else {
+            Spacing::Arbitrary
+        }
+    }
+}
+
+impl Default for Spacing {
+    #[inline]
+    fn default() -> Spacing {
+        Spacing::Arbitrary
+    }
+}
+
+/// Set the ranges to bins starting at `start`, where the upper edge of each
+/// bin is `ratio` times the lower edge.
+///
+/// Panics if `start` is not positive and finite or if `ratio` is not finite
+/// and larger than 1.
+#[doc(hidden)]
+#[inline]
+pub fn fill_geometric(range: &mut [f64], start: f64, ratio: f64) {
+    assert!(start > 0. && start.is_finite(),
+        "The lower range limit must be positive and finite");
+    assert!(ratio > 1. && ratio.is_finite(),
+        "The ratio must be finite and larger than 1");
+    for (i, r) in range.iter_mut().enumerate() {
+        *r = start * FloatCore::powi(ratio, i as i32);
+    }
+}
+
+/// Set the ranges to bins of constant width on a logarithmic scale between
+/// `start` and `end`.
+///
+/// Panics unless `0 < start < end` and both are finite.
+#[cfg(any(feature = "std", feature = "libm"))]
+#[doc(hidden)]
+#[inline]
+pub fn fill_log_width(range: &mut [f64], start: f64, end: f64) {
+    assert!(start > 0. && start < end && end.is_finite(),
+        "The range limits must be finite and satisfy 0 < start < end");
+    let ratio = Float::powf(end / start, 1. / ((range.len() - 1) as f64));
+    fill_geometric(range, start, ratio);
+    // Avoid rounding errors at the upper range limit.
+    range[range.len() - 1] = end;
+}
+
+/// Find the index of the bin corresponding to the given sample.
+///
+/// If the spacing is not arbitrary, the index is calculated directly instead
+/// of using a binary search. Fails if the sample is out of range of the
+/// histogram or `nan`.
+#[doc(hidden)]
+#[inline]
+pub fn find(range: &[f64], spacing: Spacing, x: f64) -> Result<usize, SampleOutOfRangeError> {
+    let len = range.len() - 1;
+    let (start, end) = (range[0], range[len]);
+    let estimate = match spacing {
+        Spacing::Arbitrary => return binary_search(range, x),
+        Spacing::Linear => (x - start) / (end - start),
+        #[cfg(any(feature = "std", feature = "libm"))]
+        Spacing::Logarithmic => Float::ln(x / start) / Float::ln(end / start),
+        #[cfg(not(any(feature = "std", feature = "libm")))]
+        Spacing::Logarithmic => return binary_search(range, x),
+    };
+    if !(x >= start && x < end) {
+        return Err(SampleOutOfRangeError);
+    }
+    // Correct the estimate for rounding errors. This terminates, because the
+    // sample is within the range.
+    let mut i = ((estimate * len as f64) as usize).min(len - 1);
+    while x < range[i] {
+        i -= 1;
+    }
+    while x >= range[i + 1] {
+        i += 1;
+    }
+    Ok(i)
+}
+
+/// Find the index of the bin corresponding to the given sample using a binary
+/// search.
+///
+/// Fails if the sample is out of range of the histogram or `nan`.
+#[inline]
+fn binary_search(range: &[f64], x: f64) -> Result<usize, SampleOutOfRangeError> {
+    if x.is_nan() {
+        return Err(SampleOutOfRangeError);
+    }
+    let len = range.len() - 1;
+    // The ranges were validated at construction, so we can safely unwrap.
+    match range.binary_search_by(|p| p.partial_cmp(&x).unwrap()) {
+        Ok(i) if i < len => {
+            Ok(i)
+        },
+        Err(i) if i > 0 && i < len + 1 => {
+            Ok(i - 1)
+        },
+        _ => {
+            Err(SampleOutOfRangeError)
+        },
+    }
+}
+
+/// Add the bins of another histogram with the same ranges.
+///
+/// Panics if the ranges are different.
+#[doc(hidden)]
+#[inline]
+pub fn add_bins(range: &[f64], bin: &mut [u64], other_range: &[f64], other_bin: &[u64]) {
+    assert_eq!(bin.len(), other_bin.len(),
+        "Both histograms must have the same number of bins");
+    for (a, b) in range.iter().zip(other_range.iter()) {
+        assert_eq!(a, b, "Both histograms must have the same ranges");
+    }
+    for (a, b) in bin.iter_mut().zip(other_bin.iter()) {
+        *a += *b;
+    }
+}
+
+/// Find the bin of `range` containing the bin `[a, b]`, starting the search at
+/// the bin `start`.
+#[inline]
+fn containing_bin(range: &[f64], start: usize, a: f64, b: f64) -> Option<usize> {
+    let mut j = start;
+    while j + 1 < range.len() {
+        if range[j] <= a && b <= range[j + 1] {
+            return Some(j);
+        }
+        if range[j + 1] > a {
+            return None;
+        }
+        j += 1;
+    }
+    None
+}
+
+/// Add the bins of another histogram whose ranges are a refinement of these
+/// ranges, i.e. every other bin lies within one of these bins.
+///
+/// Fails without modifying the bins if the ranges are not a refinement.
+#[doc(hidden)]
+pub fn add_refined_bins(range: &[f64], bin: &mut [u64], other_range: &[f64], other_bin: &[u64])
+    -> Result<(), IncompatibleRangesError>
+{
+    // Check all bins before adding any of them.
+    let mut j = 0;
+    for i in 0..other_bin.len() {
+        j = containing_bin(range, j, other_range[i], other_range[i + 1])
+            .ok_or(IncompatibleRangesError)?;
+    }
+    let mut j = 0;
+    for (i, &count) in other_bin.iter().enumerate() {
+        j = containing_bin(range, j, other_range[i], other_range[i + 1]).unwrap();
+        bin[j] += count;
+    }
+    Ok(())
+}
+
+/// Merge groups of adjacent bins, such that there are `len` bins of the same
+/// number of original bins left.
+///
+/// Panics if `len` is zero or the number of bins is not a multiple of `len`.
+#[doc(hidden)]
+#[inline]
+pub fn rebin(range: &[f64], bin: &[u64], new_range: &mut [f64], new_bin: &mut [u64]) {
+    let len = new_bin.len();
+    assert!(len > 0 && bin.len() % len == 0,
+        "The number of bins must be a multiple of the new number of bins");
+    let factor = bin.len() / len;
+    for (r, &x) in new_range.iter_mut().zip(range.iter().step_by(factor)) {
+        *r = x;
+    }
+    for (b, group) in new_bin.iter_mut().zip(bin.chunks(factor)) {
+        *b = group.iter().sum();
+    }
+}
+
+/// Iterate over all `(range, count)` pairs in a histogram.
+#[derive(Debug, Clone)]
+pub struct IterHistogram<'a> {
+    underflow: Option<((f64, f64), u64)>,
+    remaining_bin: &'a [u64],
+    remaining_range: &'a [f64],
+    overflow: Option<((f64, f64), u64)>,
+}
+
+impl<'a> IterHistogram<'a> {
+    /// Iterate over the given bins and their ranges.
+    #[doc(hidden)]
+    #[inline]
+    pub fn new(bin: &'a [u64], range: &'a [f64]) -> IterHistogram<'a> {
+        IterHistogram { underflow: None, remaining_bin: bin, remaining_range: range, overflow: None }
+    }
+
+    /// Iterate over the given bins and their ranges, preceded by the underflow
+    /// and followed by the overflow, if counted.
+    #[doc(hidden)]
+    #[inline]
+    pub fn with_outliers(bin: &'a [u64], range: &'a [f64], outliers: Option<OutlierCounts>)
+        -> IterHistogram<'a>
+    {
+        let inf = f64::INFINITY;
+        let (min, max) = (range[0], range[range.len() - 1]);
+        IterHistogram {
+            underflow: outliers.map(|o| ((-inf, min), o.underflow)),
+            overflow: outliers.map(|o| ((max, inf), o.overflow)),
+            ..IterHistogram::new(bin, range)
+        }
+    }
+}
+
+impl<'a> Iterator for IterHistogram<'a> {
+    type Item = ((f64, f64), u64);
+    fn next(&mut self) -> Option<((f64, f64), u64)> {
+        if let Some(underflow) = self.underflow.take() {
+            return Some(underflow);
+        }
+        if let Some((&bin, rest)) = self.remaining_bin.split_first() {
+            let left = self.remaining_range[0];
+            let right = self.remaining_range[1];
+            self.remaining_bin = rest;
+            self.remaining_range = &self.remaining_range[1..];
+            return Some(((left, right), bin));
+        }
+        self.overflow.take()
+    }
+}
+
 #[doc(hidden)]
 #[macro_export]
 macro_rules! define_histogram_common {
@@ -30,6 +472,8 @@ macro_rules! define_histogram_common {
                 self.range[..].fmt(formatter)?;
                 formatter.write_str(", bins: ")?;
                 self.bin[..].fmt(formatter)?;
+                formatter.write_str(", outliers: ")?;
+                self.outliers.fmt(formatter)?;
                 formatter.write_str(" }}")
             }
         }
@@ -38,15 +482,34 @@ macro_rules! define_histogram_common {
             /// Construct a histogram with constant bin width.
             #[inline]
             pub fn with_const_width(start: f64, end: f64) -> Self {
-                let step = (end - start) / (LEN as f64);
                 let mut range = [0.; LEN + 1];
-                for (i, r) in range.iter_mut().enumerate() {
-                    *r = start + step * (i as f64);
+                $crate::histogram::fill_const_width(&mut range, start, end);
+                Self {
+                    range,
+                    bin: [0; LEN],
+                    outliers: None,
+                    spacing: $crate::histogram::Spacing::linear(start, end),
                 }
+            }
 
+            $crate::define_histogram_log_width!();
+
+            $crate::define_histogram_dyn!();
+
+            /// Construct a histogram with bins starting at `start`, where the
+            /// upper edge of each bin is `ratio` times its lower edge.
+            ///
+            /// Panics if `start` is not positive and finite or if `ratio` is
+            /// not finite and larger than 1.
+            #[inline]
+            pub fn with_geometric_ratio(start: f64, ratio: f64) -> Self {
+                let mut range = [0.; LEN + 1];
+                $crate::histogram::fill_geometric(&mut range, start, ratio);
                 Self {
                     range,
                     bin: [0; LEN],
+                    outliers: None,
+                    spacing: $crate::histogram::Spacing::Logarithmic,
                 }
             }
 
@@ -63,57 +526,98 @@ macro_rules! define_histogram_common {
                 where T: IntoIterator<Item = f64>
             {
                 let mut range = [0.; LEN + 1];
-                let mut last_i = 0;
-                for (i, r) in ranges.into_iter().enumerate() {
-                    if i > LEN {
-                        break;
-                    }
-                    if r.is_nan() {
-                        return Err($crate::InvalidRangeError::NaN);
-                    }
-                    if i > 0 && range[i - 1] > r {
-                        return Err($crate::InvalidRangeError::NotSorted);
-                    }
-                    range[i] = r;
-                    last_i = i;
-                }
-                if last_i != LEN {
+                if $crate::histogram::fill_ranges(&mut range, ranges)? != LEN + 1 {
                     return Err($crate::InvalidRangeError::NotEnoughRanges);
                 }
                 Ok(Self {
                     range,
                     bin: [0; LEN],
+                    outliers: None,
+                    spacing: $crate::histogram::Spacing::Arbitrary,
                 })
             }
 
+            /// Merge another histogram into this one, if their ranges are
+            /// compatible.
+            ///
+            /// In contrast to `merge`, this does not panic, but fails without
+            /// modifying this histogram. The ranges are compatible if every bin
+            /// of the other histogram lies within a bin of this one. If
+            /// outliers are counted, both histograms must also have the same
+            /// range limits.
+            #[inline]
+            pub fn try_merge(&mut self, other: &Self)
+                -> Result<(), $crate::IncompatibleRangesError>
+            {
+                $crate::histogram::check_outlier_ranges(&self.outliers, &self.range,
+                    &other.outliers, &other.range)?;
+                $crate::histogram::add_refined_bins(&self.range, &mut self.bin,
+                    &other.range, &other.bin)?;
+                $crate::histogram::try_merge_outliers(&mut self.outliers, &other.outliers)
+            }
+
+            /// Construct a histogram from its ranges, bins and the spacing of
+            /// the ranges.
+            #[doc(hidden)]
+            #[inline]
+            pub fn from_parts(range: [f64; LEN + 1], bin: [u64; LEN],
+                spacing: $crate::histogram::Spacing) -> Self
+            {
+                Self { range, bin, outliers: None, spacing }
+            }
+
+            /// Construct a histogram with bins containing the same fraction of
+            /// the samples, given a function estimating the p-quantile, for
+            /// example `|p| digest.quantile(p)`.
+            ///
+            /// The largest sample is at the upper range limit and thus out of
+            /// range, unless outliers are counted. Fails if the quantiles are
+            /// not sorted or contain `nan`.
+            #[inline]
+            pub fn from_quantile_edges<F>(mut quantile: F) -> Result<Self, $crate::InvalidRangeError>
+                where F: FnMut(f64) -> f64
+            {
+                Self::from_ranges((0..=LEN).map(|i| quantile(i as f64 / LEN as f64)))
+            }
+
+            /// Count the samples that are out of range or `nan` instead of
+            /// rejecting them.
+            ///
+            /// The counts are included in the normalization of the variances,
+            /// when merging and when iterating with `iter_with_outliers`.
+            #[inline]
+            pub fn count_outliers(mut self) -> Self {
+                self.outliers = Some(Default::default());
+                self
+            }
+
+            /// Return the counts of the samples that did not fall into any
+            /// bin, if they are counted.
+            #[inline]
+            pub fn outlier_counts(&self) -> Option<$crate::OutlierCounts> {
+                self.outliers
+            }
+
             /// Find the index of the bin corresponding to the given sample.
             ///
             /// Fails if the sample is out of range of the histogram.
             #[inline]
             pub fn find(&self, x: f64) -> Result<usize, $crate::SampleOutOfRangeError> {
-                // We made sure our ranges are valid at construction, so we can
-                // safely unwrap.
-                match self.range.binary_search_by(|p| p.partial_cmp(&x).unwrap()) {
-                    Ok(i) if i < LEN => {
-                        Ok(i)
-                    },
-                    Err(i) if i > 0 && i < LEN + 1 => {
-                        Ok(i - 1)
-                    },
-                    _ => {
-                        Err($crate::SampleOutOfRangeError)
-                    },
-                }
+                $crate::histogram::find(&self.range, self.spacing, x)
             }
 
             /// Add a sample to the histogram.
             ///
-            /// Fails if the sample is out of range of the histogram.
+            /// Fails if the sample is out of range of the histogram, unless
+            /// outliers are counted.
             #[inline]
             pub fn add(&mut self, x: f64) -> Result<(), $crate::SampleOutOfRangeError> {
                 if let Ok(i) = self.find(x) {
                     self.bin[i] += 1;
                     Ok(())
+                } else if let Some(ref mut outliers) = self.outliers {
+                    outliers.add(x, self.range[0]);
+                    Ok(())
                 } else {
                     Err($crate::SampleOutOfRangeError)
                 }
@@ -132,10 +636,21 @@ macro_rules! define_histogram_common {
                 self.into_iter()
             }
 
-            /// Reset all bins to zero.
+            /// Return an iterator over the bins like `iter`, preceded by the
+            /// underflow `((-inf, lower), count)` and followed by the overflow
+            /// `((upper, inf), count)` if outliers are counted.
+            #[inline]
+            pub fn iter_with_outliers(&self) -> IterHistogram<'_> {
+                IterHistogram::with_outliers(self.bins(), self.ranges(), self.outliers)
+            }
+
+            /// Reset all bins and outlier counts to zero.
             #[inline]
             pub fn reset(&mut self) {
                 self.bin = [0; LEN];
+                if let Some(ref mut outliers) = self.outliers {
+                    outliers.reset();
+                }
             }
 
             /// Return the lower range limit.
@@ -155,35 +670,13 @@ macro_rules! define_histogram_common {
             }
         }
 
-        /// Iterate over all `(range, count)` pairs in the histogram.
-        #[derive(Debug, Clone)]
-        pub struct IterHistogram<'a> {
-            remaining_bin: &'a [u64],
-            remaining_range: &'a [f64],
-        }
-
-        impl<'a> ::core::iter::Iterator for IterHistogram<'a> {
-            type Item = ((f64, f64), u64);
-            fn next(&mut self) -> Option<((f64, f64), u64)> {
-                if let Some((&bin, rest)) = self.remaining_bin.split_first() {
-                    let left = self.remaining_range[0];
-                    let right = self.remaining_range[1];
-                    self.remaining_bin = rest;
-                    self.remaining_range = &self.remaining_range[1..];
-                    return Some(((left, right), bin));
-                }
-                None
-            }
-        }
+        pub use $crate::histogram::IterHistogram;
 
         impl<'a> ::core::iter::IntoIterator for &'a Histogram {
             type Item = ((f64, f64), u64);
             type IntoIter = IterHistogram<'a>;
             fn into_iter(self) -> IterHistogram<'a> {
-                IterHistogram {
-                    remaining_bin: self.bins(),
-                    remaining_range: self.ranges(),
-                }
+                IterHistogram::new(self.bins(), self.ranges())
             }
         }
 
@@ -192,17 +685,17 @@ macro_rules! define_histogram_common {
             fn bins(&self) -> &[u64] {
                 &self.bin[..]
             }
+
+            #[inline]
+            fn outliers(&self) -> u64 {
+                self.outliers.map_or(0, |o| o.total())
+            }
         }
 
         impl<'a> ::core::ops::AddAssign<&'a Self> for Histogram {
             #[inline]
             fn add_assign(&mut self, other: &Self) {
-                for (a, b) in self.range.iter().zip(other.range.iter()) {
-                    assert_eq!(a, b, "Both histograms must have the same ranges");
-                }
-                for (x, y) in self.bin.iter_mut().zip(other.bin.iter()) {
-                    *x += y;
-                }
+                $crate::Merge::merge(self, other);
             }
         }
 
@@ -212,29 +705,97 @@ macro_rules! define_histogram_common {
                 for x in &mut self.bin[..] {
                     *x *= other;
                 }
+                if let Some(ref mut outliers) = self.outliers {
+                    *outliers *= other;
+                }
             }
         }
 
         impl $crate::Merge for Histogram {
             fn merge(&mut self, other: &Self) {
-                assert_eq!(self.bin.len(), other.bin.len());
-                for (a, b) in self.range.iter().zip(other.range.iter()) {
-                    assert_eq!(a, b, "Both histograms must have the same ranges");
-                }
-                for (a, b) in self.bin.iter_mut().zip(other.bin.iter()) {
-                    *a += *b;
-                }
+                $crate::histogram::add_bins(&self.range, &mut self.bin,
+                    &other.range, &other.bin);
+                $crate::histogram::merge_outliers(&mut self.outliers, &other.outliers);
             }
         }
     );
 }
 
+#[cfg(any(feature = "std", feature = "libm"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! define_histogram_log_width {
+    () => (
+        /// Construct a histogram with bins of constant width on a logarithmic
+        /// scale.
+        ///
+        /// Panics unless `0 < start < end` and both are finite.
+        #[inline]
+        pub fn with_log_width(start: f64, end: f64) -> Self {
+            let mut range = [0.; LEN + 1];
+            $crate::histogram::fill_log_width(&mut range, start, end);
+            Self {
+                range,
+                bin: [0; LEN],
+                outliers: None,
+                spacing: $crate::histogram::Spacing::Logarithmic,
+            }
+        }
+    );
+}
+
+#[cfg(not(any(feature = "std", feature = "libm")))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! define_histogram_log_width {
+    () => ();
+}
+
+#[cfg(feature = "alloc")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! define_histogram_dyn {
+    () => (
+        /// Return a histogram with `len` bins, merging groups of adjacent
+        /// bins.
+        ///
+        /// Panics if `len` is zero or `LEN` is not a multiple of `len`.
+        #[inline]
+        pub fn rebin(&self, len: usize) -> $crate::DynHistogram {
+            $crate::DynHistogram::from_parts(&self.range, &self.bin,
+                self.outliers, self.spacing).rebin(len)
+        }
+
+        /// Return a histogram with the bins from the one containing `lo` to
+        /// the one containing `hi`, inclusive. Values out of range are clamped
+        /// to the range of the histogram.
+        ///
+        /// If outliers are counted, the samples in the other bins are added to
+        /// the underflow and overflow. Fails if `lo > hi`, if either is `nan`
+        /// or if no bin overlaps the range.
+        #[inline]
+        pub fn slice(&self, lo: f64, hi: f64)
+            -> Result<$crate::DynHistogram, $crate::InvalidRangeError>
+        {
+            $crate::DynHistogram::from_parts(&self.range, &self.bin,
+                self.outliers, self.spacing).slice(lo, hi)
+        }
+    );
+}
+
+#[cfg(not(feature = "alloc"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! define_histogram_dyn {
+    () => ();
+}
+
 #[cfg(feature = "serde1")]
 #[doc(hidden)]
 #[macro_export]
 macro_rules! define_histogram_inner {
-    ($name:ident, $LEN:expr) => (
-        mod $name {
+    ($vis:vis $name:ident, $LEN:expr) => (
+        $vis mod $name {
             $crate::define_histogram_common!($LEN);
 
             use ::serde::{Serialize, Deserialize};
@@ -251,6 +812,12 @@ macro_rules! define_histogram_inner {
                 /// The bins of the histogram.
                 #[serde(with = "BigArray")]
                 bin: [u64; LEN],
+                /// The counts of the samples outside of the bins, if counted.
+                #[serde(default, skip_serializing_if = "Option::is_none")]
+                outliers: Option<$crate::OutlierCounts>,
+                /// The spacing of the ranges.
+                #[serde(skip)]
+                spacing: $crate::histogram::Spacing,
             }
         }
     );
@@ -260,8 +827,8 @@ macro_rules! define_histogram_inner {
 #[doc(hidden)]
 #[macro_export]
 macro_rules! define_histogram_inner {
-    ($name:ident, $LEN:expr) => (
-        mod $name {
+    ($vis:vis $name:ident, $LEN:expr) => (
+        $vis mod $name {
             $crate::define_histogram_common!($LEN);
 
             /// A histogram with a number of bins known at compile time.
@@ -271,6 +838,10 @@ macro_rules! define_histogram_inner {
                 range: [f64; LEN + 1],
                 /// The bins of the histogram.
                 bin: [u64; LEN],
+                /// The counts of the samples outside of the bins, if counted.
+                outliers: Option<$crate::OutlierCounts>,
+                /// The spacing of the ranges.
+                spacing: $crate::histogram::Spacing,
             }
         }
     );
@@ -280,7 +851,8 @@ macro_rules! define_histogram_inner {
 ///
 /// Because macros are not hygenic for items, everything is defined in a private
 /// module with the given name. This includes the `Histogram` struct, the number
-/// of bins `LEN` and the histogram iterator `HistogramIter`.
+/// of bins `LEN` and the histogram iterator `HistogramIter`. The module can be
+/// made public by prefixing the name with a visibility, like `pub hist`.
 ///
 /// Note that you need to make sure that `core` is accessible to the macro.
 ///
@@ -299,5 +871,5 @@ macro_rules! define_histogram_inner {
 /// ```
 #[macro_export]
 macro_rules! define_histogram {
-    ($name:ident, $LEN:expr) => ($crate::define_histogram_inner!($name, $LEN););
+    ($vis:vis $name:ident, $LEN:expr) => ($crate::define_histogram_inner!($vis $name, $LEN););
 }
@@ -0,0 +1,348 @@
+/// Iterate over all `((x_lower, x_upper), (y_lower, y_upper), count)` cells in
+/// a two-dimensional histogram.
+///
+/// The cells are ordered by their x range first and by their y range second.
+#[derive(Debug, Clone)]
+pub struct IterHistogram2d<'a> {
+    x_range: &'a [f64],
+    y_range: &'a [f64],
+    bin: &'a [u64],
+    index: usize,
+}
+
+impl<'a> IterHistogram2d<'a> {
+    /// Iterate over the given cells and their ranges.
+    #[doc(hidden)]
+    #[inline]
+    pub fn new(x_range: &'a [f64], y_range: &'a [f64], bin: &'a [u64]) -> IterHistogram2d<'a> {
+        IterHistogram2d { x_range, y_range, bin, index: 0 }
+    }
+}
+
+impl<'a> Iterator for IterHistogram2d<'a> {
+    type Item = ((f64, f64), (f64, f64), u64);
+    fn next(&mut self) -> Option<((f64, f64), (f64, f64), u64)> {
+        let &count = self.bin.get(self.index)?;
+        let ny = self.y_range.len() - 1;
+        let (ix, iy) = (self.index / ny, self.index % ny);
+        self.index += 1;
+        Some((
+            (self.x_range[ix], self.x_range[ix + 1]),
+            (self.y_range[iy], self.y_range[iy + 1]),
+            count,
+        ))
+    }
+}
+
+/// Add the cells of another two-dimensional histogram with the same ranges.
+///
+/// Panics if the ranges are different.
+#[doc(hidden)]
+#[inline]
+pub fn add_cells(x_range: &[f64], y_range: &[f64], bin: &mut [u64],
+    other_x_range: &[f64], other_y_range: &[f64], other_bin: &[u64])
+{
+    for (a, b) in y_range.iter().zip(other_y_range.iter()) {
+        assert_eq!(a, b, "Both histograms must have the same ranges");
+    }
+    crate::histogram::add_bins(x_range, bin, other_x_range, other_bin);
+}
+
+/// Sum the cells of a two-dimensional histogram over the y ranges.
+#[doc(hidden)]
+#[inline]
+pub fn marginal_x(bin: &[u64], marginal: &mut [u64]) {
+    let ny = bin.len() / marginal.len();
+    for (m, row) in marginal.iter_mut().zip(bin.chunks(ny)) {
+        *m = row.iter().sum();
+    }
+}
+
+/// Sum the cells of a two-dimensional histogram over the x ranges.
+#[doc(hidden)]
+#[inline]
+pub fn marginal_y(bin: &[u64], marginal: &mut [u64]) {
+    for row in bin.chunks(marginal.len()) {
+        for (m, &b) in marginal.iter_mut().zip(row.iter()) {
+            *m += b;
+        }
+    }
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! define_histogram2d_common {
+    ($NX:expr, $NY:expr) => (
+        $crate::define_histogram!(pub x, $NX);
+        $crate::define_histogram!(pub y, $NY);
+
+        /// The number of bins along the x axis.
+        const NX: usize = $NX;
+        /// The number of bins along the y axis.
+        const NY: usize = $NY;
+
+        pub use $crate::IterHistogram2d;
+
+        impl Histogram2d {
+            /// Construct a histogram with constant bin widths along both axes.
+            #[inline]
+            pub fn with_const_width(x_start: f64, x_end: f64, y_start: f64, y_end: f64) -> Self {
+                let mut x_range = [0.; NX + 1];
+                let mut y_range = [0.; NY + 1];
+                $crate::histogram::fill_const_width(&mut x_range, x_start, x_end);
+                $crate::histogram::fill_const_width(&mut y_range, y_start, y_end);
+                Self {
+                    x_range,
+                    y_range,
+                    bin: [0; NX * NY],
+                    x_spacing: $crate::histogram::Spacing::linear(x_start, x_end),
+                    y_spacing: $crate::histogram::Spacing::linear(y_start, y_end),
+                }
+            }
+
+            /// Construct a histogram from given ranges along both axes.
+            ///
+            /// The ranges are given by iterators of floats where neighboring
+            /// pairs `(a, b)` define a bin for all `x` where `a <= x < b`.
+            ///
+            /// Fails if an iterator is too short (less than `n + 1` where `n`
+            /// is the number of bins along the axis), is not sorted or contains
+            /// `nan`. `inf` and empty ranges are allowed.
+            #[inline]
+            pub fn from_ranges<T, U>(x_ranges: T, y_ranges: U)
+                -> Result<Self, $crate::InvalidRangeError>
+                where T: IntoIterator<Item = f64>, U: IntoIterator<Item = f64>
+            {
+                let mut x_range = [0.; NX + 1];
+                let mut y_range = [0.; NY + 1];
+                if $crate::histogram::fill_ranges(&mut x_range, x_ranges)? != NX + 1
+                    || $crate::histogram::fill_ranges(&mut y_range, y_ranges)? != NY + 1
+                {
+                    return Err($crate::InvalidRangeError::NotEnoughRanges);
+                }
+                Ok(Self {
+                    x_range,
+                    y_range,
+                    bin: [0; NX * NY],
+                    x_spacing: $crate::histogram::Spacing::Arbitrary,
+                    y_spacing: $crate::histogram::Spacing::Arbitrary,
+                })
+            }
+
+            /// Find the indices `(ix, iy)` of the cell corresponding to the
+            /// given sample.
+            ///
+            /// Fails if the sample is out of range of the histogram.
+            #[inline]
+            pub fn find(&self, x: f64, y: f64)
+                -> Result<(usize, usize), $crate::SampleOutOfRangeError>
+            {
+                let ix = $crate::histogram::find(&self.x_range, self.x_spacing, x)?;
+                let iy = $crate::histogram::find(&self.y_range, self.y_spacing, y)?;
+                Ok((ix, iy))
+            }
+
+            /// Add a sample to the histogram.
+            ///
+            /// Fails if the sample is out of range of the histogram.
+            #[inline]
+            pub fn add(&mut self, x: f64, y: f64) -> Result<(), $crate::SampleOutOfRangeError> {
+                let (ix, iy) = self.find(x, y)?;
+                self.bin[ix * NY + iy] += 1;
+                Ok(())
+            }
+
+            /// Return the ranges along the x axis.
+            #[inline]
+            pub fn x_ranges(&self) -> &[f64] {
+                &self.x_range[..]
+            }
+
+            /// Return the ranges along the y axis.
+            #[inline]
+            pub fn y_ranges(&self) -> &[f64] {
+                &self.y_range[..]
+            }
+
+            /// Return the cells of the histogram.
+            ///
+            /// The count of the cell `(ix, iy)` is at index `ix * NY + iy`.
+            #[inline]
+            pub fn bins(&self) -> &[u64] {
+                &self.bin[..]
+            }
+
+            /// Return the count of the given cell.
+            #[inline]
+            pub fn count(&self, ix: usize, iy: usize) -> u64 {
+                assert!(iy < NY);
+                self.bin[ix * NY + iy]
+            }
+
+            /// Estimate the variance for the given cell.
+            ///
+            /// The square root of this estimates the error of the cell count.
+            #[inline]
+            pub fn variance(&self, ix: usize, iy: usize) -> f64 {
+                let count = self.count(ix, iy) as f64;
+                let sum: u64 = self.bin.iter().sum();
+                count * (1. - count / (sum as f64))
+            }
+
+            /// Return an iterator over the cells and corresponding ranges:
+            /// `((x_lower, x_upper), (y_lower, y_upper), count)`
+            #[inline]
+            pub fn iter(&self) -> IterHistogram2d<'_> {
+                self.into_iter()
+            }
+
+            /// Reset all cells to zero.
+            #[inline]
+            pub fn reset(&mut self) {
+                self.bin = [0; NX * NY];
+            }
+
+            /// Return the histogram of the x values, summing over the y
+            /// ranges.
+            #[inline]
+            pub fn marginal_x(&self) -> x::Histogram {
+                let mut bin = [0; NX];
+                $crate::histogram2d::marginal_x(&self.bin, &mut bin);
+                x::Histogram::from_parts(self.x_range, bin, self.x_spacing)
+            }
+
+            /// Return the histogram of the y values, summing over the x
+            /// ranges.
+            #[inline]
+            pub fn marginal_y(&self) -> y::Histogram {
+                let mut bin = [0; NY];
+                $crate::histogram2d::marginal_y(&self.bin, &mut bin);
+                y::Histogram::from_parts(self.y_range, bin, self.y_spacing)
+            }
+        }
+
+        impl<'a> ::core::iter::IntoIterator for &'a Histogram2d {
+            type Item = ((f64, f64), (f64, f64), u64);
+            type IntoIter = IterHistogram2d<'a>;
+            fn into_iter(self) -> IterHistogram2d<'a> {
+                IterHistogram2d::new(&self.x_range, &self.y_range, &self.bin)
+            }
+        }
+
+        impl<'a> ::core::ops::AddAssign<&'a Self> for Histogram2d {
+            #[inline]
+            fn add_assign(&mut self, other: &Self) {
+                $crate::Merge::merge(self, other);
+            }
+        }
+
+        impl ::core::ops::MulAssign<u64> for Histogram2d {
+            #[inline]
+            fn mul_assign(&mut self, other: u64) {
+                for x in &mut self.bin[..] {
+                    *x *= other;
+                }
+            }
+        }
+
+        impl $crate::Merge for Histogram2d {
+            fn merge(&mut self, other: &Self) {
+                $crate::histogram2d::add_cells(&self.x_range, &self.y_range, &mut self.bin,
+                    &other.x_range, &other.y_range, &other.bin);
+            }
+        }
+    );
+}
+
+#[cfg(feature = "serde1")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! define_histogram2d_inner {
+    ($vis:vis $name:ident, $NX:expr, $NY:expr) => (
+        $vis mod $name {
+            $crate::define_histogram2d_common!($NX, $NY);
+
+            use ::serde::{Serialize, Deserialize};
+
+            /// A two-dimensional histogram with a number of bins known at
+            /// compile time.
+            #[derive(Debug, Clone, Serialize, Deserialize)]
+            pub struct Histogram2d {
+                /// The ranges defining the bins along the x axis.
+                #[serde(with = "serde_big_array::BigArray")]
+                x_range: [f64; NX + 1],
+                /// The ranges defining the bins along the y axis.
+                #[serde(with = "serde_big_array::BigArray")]
+                y_range: [f64; NY + 1],
+                /// The cells of the histogram.
+                #[serde(with = "serde_big_array::BigArray")]
+                bin: [u64; NX * NY],
+                /// The spacing of the ranges along the x axis.
+                #[serde(skip)]
+                x_spacing: $crate::histogram::Spacing,
+                /// The spacing of the ranges along the y axis.
+                #[serde(skip)]
+                y_spacing: $crate::histogram::Spacing,
+            }
+        }
+    );
+}
+
+#[cfg(not(feature = "serde1"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! define_histogram2d_inner {
+    ($vis:vis $name:ident, $NX:expr, $NY:expr) => (
+        $vis mod $name {
+            $crate::define_histogram2d_common!($NX, $NY);
+
+            /// A two-dimensional histogram with a number of bins known at
+            /// compile time.
+            #[derive(Debug, Clone)]
+            pub struct Histogram2d {
+                /// The ranges defining the bins along the x axis.
+                x_range: [f64; NX + 1],
+                /// The ranges defining the bins along the y axis.
+                y_range: [f64; NY + 1],
+                /// The cells of the histogram.
+                bin: [u64; NX * NY],
+                /// The spacing of the ranges along the x axis.
+                x_spacing: $crate::histogram::Spacing,
+                /// The spacing of the ranges along the y axis.
+                y_spacing: $crate::histogram::Spacing,
+            }
+        }
+    );
+}
+
+/// Define a two-dimensional histogram with a number of bins along each axis
+/// known at compile time.
+///
+/// Like for [`define_histogram`], everything is defined in a private module
+/// with the given name. This includes the `Histogram2d` struct, the numbers of
+/// bins `NX` and `NY`, and the one-dimensional histograms `x::Histogram` and
+/// `y::Histogram` returned by the marginal projections.
+///
+/// [`define_histogram`]: ./macro.define_histogram.html
+///
+///
+/// # Example
+///
+/// ```
+/// use average::{Histogram, define_histogram2d};
+///
+/// define_histogram2d!(hist2d, 4, 2);
+/// let mut h = hist2d::Histogram2d::with_const_width(0., 4., 0., 2.);
+/// for i in 0..8 {
+///     h.add(f64::from(i / 2), f64::from(i % 2)).unwrap();
+/// }
+/// assert_eq!(h.count(3, 1), 1);
+/// assert_eq!(h.marginal_x().bins(), &[2, 2, 2, 2]);
+/// assert_eq!(h.marginal_y().bins(), &[4, 4]);
+/// ```
+#[macro_export]
+macro_rules! define_histogram2d {
+    ($vis:vis $name:ident, $NX:expr, $NY:expr) => (
+        $crate::define_histogram2d_inner!($vis $name, $NX, $NY);
+    );
+}
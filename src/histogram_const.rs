@@ -1,5 +1,8 @@
 //! Histogram implementation via const generics.
 
+use crate::OutlierCounts;
+use crate::histogram::Spacing;
+
 /// Invalid ranges were specified for constructing the histogram.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum InvalidRangeError {
@@ -24,6 +27,8 @@ where [u8; LEN + 1]: Sized {
         self.range[..].fmt(formatter)?;
         formatter.write_str(", bins: ")?;
         self.bin[..].fmt(formatter)?;
+        formatter.write_str(", outliers: ")?;
+        self.outliers.fmt(formatter)?;
         formatter.write_str(" }}")
     }
 }
@@ -42,6 +47,43 @@ where [u8; LEN + 1]: Sized {
         Self {
             range,
             bin: [0; LEN],
+            outliers: None,
+            spacing: Spacing::linear(start, end),
+        }
+    }
+
+    /// Construct a histogram with bins of constant width on a logarithmic
+    /// scale.
+    ///
+    /// Panics unless `0 < start < end` and both are finite.
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[cfg_attr(doc_cfg, doc(cfg(any(feature = "std", feature = "libm"))))]
+    #[inline]
+    pub fn with_log_width(start: f64, end: f64) -> Self {
+        let mut range = [0.; LEN + 1];
+        crate::histogram::fill_log_width(&mut range, start, end);
+        Self {
+            range,
+            bin: [0; LEN],
+            outliers: None,
+            spacing: Spacing::Logarithmic,
+        }
+    }
+
+    /// Construct a histogram with bins starting at `start`, where the upper
+    /// edge of each bin is `ratio` times its lower edge.
+    ///
+    /// Panics if `start` is not positive and finite or if `ratio` is not
+    /// finite and larger than 1.
+    #[inline]
+    pub fn with_geometric_ratio(start: f64, ratio: f64) -> Self {
+        let mut range = [0.; LEN + 1];
+        crate::histogram::fill_geometric(&mut range, start, ratio);
+        Self {
+            range,
+            bin: [0; LEN],
+            outliers: None,
+            spacing: Spacing::Logarithmic,
         }
     }
 
@@ -78,37 +120,64 @@ where [u8; LEN + 1]: Sized {
         Ok(Self {
             range,
             bin: [0; LEN],
+            outliers: None,
+            spacing: Spacing::Arbitrary,
         })
     }
 
+    /// Construct a histogram with bins containing the same fraction of the
+    /// samples, given a function estimating the p-quantile, for example
+    /// `|p| digest.quantile(p)`.
+    ///
+    /// The largest sample is at the upper range limit and thus out of range,
+    /// unless outliers are counted. Fails if the quantiles are not sorted or
+    /// contain `nan`.
+    #[inline]
+    pub fn from_quantile_edges<F>(mut quantile: F) -> Result<Self, InvalidRangeError>
+        where F: FnMut(f64) -> f64
+    {
+        Self::from_ranges((0..=LEN).map(|i| quantile(i as f64 / LEN as f64)))
+    }
+
+    /// Count the samples that are out of range or `nan` instead of rejecting
+    /// them.
+    ///
+    /// The counts are included in the normalization of the variances, when
+    /// merging and when iterating with `iter_with_outliers`.
+    #[inline]
+    pub fn count_outliers(mut self) -> Self {
+        self.outliers = Some(OutlierCounts::default());
+        self
+    }
+
+    /// Return the counts of the samples that did not fall into any bin, if
+    /// they are counted.
+    #[inline]
+    pub fn outlier_counts(&self) -> Option<OutlierCounts> {
+        self.outliers
+    }
+
     /// Find the index of the bin corresponding to the given sample.
     ///
     /// Fails if the sample is out of range of the histogram.
     #[inline]
     pub fn find(&self, x: f64) -> Result<usize, SampleOutOfRangeError> {
-        // We made sure our ranges are valid at construction, so we can
-        // safely unwrap.
-        match self.range.binary_search_by(|p| p.partial_cmp(&x).unwrap()) {
-            Ok(i) if i < LEN => {
-                Ok(i)
-            },
-            Err(i) if i > 0 && i < LEN + 1 => {
-                Ok(i - 1)
-            },
-            _ => {
-                Err(SampleOutOfRangeError)
-            },
-        }
+        crate::histogram::find(&self.range, self.spacing, x)
+            .map_err(|_| SampleOutOfRangeError)
     }
 
     /// Add a sample to the histogram.
     ///
-    /// Fails if the sample is out of range of the histogram.
+    /// Fails if the sample is out of range of the histogram, unless outliers
+    /// are counted.
     #[inline]
     pub fn add(&mut self, x: f64) -> Result<(), SampleOutOfRangeError> {
         if let Ok(i) = self.find(x) {
             self.bin[i] += 1;
             Ok(())
+        } else if let Some(ref mut outliers) = self.outliers {
+            outliers.add(x, self.range[0]);
+            Ok(())
         } else {
             Err(SampleOutOfRangeError)
         }
@@ -127,10 +196,21 @@ where [u8; LEN + 1]: Sized {
         self.into_iter()
     }
 
-    /// Reset all bins to zero.
+    /// Return an iterator over the bins like `iter`, preceded by the underflow
+    /// `((-inf, lower), count)` and followed by the overflow
+    /// `((upper, inf), count)` if outliers are counted.
+    #[inline]
+    pub fn iter_with_outliers(&self) -> IterHistogram<'_> {
+        IterHistogram::with_outliers(self.bins(), self.ranges(), self.outliers)
+    }
+
+    /// Reset all bins and outlier counts to zero.
     #[inline]
     pub fn reset(&mut self) {
         self.bin = [0; LEN];
+        if let Some(ref mut outliers) = self.outliers {
+            outliers.reset();
+        }
     }
 
     /// Return the lower range limit.
@@ -155,13 +235,65 @@ where [u8; LEN + 1]: Sized {
         &self.bin[..]
     }
 
+    /// Return a histogram with `M` bins, merging groups of adjacent bins.
+    ///
+    /// Panics if `M` is zero or `LEN` is not a multiple of `M`.
+    pub fn rebin<const M: usize>(&self) -> Histogram<M>
+    where [u8; M + 1]: Sized {
+        let mut range = [0.; M + 1];
+        let mut bin = [0; M];
+        crate::histogram::rebin(&self.range, &self.bin, &mut range, &mut bin);
+        Histogram { range, bin, outliers: self.outliers, spacing: self.spacing }
+    }
+
+    /// Return a histogram with the bins from the one containing `lo` to the
+    /// one containing `hi`, inclusive. Values out of range are clamped to the
+    /// range of the histogram.
+    ///
+    /// The number of bins is only known at runtime, so the result is a
+    /// `DynHistogram`. If outliers are counted, the samples in the other bins
+    /// are added to the underflow and overflow. Fails if `lo > hi`, if either
+    /// is `nan` or if no bin overlaps the range.
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+    pub fn slice(&self, lo: f64, hi: f64) -> Result<crate::DynHistogram, InvalidRangeError> {
+        crate::DynHistogram::from_parts(&self.range, &self.bin,
+            self.outliers, self.spacing)
+            .slice(lo, hi)
+            .map_err(|e| match e {
+                crate::InvalidRangeError::NotEnoughRanges => InvalidRangeError::NotEnoughRanges,
+                crate::InvalidRangeError::NotSorted => InvalidRangeError::NotSorted,
+                crate::InvalidRangeError::NaN => InvalidRangeError::NaN,
+            })
+    }
+
+    /// Merge another histogram into this one, if their ranges are compatible.
+    ///
+    /// In contrast to `merge`, this does not panic, but fails without
+    /// modifying this histogram. The ranges are compatible if every bin of
+    /// the other histogram lies within a bin of this one, so the other
+    /// histogram may have more bins. Both or none of the histograms must count
+    /// outliers, and if they do, they must have the same range limits.
+    ///
+    /// The number of bins is part of the type, so the merged histogram always
+    /// has the ranges of this one. If this histogram is the finer one, merge
+    /// it into the other one instead, as in `coarse.try_merge(&fine)`.
+    pub fn try_merge<const M: usize>(&mut self, other: &Histogram<M>)
+        -> Result<(), crate::IncompatibleRangesError>
+    where [u8; M + 1]: Sized {
+        crate::histogram::check_outlier_ranges(&self.outliers, &self.range,
+            &other.outliers, &other.range)?;
+        crate::histogram::add_refined_bins(&self.range, &mut self.bin, &other.range, &other.bin)?;
+        crate::histogram::try_merge_outliers(&mut self.outliers, &other.outliers)
+    }
+
     /// Estimate the variance for the given bin.
     ///
     /// The square root of this estimates the error of the bin count.
     #[inline]
     pub fn variance(&self, bin: usize) -> f64 {
         let count = self.bins()[bin];
-        let sum: u64 = self.bins().iter().sum();
+        let sum: u64 = self.bins().iter().sum::<u64>() + crate::Histogram::outliers(self);
         multinomal_variance(count as f64, 1./(sum as f64))
     }
 
@@ -188,7 +320,7 @@ where [u8; LEN + 1]: Sized {
     /// This is more efficient than calling `variance()` for each bin.
     #[inline]
     pub fn variances(&self) -> IterVariances<<&Self as IntoIterator>::IntoIter> {
-        let sum: u64 = self.bins().iter().sum();
+        let sum: u64 = self.bins().iter().sum::<u64>() + crate::Histogram::outliers(self);
         IterVariances {
             histogram_iter: self.into_iter(),
             sum_inv: 1./(sum as f64)
@@ -196,40 +328,18 @@ where [u8; LEN + 1]: Sized {
     }
 }
 
-/// Iterate over all `(range, count)` pairs in the histogram.
-#[derive(Clone, Debug)]
-pub struct IterHistogram<'a> {
-    remaining_bin: &'a [u64],
-    remaining_range: &'a [f64],
-}
-
-impl<'a> ::core::iter::Iterator for IterHistogram<'a> {
-    type Item = ((f64, f64), u64);
-    fn next(&mut self) -> Option<((f64, f64), u64)> {
-        if let Some((&bin, rest)) = self.remaining_bin.split_first() {
-            let left = self.remaining_range[0];
-            let right = self.remaining_range[1];
-            self.remaining_bin = rest;
-            self.remaining_range = &self.remaining_range[1..];
-            return Some(((left, right), bin));
-        }
-        None
-    }
-}
+pub use crate::histogram::IterHistogram;
 
 impl<'a, const LEN: usize> ::core::iter::IntoIterator for &'a Histogram<LEN>
 where [u8; LEN + 1]: Sized {
     type Item = ((f64, f64), u64);
     type IntoIter = IterHistogram<'a>;
     fn into_iter(self) -> IterHistogram<'a> {
-        IterHistogram {
-            remaining_bin: self.bins(),
-            remaining_range: self.ranges(),
-        }
+        IterHistogram::new(self.bins(), self.ranges())
     }
 }
 
-impl<'a, const LEN: usize> ::core::ops::AddAssign<&'a Self> for Histogram<LEN>
+impl<const LEN: usize> ::core::ops::AddAssign<&Self> for Histogram<LEN>
 where [u8; LEN + 1]: Sized {
     #[inline]
     fn add_assign(&mut self, other: &Self) {
@@ -239,6 +349,20 @@ where [u8; LEN + 1]: Sized {
         for (x, y) in self.bin.iter_mut().zip(other.bin.iter()) {
             *x += y;
         }
+        crate::histogram::merge_outliers(&mut self.outliers, &other.outliers);
+    }
+}
+
+impl<const LEN: usize> crate::Histogram for Histogram<LEN>
+where [u8; LEN + 1]: Sized {
+    #[inline]
+    fn bins(&self) -> &[u64] {
+        &self.bin[..]
+    }
+
+    #[inline]
+    fn outliers(&self) -> u64 {
+        self.outliers.map_or(0, |o| o.total())
     }
 }
 
@@ -249,6 +373,9 @@ where [u8; LEN + 1]: Sized {
         for x in &mut self.bin[..] {
             *x *= other;
         }
+        if let Some(ref mut outliers) = self.outliers {
+            *outliers *= other;
+        }
     }
 }
 
@@ -262,6 +389,7 @@ where [u8; LEN + 1]: Sized {
         for (a, b) in self.bin.iter_mut().zip(other.bin.iter()) {
             *a += *b;
         }
+        crate::histogram::merge_outliers(&mut self.outliers, &other.outliers);
     }
 }
 
@@ -273,6 +401,190 @@ where [u8; LEN + 1]: Sized {
     range: [f64; LEN + 1],
     /// The bins of the histogram.
     bin: [u64; LEN],
+    /// The counts of the samples outside of the bins, if counted.
+    outliers: Option<OutlierCounts>,
+    /// The spacing of the ranges.
+    spacing: Spacing,
+}
+
+/// A two-dimensional histogram with a number of bins along each axis known at
+/// compile time.
+#[derive(Clone, Debug)]
+pub struct Histogram2d<const NX: usize, const NY: usize>
+where [u8; NX + 1]: Sized, [u8; NY + 1]: Sized, [u8; NX * NY]: Sized {
+    /// The ranges defining the bins along the x axis.
+    x_range: [f64; NX + 1],
+    /// The ranges defining the bins along the y axis.
+    y_range: [f64; NY + 1],
+    /// The cells of the histogram.
+    bin: [u64; NX * NY],
+    /// The spacing of the ranges along the x axis.
+    x_spacing: Spacing,
+    /// The spacing of the ranges along the y axis.
+    y_spacing: Spacing,
+}
+
+impl<const NX: usize, const NY: usize> Histogram2d<NX, NY>
+where [u8; NX + 1]: Sized, [u8; NY + 1]: Sized, [u8; NX * NY]: Sized {
+    /// Construct a histogram from the ranges of two empty histograms.
+    #[inline]
+    fn from_axes(x: Histogram<NX>, y: Histogram<NY>) -> Self {
+        Self {
+            x_range: x.range,
+            y_range: y.range,
+            bin: [0; NX * NY],
+            x_spacing: x.spacing,
+            y_spacing: y.spacing,
+        }
+    }
+
+    /// Construct a histogram with constant bin widths along both axes.
+    #[inline]
+    pub fn with_const_width(x_start: f64, x_end: f64, y_start: f64, y_end: f64) -> Self {
+        Self::from_axes(
+            Histogram::with_const_width(x_start, x_end),
+            Histogram::with_const_width(y_start, y_end))
+    }
+
+    /// Construct a histogram from given ranges along both axes.
+    ///
+    /// The ranges are given by iterators of floats where neighboring pairs
+    /// `(a, b)` define a bin for all `x` where `a <= x < b`.
+    ///
+    /// Fails if an iterator is too short (less than `n + 1` where `n` is the
+    /// number of bins along the axis), is not sorted or contains `nan`. `inf`
+    /// and empty ranges are allowed.
+    #[inline]
+    pub fn from_ranges<T, U>(x_ranges: T, y_ranges: U) -> Result<Self, InvalidRangeError>
+        where T: IntoIterator<Item = f64>, U: IntoIterator<Item = f64>
+    {
+        Ok(Self::from_axes(Histogram::from_ranges(x_ranges)?, Histogram::from_ranges(y_ranges)?))
+    }
+
+    /// Find the indices `(ix, iy)` of the cell corresponding to the given
+    /// sample.
+    ///
+    /// Fails if the sample is out of range of the histogram.
+    #[inline]
+    pub fn find(&self, x: f64, y: f64) -> Result<(usize, usize), SampleOutOfRangeError> {
+        let ix = crate::histogram::find(&self.x_range, self.x_spacing, x);
+        let iy = crate::histogram::find(&self.y_range, self.y_spacing, y);
+        match (ix, iy) {
+            (Ok(ix), Ok(iy)) => Ok((ix, iy)),
+            _ => Err(SampleOutOfRangeError),
+        }
+    }
+
+    /// Add a sample to the histogram.
+    ///
+    /// Fails if the sample is out of range of the histogram.
+    #[inline]
+    pub fn add(&mut self, x: f64, y: f64) -> Result<(), SampleOutOfRangeError> {
+        let (ix, iy) = self.find(x, y)?;
+        self.bin[ix * NY + iy] += 1;
+        Ok(())
+    }
+
+    /// Return the ranges along the x axis.
+    #[inline]
+    pub fn x_ranges(&self) -> &[f64] {
+        &self.x_range[..]
+    }
+
+    /// Return the ranges along the y axis.
+    #[inline]
+    pub fn y_ranges(&self) -> &[f64] {
+        &self.y_range[..]
+    }
+
+    /// Return the cells of the histogram.
+    ///
+    /// The count of the cell `(ix, iy)` is at index `ix * NY + iy`.
+    #[inline]
+    pub fn bins(&self) -> &[u64] {
+        &self.bin[..]
+    }
+
+    /// Return the count of the given cell.
+    #[inline]
+    pub fn count(&self, ix: usize, iy: usize) -> u64 {
+        assert!(iy < NY);
+        self.bin[ix * NY + iy]
+    }
+
+    /// Estimate the variance for the given cell.
+    ///
+    /// The square root of this estimates the error of the cell count.
+    #[inline]
+    pub fn variance(&self, ix: usize, iy: usize) -> f64 {
+        let count = self.count(ix, iy);
+        let sum: u64 = self.bins().iter().sum();
+        multinomal_variance(count as f64, 1./(sum as f64))
+    }
+
+    /// Return an iterator over the cells and corresponding ranges:
+    /// `((x_lower, x_upper), (y_lower, y_upper), count)`
+    #[inline]
+    pub fn iter(&self) -> crate::IterHistogram2d<'_> {
+        self.into_iter()
+    }
+
+    /// Reset all cells to zero.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.bin = [0; NX * NY];
+    }
+
+    /// Return the histogram of the x values, summing over the y ranges.
+    #[inline]
+    pub fn marginal_x(&self) -> Histogram<NX> {
+        let mut bin = [0; NX];
+        crate::histogram2d::marginal_x(&self.bin, &mut bin);
+        Histogram { range: self.x_range, bin, outliers: None, spacing: self.x_spacing }
+    }
+
+    /// Return the histogram of the y values, summing over the x ranges.
+    #[inline]
+    pub fn marginal_y(&self) -> Histogram<NY> {
+        let mut bin = [0; NY];
+        crate::histogram2d::marginal_y(&self.bin, &mut bin);
+        Histogram { range: self.y_range, bin, outliers: None, spacing: self.y_spacing }
+    }
+}
+
+impl<'a, const NX: usize, const NY: usize> ::core::iter::IntoIterator for &'a Histogram2d<NX, NY>
+where [u8; NX + 1]: Sized, [u8; NY + 1]: Sized, [u8; NX * NY]: Sized {
+    type Item = ((f64, f64), (f64, f64), u64);
+    type IntoIter = crate::IterHistogram2d<'a>;
+    fn into_iter(self) -> crate::IterHistogram2d<'a> {
+        crate::IterHistogram2d::new(&self.x_range, &self.y_range, &self.bin)
+    }
+}
+
+impl<const NX: usize, const NY: usize> ::core::ops::AddAssign<&Self> for Histogram2d<NX, NY>
+where [u8; NX + 1]: Sized, [u8; NY + 1]: Sized, [u8; NX * NY]: Sized {
+    #[inline]
+    fn add_assign(&mut self, other: &Self) {
+        crate::Merge::merge(self, other);
+    }
+}
+
+impl<const NX: usize, const NY: usize> ::core::ops::MulAssign<u64> for Histogram2d<NX, NY>
+where [u8; NX + 1]: Sized, [u8; NY + 1]: Sized, [u8; NX * NY]: Sized {
+    #[inline]
+    fn mul_assign(&mut self, other: u64) {
+        for x in &mut self.bin[..] {
+            *x *= other;
+        }
+    }
+}
+
+impl<const NX: usize, const NY: usize> crate::Merge for Histogram2d<NX, NY>
+where [u8; NX + 1]: Sized, [u8; NY + 1]: Sized, [u8; NX * NY]: Sized {
+    fn merge(&mut self, other: &Self) {
+        crate::histogram2d::add_cells(&self.x_range, &self.y_range, &mut self.bin,
+            &other.x_range, &other.y_range, &other.bin);
+    }
 }
 
 /// Calculate the multinomial variance. Relevant for histograms.
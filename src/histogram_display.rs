@@ -0,0 +1,172 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use num_traits::Float;
+
+use super::Histogram;
+
+/// Render a histogram as text, with one line per bin.
+///
+/// Every line shows the range and the count of a bin, followed by a bar
+/// proportional to the count. The output only uses ASCII characters. If error
+/// bars are enabled, the line also shows the error of the count estimated from
+/// `variances()` as `+/- error`, and the bar marks the interval of one standard
+/// deviation: `#` is below the lower error, `=` is between the lower error and
+/// the count, and `-` is between the count and the upper error.
+///
+/// This is created by `Histogram::display`.
+///
+///
+/// ## Example
+///
+/// ```
+/// use average::{Histogram, define_histogram};
+///
+/// define_histogram!(hist, 3);
+/// let mut h = hist::Histogram::with_const_width(0., 3.);
+/// for &x in &[0.5, 1.5, 1.5, 2.5, 2.5, 2.5, 2.5] {
+///     h.add(x).unwrap();
+/// }
+/// assert_eq!(h.display().width(8).to_string(), "\
+/// [0, 1) 1 |##
+/// [1, 2) 2 |####
+/// [2, 3) 4 |########
+/// ");
+/// ```
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(doc_cfg, doc(cfg(all(feature = "alloc", any(feature = "std", feature = "libm")))))]
+pub struct HistogramDisplay<'a, H> {
+    histogram: &'a H,
+    width: usize,
+    log_scale: bool,
+    error_bars: bool,
+    precision: Option<usize>,
+}
+
+impl<'a, H> HistogramDisplay<'a, H>
+    where H: Histogram,
+          for<'b> &'b H: IntoIterator<Item = ((f64, f64), u64)>,
+{
+    /// Create a renderer for the given histogram, with bars of at most 40
+    /// characters on a linear scale and without error bars.
+    #[inline]
+    pub fn new(histogram: &'a H) -> HistogramDisplay<'a, H> {
+        HistogramDisplay {
+            histogram,
+            width: 40,
+            log_scale: false,
+            error_bars: false,
+            precision: None,
+        }
+    }
+
+    /// Set the length of the longest bar.
+    #[inline]
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Set whether the bars are proportional to the logarithm of one plus the
+    /// count, which makes small counts visible next to large ones.
+    #[inline]
+    pub fn log_scale(mut self, log_scale: bool) -> Self {
+        self.log_scale = log_scale;
+        self
+    }
+
+    /// Set whether the errors of the counts are shown.
+    #[inline]
+    pub fn error_bars(mut self, error_bars: bool) -> Self {
+        self.error_bars = error_bars;
+        self
+    }
+
+    /// Set the number of decimals of the ranges and errors.
+    ///
+    /// By default, the ranges are printed exactly and the errors with one
+    /// decimal.
+    #[inline]
+    pub fn precision(mut self, precision: usize) -> Self {
+        self.precision = Some(precision);
+        self
+    }
+
+    /// Format a number with the configured precision.
+    fn format_number(&self, x: f64, default_precision: Option<usize>) -> String {
+        match self.precision.or(default_precision) {
+            Some(precision) => format!("{:.*}", precision, x),
+            None => format!("{}", x),
+        }
+    }
+
+    /// Scale a count to a bar length.
+    fn scale(&self, x: f64, max: f64) -> usize {
+        if max <= 0. || x <= 0. {
+            return 0;
+        }
+        let fraction = if self.log_scale {
+            Float::ln_1p(x) / Float::ln_1p(max)
+        } else {
+            x / max
+        };
+        (fraction * self.width as f64).round() as usize
+    }
+}
+
+impl<'a, H> fmt::Display for HistogramDisplay<'a, H>
+    where H: Histogram,
+          for<'b> &'b H: IntoIterator<Item = ((f64, f64), u64)>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let errors: Vec<f64> = if self.error_bars {
+            self.histogram.variances().map(|v| Float::sqrt(v.max(0.))).collect()
+        } else {
+            self.histogram.bins().iter().map(|_| 0.).collect()
+        };
+        let mut rows = Vec::with_capacity(errors.len());
+        for (((a, b), count), &error) in self.histogram.into_iter().zip(errors.iter()) {
+            let range = format!("[{}, {})",
+                self.format_number(a, None), self.format_number(b, None));
+            let error = if self.error_bars {
+                format!("+/- {}", self.format_number(error, Some(1)))
+            } else {
+                String::new()
+            };
+            rows.push((range, format!("{}", count), error));
+        }
+        let range_width = rows.iter().map(|r| r.0.chars().count()).max().unwrap_or(0);
+        let count_width = rows.iter().map(|r| r.1.len()).max().unwrap_or(0);
+        let error_width = rows.iter().map(|r| r.2.chars().count()).max().unwrap_or(0);
+        let max = self.histogram.bins().iter().zip(errors.iter())
+            .map(|(&count, &error)| count as f64 + error)
+            .fold(0., f64::max);
+
+        for ((range, count, error), (&n, &sigma)) in
+            rows.iter().zip(self.histogram.bins().iter().zip(errors.iter()))
+        {
+            write!(f, "{:<rw$} {:>cw$} ", range, count, rw = range_width, cw = count_width)?;
+            if self.error_bars {
+                write!(f, "{:<ew$} ", error, ew = error_width)?;
+            }
+            f.write_str("|")?;
+            let n = n as f64;
+            let lower = self.scale(n - sigma, max);
+            let center = self.scale(n, max);
+            let upper = self.scale(n + sigma, max);
+            for i in 0..upper.max(center) {
+                f.write_str(if i < lower {
+                    "#"
+                } else if i < center {
+                    "="
+                } else {
+                    "-"
+                })?;
+            }
+            f.write_str("\n")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,366 @@
+use alloc::vec::Vec;
+
+use num_traits::float::FloatCore;
+#[cfg(feature = "serde1")] use serde::{Serialize, Deserialize};
+
+use super::{Estimate, Merge};
+
+/// Sort a slice of floats that does not contain `nan`.
+#[inline]
+fn sort_floats<T: FloatCore>(v: &mut [T]) {
+    v.sort_unstable_by(|a, b| a.partial_cmp(b).unwrap());
+}
+
+/// Turn a seed into a state of the pseudo-random number generator.
+///
+/// This is SplitMix64, avoiding the invalid all-zero state of xorshift.
+#[inline]
+fn mix(seed: u64) -> u64 {
+    let mut z = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^= z >> 31;
+    if z == 0 { 0x853c_49e6_748f_ea9b } else { z }
+}
+
+/// Return the capacity of a compactor `depth` levels below the top one.
+///
+/// The capacities decrease geometrically with a factor of 2/3 towards the
+/// bottom, but are at least 2.
+#[inline]
+fn capacity(k: u16, depth: usize) -> usize {
+    let mut c = usize::from(k);
+    for _ in 0..depth {
+        // This is `ceil(2c/3)`.
+        c -= c / 3;
+        if c <= 2 {
+            return 2;
+        }
+    }
+    c
+}
+
+/// Estimate quantiles and ranks of a sequence of numbers ("population") using
+/// a KLL sketch.
+///
+/// The [KLL sketch][1] keeps a hierarchy of compactors. Samples enter the
+/// lowest compactor, and when a compactor is full, it is sorted and a random
+/// half of its samples is promoted to the next compactor, where they count
+/// twice. The error of the estimated ranks is bounded with high probability,
+/// independent of the order of the samples and of the order in which sketches
+/// are merged. It is roughly proportional to `1/k`, where `k` is the capacity
+/// of the top compactor. For `k = 200`, the rank error is typically below
+/// 1 % of the sample size.
+///
+/// The sketch retains `O(k log(n/k))` samples. Its serialized form contains
+/// only the retained samples, and `nan` samples are ignored.
+///
+/// The random choices are made with a deterministic pseudo-random number
+/// generator, so the results are reproducible. Collecting an iterator into a
+/// `KllSketch` uses `k = 200`, and [`Estimate::estimate`] estimates the
+/// median.
+///
+/// [1]: https://arxiv.org/abs/1603.05346
+/// [`Estimate::estimate`]: ./trait.Estimate.html#tymethod.estimate
+///
+///
+/// ## Example
+///
+/// ```
+/// use average::{KllSketch, Estimate};
+///
+/// let mut s = KllSketch::new();
+/// for i in 1..1001 {
+///     s.add(f64::from(i));
+/// }
+/// println!("The median is {}, {}% of the samples are at most 100.",
+///     s.quantile(0.5), 100. * s.rank(100.));
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+pub struct KllSketch<T = f64> {
+    /// Capacity of the top compactor.
+    k: u16,
+    /// Retained samples for each compactor, starting with the lowest one.
+    compactors: Vec<Vec<T>>,
+    /// Smallest sample.
+    min: T,
+    /// Largest sample.
+    max: T,
+    /// Sample size.
+    len: u64,
+    /// State of the pseudo-random number generator.
+    rng: u64,
+}
+
+impl KllSketch<f64> {
+    /// Create a new KLL sketch with `k = 200`.
+    ///
+    /// Use `new_generic` for other floating point types than `f64`.
+    #[inline]
+    pub fn new() -> KllSketch<f64> {
+        KllSketch::new_generic()
+    }
+}
+
+impl<T: FloatCore> KllSketch<T> {
+    /// Create a new KLL sketch with `k = 200`.
+    #[inline]
+    pub fn new_generic() -> KllSketch<T> {
+        KllSketch::with_k(200)
+    }
+
+    /// Create a new KLL sketch with the given capacity of the top compactor.
+    ///
+    /// Panics if `k` is smaller than 8.
+    #[inline]
+    pub fn with_k(k: u16) -> KllSketch<T> {
+        assert!(k >= 8, "k must be at least 8");
+        KllSketch {
+            k,
+            compactors: Vec::new(),
+            min: T::infinity(),
+            max: T::neg_infinity(),
+            len: 0,
+            rng: 0x853c_49e6_748f_ea9b,
+        }
+    }
+
+    /// Create a new KLL sketch with the given capacity of the top compactor
+    /// and seed for the random choices made during compaction.
+    ///
+    /// Sketches that are going to be merged should use different seeds, so
+    /// that they make independent choices.
+    ///
+    /// Panics if `k` is smaller than 8.
+    #[inline]
+    pub fn with_k_and_seed(k: u16, seed: u64) -> KllSketch<T> {
+        KllSketch { rng: mix(seed), ..KllSketch::with_k(k) }
+    }
+
+    /// Return the capacity of the top compactor.
+    #[inline]
+    pub fn k(&self) -> u16 {
+        self.k
+    }
+
+    /// Return the sample size.
+    #[inline]
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Determine whether the sample is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Return the number of retained samples.
+    #[inline]
+    pub fn retained(&self) -> usize {
+        self.compactors.iter().map(Vec::len).sum()
+    }
+
+    /// Return a random bit.
+    #[inline]
+    fn random_bit(&mut self) -> usize {
+        // This is xorshift64 by Marsaglia.
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 7;
+        self.rng ^= self.rng << 17;
+        (self.rng >> 32) as usize & 1
+    }
+
+    /// Compact compactors until the number of retained samples is within the
+    /// total capacity.
+    fn compress(&mut self) {
+        loop {
+            let height = self.compactors.len();
+            let full = (0..height).find(|&h| {
+                self.compactors[h].len() >= capacity(self.k, height - 1 - h)
+            });
+            let total: usize = (0..height).map(|h| capacity(self.k, h)).sum();
+            let h = match full {
+                Some(h) if self.retained() >= total => h,
+                _ => return,
+            };
+            if h + 1 == height {
+                self.compactors.push(Vec::new());
+            }
+
+            // Keep one sample if the number is odd, and promote every second
+            // of the remaining ones, starting with a random one.
+            let offset = self.random_bit();
+            let mut level = core::mem::take(&mut self.compactors[h]);
+            sort_floats(&mut level);
+            let start = level.len() % 2;
+            self.compactors[h + 1].extend(level[start + offset..].iter().step_by(2));
+            level.truncate(start);
+            self.compactors[h] = level;
+        }
+    }
+
+    /// Return the retained samples and their cumulative weights, sorted by
+    /// the samples.
+    fn sorted_view(&self) -> Vec<(T, u64)> {
+        let mut view: Vec<(T, u64)> = Vec::with_capacity(self.retained());
+        for (h, level) in self.compactors.iter().enumerate() {
+            view.extend(level.iter().map(|&x| (x, 1 << h)));
+        }
+        view.sort_unstable_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        let mut cumulative = 0;
+        for entry in &mut view {
+            cumulative += entry.1;
+            entry.1 = cumulative;
+        }
+        view
+    }
+
+    /// Estimate the normalized rank of `x`, i.e. the fraction of the
+    /// population less than or equal to `x`.
+    ///
+    /// Returns 0 for an empty sample and `nan` if `x` is `nan`.
+    pub fn rank(&self, x: T) -> T {
+        if x.is_nan() {
+            return x;
+        }
+        if self.is_empty() {
+            return T::zero();
+        }
+        let mut weight = 0u64;
+        for (h, level) in self.compactors.iter().enumerate() {
+            let count = level.iter().filter(|&&y| y <= x).count();
+            weight += (count as u64) << h;
+        }
+        T::from(weight).unwrap() / T::from(self.len).unwrap()
+    }
+
+    /// Estimate the p-quantile of the population.
+    ///
+    /// This is the smallest retained sample whose rank is at least `p`.
+    /// Returns 0 for an empty sample. Panics if `p` is not between 0 and 1.
+    pub fn quantile(&self, p: T) -> T {
+        assert!(T::zero() <= p && p <= T::one());
+        if self.is_empty() {
+            return T::zero();
+        }
+        if p == T::zero() {
+            return self.min;
+        }
+        if p == T::one() {
+            return self.max;
+        }
+        let target = p * T::from(self.len).unwrap();
+        let view = self.sorted_view();
+        for &(x, cumulative) in &view {
+            if T::from(cumulative).unwrap() >= target {
+                return x;
+            }
+        }
+        self.max
+    }
+
+    /// Estimate the cumulative distribution function of the population at
+    /// the given split points.
+    ///
+    /// The split points must be strictly increasing. The `i`th entry of the
+    /// returned vector is the fraction of the population less than or equal
+    /// to `split_points[i]`, and the last entry is 1. Returns only zeros for
+    /// an empty sample.
+    pub fn cdf(&self, split_points: &[T]) -> Vec<T> {
+        assert!(split_points.windows(2).all(|w| w[0] < w[1]),
+            "The split points must be strictly increasing");
+        let mut result = Vec::with_capacity(split_points.len() + 1);
+        if self.is_empty() {
+            result.resize(split_points.len() + 1, T::zero());
+            return result;
+        }
+        let len = T::from(self.len).unwrap();
+        let view = self.sorted_view();
+        let mut i = 0;
+        let mut cumulative = 0;
+        for &split in split_points {
+            while i < view.len() && view[i].0 <= split {
+                cumulative = view[i].1;
+                i += 1;
+            }
+            result.push(T::from(cumulative).unwrap() / len);
+        }
+        result.push(T::one());
+        result
+    }
+}
+
+impl<T: FloatCore> core::default::Default for KllSketch<T> {
+    fn default() -> KllSketch<T> {
+        KllSketch::new_generic()
+    }
+}
+
+impl<T: FloatCore> Estimate<T> for KllSketch<T> {
+    #[inline]
+    fn add(&mut self, x: T) {
+        if x.is_nan() {
+            return;
+        }
+        self.min = self.min.min(x);
+        self.max = self.max.max(x);
+        self.len += 1;
+        if self.compactors.is_empty() {
+            self.compactors.push(Vec::new());
+        }
+        self.compactors[0].push(x);
+        self.compress();
+    }
+
+    #[inline]
+    fn estimate(&self) -> T {
+        self.quantile(T::from(0.5).unwrap())
+    }
+}
+
+impl<T: FloatCore> Merge for KllSketch<T> {
+    /// Merge another sample into this one.
+    ///
+    /// If the sketches have different `k`, the smaller one is used. The
+    /// random states of both sketches are mixed, so that later compactions
+    /// are not correlated with those of the other sketch.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use average::{KllSketch, Merge};
+    ///
+    /// let sequence: &[f64] = &[1., 2., 3., 4., 5., 6., 7., 8., 9.];
+    /// let (left, right) = sequence.split_at(3);
+    /// let mut s_left: KllSketch = left.iter().collect();
+    /// let s_right: KllSketch = right.iter().collect();
+    /// s_left.merge(&s_right);
+    /// assert_eq!(s_left.len(), 9);
+    /// assert_eq!(s_left.quantile(0.5), 5.);
+    /// ```
+    fn merge(&mut self, other: &KllSketch<T>) {
+        if other.is_empty() {
+            return;
+        }
+        self.k = self.k.min(other.k);
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.len += other.len;
+        // Sketches built with the same seed make the same choices, so mix in
+        // the state of the other sketch to avoid correlated compactions.
+        self.rng = mix(self.rng ^ other.rng.rotate_left(32) ^ other.len);
+        if self.compactors.len() < other.compactors.len() {
+            self.compactors.resize(other.compactors.len(), Vec::new());
+        }
+        for (level, other_level) in self.compactors.iter_mut().zip(&other.compactors) {
+            level.extend_from_slice(other_level);
+        }
+        self.compress();
+    }
+}
+
+impl_from_iterator!(KllSketch<T>);
+impl_from_par_iterator!(KllSketch<T>);
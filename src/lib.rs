@@ -16,9 +16,21 @@
 //! so the sequence of numbers can be an iterator. The used algorithms try to
 //! avoid numerical instabilities.
 //!
+//! The estimators are generic over the floating point type, which defaults to
+//! `f64`. Use for example `Mean<f32>` for single precision. `new()` always
+//! constructs the `f64` version, so that the type can be inferred. For other
+//! types, use `new_generic()` or `Default`, e.g. `Mean::<f32>::new_generic()`.
+//! Histograms and the estimators defined by [`define_moments`] only support
+//! `f64`.
+//!
 //! If you want [Serde](https://github.com/serde-rs/serde) support,
 //! include `"serde1"` in your list of features.
 //!
+//! Most estimators use constant memory. Estimators that allocate, like
+//! [`TDigest`], [`KllSketch`], [`GkQuantiles`], [`DdSketch`],
+//! [`HdrHistogram`], [`DynHistogram`] and [`WeightedHistogram`], require the
+//! `"alloc"` feature, which is implied by `"std"`.
+//!
 //! Note that deserializing does not currently check for all invalid inputs.
 //! For example, if you deserialize a corrupted [`Variance`] it may return
 //! a negative value for variance, even though that is mathematically impossible.
@@ -41,10 +53,23 @@
 //! * Mean ([`Mean`]) and its error ([`MeanWithError`]).
 //! * Weighted mean ([`WeightedMean`]) and its error
 //!   ([`WeightedMeanWithError`]).
+//! * Weighted variance ([`WeightedVariance`]), skewness ([`WeightedSkewness`])
+//!   and kurtosis ([`WeightedKurtosis`]).
 //! * Variance ([`Variance`]), skewness ([`Skewness`]) and kurtosis
 //!   ([`Kurtosis`]).
+//! * Exponentially weighted moving mean ([`ExpMean`]) and variance
+//!   ([`ExpVariance`]).
+//! * Mean ([`WindowedMean`]), variance ([`WindowedVariance`]), minimum
+//!   ([`WindowedMin`]) and maximum ([`WindowedMax`]) of a sliding window.
 //! * Arbitrary higher moments ([`define_moments`]).
-//! * Quantiles ([`Quantile`]).
+//! * Covariance and Pearson correlation ([`Covariance`]).
+//! * Simple linear regression ([`LinearRegression`]).
+//! * Quantiles ([`Quantile`], [`Quantiles`]).
+//! * Quantiles and cumulative distribution function with bounded relative
+//!   error ([`TDigest`]).
+//! * Quantiles and ranks with bounded rank error ([`KllSketch`]).
+//! * Arbitrary quantiles with a chosen rank error ([`GkQuantiles`]).
+//! * Quantiles with bounded relative error ([`DdSketch`]).
 //! * Minimum ([`Min`]) and maximum ([`Max`]).
 //!
 //!
@@ -67,30 +92,78 @@
 //! The [`define_histogram`] macro can be used to define a histogram struct that
 //! uses constant memory. See [`Histogram10`] (defined using
 //! `define_histogram!(..., 10)`) and the extension trait [`Histogram`]
-//! for the methods available to the generated struct.
+//! for the methods available to the generated struct. If the number of bins is
+//! only known at runtime, use [`DynHistogram`] instead.
+//!
+//! By default, adding a sample outside of the ranges of these histograms fails.
+//! Call `count_outliers()` on a new histogram to count such samples and `nan`
+//! in [`OutlierCounts`] instead, so that no sample is lost.
+//!
+//! Two-dimensional histograms of `(x, y)` pairs can be defined with the
+//! [`define_histogram2d`] macro. Their marginal projections are histograms
+//! of the kind defined by [`define_histogram`].
+//!
+//! The distributions of two histograms can be compared with the tests in
+//! [`goodness_of_fit`].
+//!
+//! For test logs and command line output, `display()` renders a histogram as
+//! text with bars and optional error bars, see [`HistogramDisplay`].
+//!
+//! For weighted samples, [`WeightedHistogram`] accumulates the weights and
+//! their squares, which estimate the variances of the bins.
+//!
+//! If the bin edges should not be chosen by hand, the rules in [`bin_edges`]
+//! derive them from summarized data, and [`HdrHistogram`] derives log-linear
+//! bins from a value range and a number of significant digits.
 //!
 //!
 //! [`Mean`]: ./struct.Mean.html
 //! [`MeanWithError`]: ./type.MeanWithError.html
 //! [`WeightedMean`]: ./struct.WeightedMean.html
 //! [`WeightedMeanWithError`]: ./struct.WeightedMeanWithError.html
+//! [`WeightedVariance`]: ./struct.WeightedVariance.html
+//! [`WeightedSkewness`]: ./struct.WeightedSkewness.html
+//! [`WeightedKurtosis`]: ./struct.WeightedKurtosis.html
 //! [`Variance`]: ./struct.Variance.html
 //! [`Skewness`]: ./struct.Skewness.html
 //! [`Kurtosis`]: ./struct.Kurtosis.html
+//! [`ExpMean`]: ./struct.ExpMean.html
+//! [`ExpVariance`]: ./struct.ExpVariance.html
+//! [`WindowedMean`]: ./struct.WindowedMean.html
+//! [`WindowedVariance`]: ./struct.WindowedVariance.html
+//! [`WindowedMin`]: ./struct.WindowedMin.html
+//! [`WindowedMax`]: ./struct.WindowedMax.html
+//! [`Covariance`]: ./struct.Covariance.html
+//! [`LinearRegression`]: ./struct.LinearRegression.html
 //! [`Quantile`]: ./struct.Quantile.html
+//! [`Quantiles`]: ./struct.Quantiles.html
+//! [`TDigest`]: ./struct.TDigest.html
+//! [`KllSketch`]: ./struct.KllSketch.html
+//! [`GkQuantiles`]: ./struct.GkQuantiles.html
+//! [`DdSketch`]: ./struct.DdSketch.html
 //! [`Min`]: ./struct.Min.html
 //! [`Max`]: ./struct.Max.html
 //! [`concatenate`]: ./macro.concatenate.html
 //! [`define_moments`]: ./macro.define_moments.html
 //! [`define_histogram`]: ./macro.define_histogram.html
+//! [`define_histogram2d`]: ./macro.define_histogram2d.html
+//! [`goodness_of_fit`]: ./goodness_of_fit/index.html
+//! [`bin_edges`]: ./bin_edges/index.html
+//! [`HistogramDisplay`]: ./struct.HistogramDisplay.html
 //! [`Histogram10`]: ./struct.Histogram10.html
 //! [`Histogram`]: ./trait.Histogram.html
+//! [`HdrHistogram`]: ./struct.HdrHistogram.html
+//! [`DynHistogram`]: ./struct.DynHistogram.html
+//! [`OutlierCounts`]: ./struct.OutlierCounts.html
+//! [`WeightedHistogram`]: ./struct.WeightedHistogram.html
 
 #![cfg_attr(doc_cfg, feature(doc_cfg))]
 
 #![allow(
     clippy::float_cmp,
     clippy::suspicious_operation_groupings,
+    clippy::legacy_numeric_constants,
+    clippy::manual_is_multiple_of,
 )]
 
 #![no_std]
@@ -100,32 +173,115 @@
 
 #![cfg_attr(feature = "nightly",
    feature(generic_const_exprs))]
+#![cfg_attr(feature = "nightly",
+   allow(incomplete_features))]
+
+#[cfg(feature = "alloc")] extern crate alloc;
+
+// Allow macros to refer to Rayon without a direct dependency of the caller.
+#[cfg(feature = "rayon")]
+#[doc(hidden)]
+pub use rayon;
 
 #[macro_use] mod macros;
 #[macro_use] mod moments;
 mod weighted_mean;
+mod weighted_moments;
+mod exp_moments;
+mod windowed;
 mod minmax;
 #[cfg(any(feature = "std", feature = "libm"))]
 #[cfg_attr(doc_cfg, doc(cfg(any(feature = "std", feature = "libm"))))]
 mod quantile;
+#[cfg(any(feature = "std", feature = "libm"))]
+#[cfg_attr(doc_cfg, doc(cfg(any(feature = "std", feature = "libm"))))]
+mod quantiles;
+#[cfg(all(feature = "alloc", any(feature = "std", feature = "libm")))]
+#[cfg_attr(doc_cfg, doc(cfg(all(feature = "alloc", any(feature = "std", feature = "libm")))))]
+mod tdigest;
+#[cfg(all(feature = "alloc", any(feature = "std", feature = "libm")))]
+#[cfg_attr(doc_cfg, doc(cfg(all(feature = "alloc", any(feature = "std", feature = "libm")))))]
+mod ddsketch;
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+mod kll;
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+mod gk;
 mod traits;
-#[macro_use] mod histogram;
+#[doc(hidden)]
+#[macro_use] pub mod histogram;
+#[doc(hidden)]
+#[macro_use] pub mod histogram2d;
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+mod dyn_histogram;
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+mod weighted_histogram;
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+mod hdr_histogram;
+#[cfg(all(feature = "alloc", any(feature = "std", feature = "libm")))]
+#[cfg_attr(doc_cfg, doc(cfg(all(feature = "alloc", any(feature = "std", feature = "libm")))))]
+mod histogram_display;
 #[cfg(feature = "nightly")]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "nightly")))]
 pub mod histogram_const;
+#[cfg(any(feature = "std", feature = "libm"))]
+#[cfg_attr(doc_cfg, doc(cfg(any(feature = "std", feature = "libm"))))]
+pub mod bin_edges;
+#[cfg(any(feature = "std", feature = "libm"))]
+#[cfg_attr(doc_cfg, doc(cfg(any(feature = "std", feature = "libm"))))]
+pub mod goodness_of_fit;
 
-pub use crate::moments::{Mean, Variance, MeanWithError};
+pub use crate::moments::{Mean, Variance, MeanWithError, Covariance, LinearRegression};
 #[cfg(any(feature = "std", feature = "libm"))]
 #[cfg_attr(doc_cfg, doc(cfg(any(feature = "std", feature = "libm"))))]
 pub use crate::moments::{Skewness, Kurtosis};
 
 pub use crate::weighted_mean::{WeightedMean, WeightedMeanWithError};
+pub use crate::weighted_moments::{WeightedVariance, WeightKind};
+#[cfg(any(feature = "std", feature = "libm"))]
+#[cfg_attr(doc_cfg, doc(cfg(any(feature = "std", feature = "libm"))))]
+pub use crate::weighted_moments::{WeightedSkewness, WeightedKurtosis};
+pub use crate::exp_moments::{ExpMean, ExpVariance};
+pub use crate::windowed::{WindowedMean, WindowedVariance, WindowedMin, WindowedMax};
 pub use crate::minmax::{Min, Max};
 #[cfg(any(feature = "std", feature = "libm"))]
 #[cfg_attr(doc_cfg, doc(cfg(any(feature = "std", feature = "libm"))))]
 pub use crate::quantile::Quantile;
+#[cfg(any(feature = "std", feature = "libm"))]
+#[cfg_attr(doc_cfg, doc(cfg(any(feature = "std", feature = "libm"))))]
+pub use crate::quantiles::Quantiles;
+#[cfg(all(feature = "alloc", any(feature = "std", feature = "libm")))]
+#[cfg_attr(doc_cfg, doc(cfg(all(feature = "alloc", any(feature = "std", feature = "libm")))))]
+pub use crate::tdigest::TDigest;
+#[cfg(all(feature = "alloc", any(feature = "std", feature = "libm")))]
+#[cfg_attr(doc_cfg, doc(cfg(all(feature = "alloc", any(feature = "std", feature = "libm")))))]
+pub use crate::ddsketch::DdSketch;
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+pub use crate::kll::KllSketch;
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+pub use crate::gk::GkQuantiles;
 pub use crate::traits::{Estimate, Merge, Histogram};
-pub use crate::histogram::{InvalidRangeError, SampleOutOfRangeError};
+pub use crate::histogram::{InvalidRangeError, SampleOutOfRangeError, OutlierCounts};
+pub use crate::histogram::IncompatibleRangesError;
+pub use crate::histogram2d::IterHistogram2d;
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+pub use crate::hdr_histogram::{HdrHistogram, IterHdrHistogram};
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+pub use crate::dyn_histogram::DynHistogram;
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+pub use crate::weighted_histogram::{WeightedHistogram, IterWeightedHistogram};
+#[cfg(all(feature = "alloc", any(feature = "std", feature = "libm")))]
+#[cfg_attr(doc_cfg, doc(cfg(all(feature = "alloc", any(feature = "std", feature = "libm")))))]
+pub use crate::histogram_display::HistogramDisplay;
 
 define_histogram!(hist, 10);
 pub use crate::hist::Histogram as Histogram10;
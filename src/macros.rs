@@ -32,6 +32,19 @@ macro_rules! assert_almost_eq {
 ///
 /// The following traits will be implemented: `Default`, `FromIterator<f64>`.
 ///
+/// Further traits can be requested by listing them after the name, separated
+/// by a colon, and terminating the list with a semicolon:
+///
+/// * `Merge` implements [`Merge`] by merging each estimator, and
+///   `FromParallelIterator<f64>` if the `rayon` feature of this crate is
+///   enabled. The calling crate does not need a dependency on `rayon` for
+///   that. All estimators must implement `Merge`.
+/// * `Clone` and `Debug` derive the standard library traits.
+/// * `Serialize` and `Deserialize` derive the Serde traits. This requires the
+///   `serde1` feature and a dependency on `serde` with the `derive` feature.
+///
+/// [`Merge`]: ./trait.Merge.html
+///
 ///
 /// # Examples
 ///
@@ -86,12 +99,68 @@ macro_rules! assert_almost_eq {
 ///     [Variance, variance, mean, sample_variance],
 ///     [Quantile, quantile, quantile]);
 /// ```
+///
+/// Estimators that can be merged, for example for calculating the statistics
+/// of several samples in parallel:
+///
+/// ```
+/// use average::{Min, Max, Estimate, Merge, concatenate};
+///
+/// concatenate!(MinMax: Merge, Clone, Debug; [Min, min], [Max, max]);
+///
+/// let mut s: MinMax = (1..4).map(f64::from).collect();
+/// let t: MinMax = (4..6).map(f64::from).collect();
+/// s.merge(&t);
+///
+/// assert_eq!(s.min(), 1.0);
+/// assert_eq!(s.max(), 5.0);
+/// println!("{:?}", s.clone());
+/// ```
 #[macro_export]
 macro_rules! concatenate {
-    ( $name:ident, $([$estimator:ident, $statistic:ident]),+ ) => {
-        concatenate!( $name, $([$estimator, $statistic, $statistic]),* );
+    ( @options $name:ident, [$($derive:path),*], [],
+      $fields:tt ) => {
+        $crate::concatenate!( @struct $name, [$($derive),*], $fields );
     };
-    ( $name:ident, $( [$estimator:ident, $field:ident, $($statistic:ident),+] ),+ ) => {
+    ( @options $name:ident, [$($derive:path),*], [Merge $(, $option:ident)*],
+      { $( [$estimator:ident, $field:ident, $($statistic:ident),+] ),+ } ) => {
+        impl $crate::Merge for $name {
+            #[inline]
+            fn merge(&mut self, other: &$name) {
+                $(
+                    $crate::Merge::merge(&mut self.$field, &other.$field);
+                )*
+            }
+        }
+
+        $crate::concatenate_from_par_iterator!($name);
+
+        $crate::concatenate!( @options $name, [$($derive),*], [$($option),*],
+            { $( [$estimator, $field, $($statistic),+] ),+ } );
+    };
+    ( @options $name:ident, [$($derive:path),*], [Clone $(, $option:ident)*],
+      $fields:tt ) => {
+        $crate::concatenate!( @options $name, [$($derive,)* ::core::clone::Clone],
+            [$($option),*], $fields );
+    };
+    ( @options $name:ident, [$($derive:path),*], [Debug $(, $option:ident)*],
+      $fields:tt ) => {
+        $crate::concatenate!( @options $name, [$($derive,)* ::core::fmt::Debug],
+            [$($option),*], $fields );
+    };
+    ( @options $name:ident, [$($derive:path),*], [Serialize $(, $option:ident)*],
+      $fields:tt ) => {
+        $crate::concatenate!( @options $name, [$($derive,)* ::serde::Serialize],
+            [$($option),*], $fields );
+    };
+    ( @options $name:ident, [$($derive:path),*], [Deserialize $(, $option:ident)*],
+      $fields:tt ) => {
+        $crate::concatenate!( @options $name, [$($derive,)* ::serde::Deserialize],
+            [$($option),*], $fields );
+    };
+    ( @struct $name:ident, [$($derive:path),*],
+      { $( [$estimator:ident, $field:ident, $($statistic:ident),+] ),+ } ) => {
+        #[derive($($derive),*)]
         struct $name {
         $(
             $field: $estimator,
@@ -130,24 +199,32 @@ macro_rules! concatenate {
         }
 
         $crate::impl_from_iterator!($name);
-
-        // This should be conditionally activated if all fields implement `Merge`.
-        // Could probably be implemented with specialization.
-        /*
-        impl $crate::Merge for $name {
-            #[inline]
-            fn merge(&mut self, other: &Self) {
-                use $crate::Merge;
-                $(
-                    self.$field.merge(&other.$field);
-                )*
-            }
-        }
-        */
+    };
+    ( $name:ident : $($option:ident),+ ; $([$estimator:ident, $statistic:ident]),+ ) => {
+        $crate::concatenate!( $name : $($option),+ ; $([$estimator, $statistic, $statistic]),* );
+    };
+    ( $name:ident : $($option:ident),+ ;
+      $( [$estimator:ident, $field:ident, $($statistic:ident),+] ),+ ) => {
+        $crate::concatenate!( @options $name, [], [$($option),+],
+            { $( [$estimator, $field, $($statistic),+] ),+ } );
+    };
+    ( $name:ident, $([$estimator:ident, $statistic:ident]),+ ) => {
+        $crate::concatenate!( $name, $([$estimator, $statistic, $statistic]),* );
+    };
+    ( $name:ident, $( [$estimator:ident, $field:ident, $($statistic:ident),+] ),+ ) => {
+        $crate::concatenate!( @struct $name, [],
+            { $( [$estimator, $field, $($statistic),+] ),+ } );
     };
 }
 
 /// Implement `FromIterator<f64>` for an iterative estimator.
+///
+/// The estimator must have a `new()` method without arguments. For an
+/// estimator generic over the floating point type, use
+/// `impl_from_iterator!(Name<T>)` to implement `FromIterator<T>` instead; it
+/// must then implement `Default` and `Estimate<T>`.
+/// Estimators that additionally take a const generic size parameter before the
+/// floating point type are supported via `impl_from_iterator!(Name<const N, T>)`.
 #[macro_export]
 macro_rules! impl_from_iterator {
     ( $name:ident ) => {
@@ -174,11 +251,76 @@ macro_rules! impl_from_iterator {
                 e
             }
         }
-    }
+    };
+    ( $name:ident < $T:ident > ) => {
+        impl<$T> ::core::iter::FromIterator<$T> for $name<$T>
+            where $name<$T>: $crate::Estimate<$T> + ::core::default::Default
+        {
+            fn from_iter<I>(iter: I) -> $name<$T>
+                where I: IntoIterator<Item=$T>
+            {
+                let mut e = <$name<$T> as ::core::default::Default>::default();
+                for i in iter {
+                    $crate::Estimate::add(&mut e, i);
+                }
+                e
+            }
+        }
+
+        impl<'a, $T> ::core::iter::FromIterator<&'a $T> for $name<$T>
+            where $T: Copy + 'a,
+                  $name<$T>: $crate::Estimate<$T> + ::core::default::Default
+        {
+            fn from_iter<I>(iter: I) -> $name<$T>
+                where I: IntoIterator<Item=&'a $T>
+            {
+                let mut e = <$name<$T> as ::core::default::Default>::default();
+                for &i in iter {
+                    $crate::Estimate::add(&mut e, i);
+                }
+                e
+            }
+        }
+    };
+    ( $name:ident < const $N:ident, $T:ident > ) => {
+        impl<const $N: usize, $T> ::core::iter::FromIterator<$T> for $name<$N, $T>
+            where $name<$N, $T>: $crate::Estimate<$T> + ::core::default::Default
+        {
+            fn from_iter<I>(iter: I) -> $name<$N, $T>
+                where I: IntoIterator<Item=$T>
+            {
+                let mut e = <$name<$N, $T> as ::core::default::Default>::default();
+                for i in iter {
+                    $crate::Estimate::add(&mut e, i);
+                }
+                e
+            }
+        }
+
+        impl<'a, const $N: usize, $T> ::core::iter::FromIterator<&'a $T> for $name<$N, $T>
+            where $T: Copy + 'a,
+                  $name<$N, $T>: $crate::Estimate<$T> + ::core::default::Default
+        {
+            fn from_iter<I>(iter: I) -> $name<$N, $T>
+                where I: IntoIterator<Item=&'a $T>
+            {
+                let mut e = <$name<$N, $T> as ::core::default::Default>::default();
+                for &i in iter {
+                    $crate::Estimate::add(&mut e, i);
+                }
+                e
+            }
+        }
+    };
 }
 
 /// Implement `FromParallelIterator<f64>` for an iterative estimator.
 ///
+/// The estimator must have a `new()` method without arguments and implement
+/// `Merge`. For an estimator generic over the floating point type, use
+/// `impl_from_par_iterator!(Name<T>)` to implement `FromParallelIterator<T>`
+/// instead; it must then implement `Default` and `Estimate<T>`.
+///
 /// This will do nothing unless the `rayon` feature is enabled.
 #[macro_export]
 macro_rules! impl_from_par_iterator {
@@ -225,4 +367,108 @@ macro_rules! impl_from_par_iterator {
             }
         }
     };
+    ( $name:ident < $T:ident > ) => {
+        #[cfg(feature = "rayon")]
+        #[cfg_attr(doc_cfg, doc(cfg(feature = "rayon")))]
+        impl<$T> ::rayon::iter::FromParallelIterator<$T> for $name<$T>
+            where $T: Send,
+                  $name<$T>: $crate::Estimate<$T> + $crate::Merge
+                      + ::core::default::Default + Send,
+        {
+            fn from_par_iter<I>(par_iter: I) -> $name<$T>
+                where I: ::rayon::iter::IntoParallelIterator<Item = $T>,
+            {
+                use $crate::Merge;
+                use ::rayon::iter::ParallelIterator;
+
+                let par_iter = par_iter.into_par_iter();
+                par_iter.fold(<$name<$T> as ::core::default::Default>::default, |mut e, i| {
+                    $crate::Estimate::add(&mut e, i);
+                    e
+                }).reduce(<$name<$T> as ::core::default::Default>::default, |mut a, b| {
+                    a.merge(&b);
+                    a
+                })
+            }
+        }
+
+        #[cfg(feature = "rayon")]
+        #[cfg_attr(doc_cfg, doc(cfg(feature = "rayon")))]
+        impl<'a, $T> ::rayon::iter::FromParallelIterator<&'a $T> for $name<$T>
+            where $T: Copy + Sync + 'a,
+                  $name<$T>: $crate::Estimate<$T> + $crate::Merge
+                      + ::core::default::Default + Send,
+        {
+            fn from_par_iter<I>(par_iter: I) -> $name<$T>
+                where I: ::rayon::iter::IntoParallelIterator<Item = &'a $T>,
+            {
+                use $crate::Merge;
+                use ::rayon::iter::ParallelIterator;
+
+                let par_iter = par_iter.into_par_iter();
+                par_iter.fold(<$name<$T> as ::core::default::Default>::default, |mut e, i| {
+                    $crate::Estimate::add(&mut e, *i);
+                    e
+                }).reduce(<$name<$T> as ::core::default::Default>::default, |mut a, b| {
+                    a.merge(&b);
+                    a
+                })
+            }
+        }
+    };
+}
+
+/// Implement `FromParallelIterator<f64>` for a struct defined by
+/// `concatenate!`, if the `rayon` feature of this crate is enabled.
+///
+/// In contrast to `impl_from_par_iterator!`, this does not depend on the
+/// features and dependencies of the calling crate.
+#[cfg(feature = "rayon")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! concatenate_from_par_iterator {
+    ( $name:ident ) => {
+        impl $crate::rayon::iter::FromParallelIterator<f64> for $name {
+            fn from_par_iter<I>(par_iter: I) -> $name
+                where I: $crate::rayon::iter::IntoParallelIterator<Item = f64>,
+            {
+                use $crate::Merge;
+                use $crate::rayon::iter::ParallelIterator;
+
+                let par_iter = par_iter.into_par_iter();
+                par_iter.fold(|| $name::new(), |mut e, i| {
+                    e.add(i);
+                    e
+                }).reduce(|| $name::new(), |mut a, b| {
+                    a.merge(&b);
+                    a
+                })
+            }
+        }
+
+        impl<'a> $crate::rayon::iter::FromParallelIterator<&'a f64> for $name {
+            fn from_par_iter<I>(par_iter: I) -> $name
+                where I: $crate::rayon::iter::IntoParallelIterator<Item = &'a f64>,
+            {
+                use $crate::Merge;
+                use $crate::rayon::iter::ParallelIterator;
+
+                let par_iter = par_iter.into_par_iter();
+                par_iter.fold(|| $name::new(), |mut e, i| {
+                    e.add(*i);
+                    e
+                }).reduce(|| $name::new(), |mut a, b| {
+                    a.merge(&b);
+                    a
+                })
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "rayon"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! concatenate_from_par_iterator {
+    ( $name:ident ) => ();
 }
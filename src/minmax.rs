@@ -1,14 +1,16 @@
 #[cfg(feature = "serde1")] use serde::{Serialize, Deserialize};
 
+use num_traits::float::FloatCore;
+
 use super::{Estimate, Merge};
 
 /// Calculate the minimum of `a` and `b`.
-fn min(a: f64, b: f64) -> f64 {
+fn min<T: FloatCore>(a: T, b: T) -> T {
     a.min(b)
 }
 
 /// Calculate the maximum of `a` and `b`.
-fn max(a: f64, b: f64) -> f64 {
+fn max<T: FloatCore>(a: T, b: T) -> T {
     a.max(b)
 }
 
@@ -25,52 +27,70 @@ fn max(a: f64, b: f64) -> f64 {
 /// ```
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
-pub struct Min {
-    x: f64,
+pub struct Min<T = f64> {
+    x: T,
+}
+
+impl Min<f64> {
+    /// Create a new minimum estimator.
+    ///
+    /// Use `new_generic` for other floating point types than `f64`.
+    #[inline]
+    pub fn new() -> Min<f64> {
+        Min::new_generic()
+    }
+
+    /// Create a new minium estimator from a given value.
+    ///
+    /// Use `from_value_generic` for other floating point types than `f64`.
+    #[inline]
+    pub fn from_value(x: f64) -> Min<f64> {
+        Min::from_value_generic(x)
+    }
 }
 
-impl Min {
+impl<T: FloatCore> Min<T> {
     /// Create a new minium estimator from a given value.
     #[inline]
-    pub fn from_value(x: f64) -> Min {
+    pub fn from_value_generic(x: T) -> Min<T> {
         Min { x }
     }
 
     /// Create a new minimum estimator.
     #[inline]
-    pub fn new() -> Min {
-        Min::from_value(::core::f64::INFINITY)
+    pub fn new_generic() -> Min<T> {
+        Min::from_value_generic(T::infinity())
     }
 
     /// Estimate the minium of the population.
     #[inline]
-    pub fn min(&self) -> f64 {
+    pub fn min(&self) -> T {
         self.x
     }
 }
 
-impl core::default::Default for Min {
-    fn default() -> Min {
-        Min::new()
+impl<T: FloatCore> core::default::Default for Min<T> {
+    fn default() -> Min<T> {
+        Min::new_generic()
     }
 }
 
-impl_from_iterator!(Min);
-impl_from_par_iterator!(Min);
+impl_from_iterator!(Min<T>);
+impl_from_par_iterator!(Min<T>);
 
-impl Estimate for Min {
+impl<T: FloatCore> Estimate<T> for Min<T> {
     #[inline]
-    fn add(&mut self, x: f64) {
+    fn add(&mut self, x: T) {
         self.x = min(self.x, x);
     }
 
     #[inline]
-    fn estimate(&self) -> f64 {
+    fn estimate(&self) -> T {
         self.min()
     }
 }
 
-impl Merge for Min {
+impl<T: FloatCore> Merge for Min<T> {
     /// Merge another sample into this one.
     ///
     ///
@@ -88,7 +108,7 @@ impl Merge for Min {
     /// assert_eq!(min_total.min(), min_left.min());
     /// ```
     #[inline]
-    fn merge(&mut self, other: &Min) {
+    fn merge(&mut self, other: &Min<T>) {
         self.add(other.x);
     }
 }
@@ -106,52 +126,70 @@ impl Merge for Min {
 /// ```
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
-pub struct Max {
-    x: f64,
+pub struct Max<T = f64> {
+    x: T,
+}
+
+impl Max<f64> {
+    /// Create a new maximum estimator.
+    ///
+    /// Use `new_generic` for other floating point types than `f64`.
+    #[inline]
+    pub fn new() -> Max<f64> {
+        Max::new_generic()
+    }
+
+    /// Create a new maxium estimator from a given value.
+    ///
+    /// Use `from_value_generic` for other floating point types than `f64`.
+    #[inline]
+    pub fn from_value(x: f64) -> Max<f64> {
+        Max::from_value_generic(x)
+    }
 }
 
-impl Max {
+impl<T: FloatCore> Max<T> {
     /// Create a new maxium estimator from a given value.
     #[inline]
-    pub fn from_value(x: f64) -> Max {
+    pub fn from_value_generic(x: T) -> Max<T> {
         Max { x }
     }
 
     /// Create a new maximum estimator.
     #[inline]
-    pub fn new() -> Max {
-        Max::from_value(::core::f64::NEG_INFINITY)
+    pub fn new_generic() -> Max<T> {
+        Max::from_value_generic(T::neg_infinity())
     }
 
     /// Estimate the maxium of the population.
     #[inline]
-    pub fn max(&self) -> f64 {
+    pub fn max(&self) -> T {
         self.x
     }
 }
 
-impl core::default::Default for Max {
-    fn default() -> Max {
-        Max::new()
+impl<T: FloatCore> core::default::Default for Max<T> {
+    fn default() -> Max<T> {
+        Max::new_generic()
     }
 }
 
-impl_from_iterator!(Max);
-impl_from_par_iterator!(Max);
+impl_from_iterator!(Max<T>);
+impl_from_par_iterator!(Max<T>);
 
-impl Estimate for Max {
+impl<T: FloatCore> Estimate<T> for Max<T> {
     #[inline]
-    fn add(&mut self, x: f64) {
+    fn add(&mut self, x: T) {
         self.x = max(self.x, x);
     }
 
     #[inline]
-    fn estimate(&self) -> f64 {
+    fn estimate(&self) -> T {
         self.max()
     }
 }
 
-impl Merge for Max {
+impl<T: FloatCore> Merge for Max<T> {
     /// Merge another sample into this one.
     ///
     ///
@@ -169,7 +207,7 @@ impl Merge for Max {
     /// assert_eq!(max_total.max(), max_left.max());
     /// ```
     #[inline]
-    fn merge(&mut self, other: &Max) {
+    fn merge(&mut self, other: &Max<T>) {
         self.add(other.x);
     }
 }
@@ -0,0 +1,231 @@
+/// Estimate the arithmetic means and the covariance of a sequence of number
+/// pairs ("population").
+///
+/// This can also be used to estimate the Pearson correlation coefficient.
+///
+///
+/// ## Example
+///
+/// ```
+/// use average::Covariance;
+///
+/// let a: Covariance = (1..6).map(|x| (f64::from(x), f64::from(2 * x))).collect();
+/// assert_eq!(a.sample_covariance(), 5.);
+/// println!("The correlation is {}.", a.pearson());
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+pub struct Covariance<T = f64> {
+    /// Estimator of the mean and variance of `x`.
+    x: Variance<T>,
+    /// Estimator of the mean and variance of `y`.
+    y: Variance<T>,
+    /// Intermediate sum of products for calculating the covariance.
+    sum_xy: T,
+}
+
+impl Covariance<f64> {
+    /// Create a new covariance estimator.
+    ///
+    /// Use `new_generic` for other floating point types than `f64`.
+    #[inline]
+    pub fn new() -> Covariance<f64> {
+        Covariance::new_generic()
+    }
+}
+
+impl<T: FloatCore> Covariance<T> {
+    /// Create a new covariance estimator.
+    #[inline]
+    pub fn new_generic() -> Covariance<T> {
+        Covariance {
+            x: Variance::new_generic(),
+            y: Variance::new_generic(),
+            sum_xy: T::zero(),
+        }
+    }
+
+    /// Add an observation sampled from the population.
+    #[inline]
+    pub fn add(&mut self, x: T, y: T) {
+        // This algorithm was suggested by Welford in 1962.
+        //
+        // See https://en.wikipedia.org/wiki/Algorithms_for_calculating_variance.
+        let delta_x = x - self.x.mean();
+        self.x.add(x);
+        self.y.add(y);
+        self.sum_xy = self.sum_xy + delta_x * (y - self.y.mean());
+    }
+
+    /// Determine whether the sample is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.x.is_empty()
+    }
+
+    /// Return the sample size.
+    #[inline]
+    pub fn len(&self) -> u64 {
+        self.x.len()
+    }
+
+    /// Estimate the mean of the `x` values of the population.
+    ///
+    /// Returns 0 for an empty sample.
+    #[inline]
+    pub fn mean_x(&self) -> T {
+        self.x.mean()
+    }
+
+    /// Estimate the mean of the `y` values of the population.
+    ///
+    /// Returns 0 for an empty sample.
+    #[inline]
+    pub fn mean_y(&self) -> T {
+        self.y.mean()
+    }
+
+    /// Calculate the sample covariance.
+    ///
+    /// This is an unbiased estimator of the covariance of the population.
+    #[inline]
+    pub fn sample_covariance(&self) -> T {
+        let n = self.len();
+        if n < 2 {
+            return T::zero();
+        }
+        self.sum_xy / T::from(n - 1).unwrap()
+    }
+
+    /// Calculate the population covariance of the sample.
+    ///
+    /// This is a biased estimator of the covariance of the population.
+    #[inline]
+    pub fn population_covariance(&self) -> T {
+        let n = self.len();
+        if n < 2 {
+            return T::zero();
+        }
+        self.sum_xy / T::from(n).unwrap()
+    }
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+impl<T: FloatCore + num_traits::Float> Covariance<T> {
+    /// Estimate the Pearson correlation coefficient of the population.
+    ///
+    /// Returns `nan` if the `x` or the `y` values of the sample have no
+    /// variance.
+    #[cfg_attr(doc_cfg, doc(cfg(any(feature = "std", feature = "libm"))))]
+    #[inline]
+    pub fn pearson(&self) -> T {
+        self.sum_xy / num_traits::Float::sqrt(self.x.sum_2 * self.y.sum_2)
+    }
+}
+
+impl<T: FloatCore> core::default::Default for Covariance<T> {
+    fn default() -> Covariance<T> {
+        Covariance::new_generic()
+    }
+}
+
+impl<T: FloatCore> Merge for Covariance<T> {
+    /// Merge another sample into this one.
+    ///
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use average::{Covariance, Merge};
+    ///
+    /// let sequence: &[(f64, f64)] = &[
+    ///     (1., 2.), (2., 3.), (3., 5.), (4., 4.), (5., 7.),
+    ///     (6., 9.), (7., 8.), (8., 10.), (9., 12.)];
+    /// let (left, right) = sequence.split_at(3);
+    /// let cov_total: Covariance = sequence.iter().collect();
+    /// let mut cov_left: Covariance = left.iter().collect();
+    /// let cov_right: Covariance = right.iter().collect();
+    /// cov_left.merge(&cov_right);
+    /// assert!((cov_total.sample_covariance() - cov_left.sample_covariance()).abs() < 1e-14);
+    /// ```
+    #[inline]
+    fn merge(&mut self, other: &Covariance<T>) {
+        // This algorithm was proposed by Chan et al. in 1979.
+        //
+        // See https://en.wikipedia.org/wiki/Algorithms_for_calculating_variance.
+        let len_self = T::from(self.len()).unwrap();
+        let len_other = T::from(other.len()).unwrap();
+        let len_total = len_self + len_other;
+        if len_total == T::zero() {
+            return;
+        }
+        let delta_x = other.mean_x() - self.mean_x();
+        let delta_y = other.mean_y() - self.mean_y();
+        self.x.merge(&other.x);
+        self.y.merge(&other.y);
+        self.sum_xy = self.sum_xy
+            + other.sum_xy + delta_x*delta_y * len_self * len_other / len_total;
+    }
+}
+
+impl<T: FloatCore> core::iter::FromIterator<(T, T)> for Covariance<T> {
+    fn from_iter<I>(iter: I) -> Covariance<T>
+        where I: IntoIterator<Item=(T, T)>
+    {
+        let mut a = Covariance::new_generic();
+        for (x, y) in iter {
+            a.add(x, y);
+        }
+        a
+    }
+}
+
+impl<'a, T: FloatCore + 'a> core::iter::FromIterator<&'a (T, T)> for Covariance<T> {
+    fn from_iter<I>(iter: I) -> Covariance<T>
+        where I: IntoIterator<Item=&'a (T, T)>
+    {
+        let mut a = Covariance::new_generic();
+        for &(x, y) in iter {
+            a.add(x, y);
+        }
+        a
+    }
+}
+
+#[cfg(feature = "rayon")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "rayon")))]
+impl<T: FloatCore + Send> rayon::iter::FromParallelIterator<(T, T)> for Covariance<T> {
+    fn from_par_iter<I>(par_iter: I) -> Covariance<T>
+        where I: rayon::iter::IntoParallelIterator<Item = (T, T)>
+    {
+        use rayon::iter::ParallelIterator;
+
+        par_iter.into_par_iter().fold(Covariance::new_generic, |mut a, (x, y)| {
+            a.add(x, y);
+            a
+        }).reduce(Covariance::new_generic, |mut a, b| {
+            a.merge(&b);
+            a
+        })
+    }
+}
+
+#[cfg(feature = "rayon")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "rayon")))]
+impl<'a, T: FloatCore + Send + Sync + 'a> rayon::iter::FromParallelIterator<&'a (T, T)>
+    for Covariance<T>
+{
+    fn from_par_iter<I>(par_iter: I) -> Covariance<T>
+        where I: rayon::iter::IntoParallelIterator<Item = &'a (T, T)>
+    {
+        use rayon::iter::ParallelIterator;
+
+        par_iter.into_par_iter().fold(Covariance::new_generic, |mut a, &(x, y)| {
+            a.add(x, y);
+            a
+        }).reduce(Covariance::new_generic, |mut a, b| {
+            a.merge(&b);
+            a
+        })
+    }
+}
@@ -4,20 +4,30 @@
 /// This can be used to estimate the standard error of the mean.
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
-pub struct Kurtosis {
+pub struct Kurtosis<T = f64> {
     /// Estimator of mean, variance and skewness.
-    avg: Skewness,
+    avg: Skewness<T>,
     /// Intermediate sum of terms to the fourth for calculating the skewness.
-    sum_4: f64,
+    sum_4: T,
 }
 
-impl Kurtosis {
+impl Kurtosis<f64> {
+    /// Create a new kurtosis estimator.
+    ///
+    /// Use `new_generic` for other floating point types than `f64`.
+    #[inline]
+    pub fn new() -> Kurtosis<f64> {
+        Kurtosis::new_generic()
+    }
+}
+
+impl<T: Float + FloatCore> Kurtosis<T> {
     /// Create a new kurtosis estimator.
     #[inline]
-    pub fn new() -> Kurtosis {
+    pub fn new_generic() -> Kurtosis<T> {
         Kurtosis {
-            avg: Skewness::new(),
-            sum_4: 0.,
+            avg: Skewness::new_generic(),
+            sum_4: T::zero(),
         }
     }
 
@@ -35,16 +45,19 @@ impl Kurtosis {
     ///
     /// This is useful for avoiding unnecessary divisions in the inner loop.
     #[inline]
-    fn add_inner(&mut self, delta: f64, delta_n: f64) {
+    fn add_inner(&mut self, delta: T, delta_n: T) {
         // This algorithm was suggested by Terriberry.
         //
         // See https://en.wikipedia.org/wiki/Algorithms_for_calculating_variance.
-        let n = self.len().to_f64().unwrap();
-        let term = delta * delta_n * (n - 1.);
+        let n = T::from(self.len()).unwrap();
+        let three = T::from(3).unwrap();
+        let four = T::from(4).unwrap();
+        let six = T::from(6).unwrap();
+        let term = delta * delta_n * (n - T::one());
         let delta_n_sq = delta_n*delta_n;
-        self.sum_4 += term * delta_n_sq * (n*n - 3.*n + 3.)
-            + 6. * delta_n_sq * self.avg.avg.sum_2
-            - 4. * delta_n * self.avg.sum_3;
+        self.sum_4 = self.sum_4 + term * delta_n_sq * (n*n - three*n + three)
+            + six * delta_n_sq * self.avg.avg.sum_2
+            - four * delta_n * self.avg.sum_3;
         self.avg.add_inner(delta, delta_n);
     }
 
@@ -58,7 +71,7 @@ impl Kurtosis {
     ///
     /// Returns 0 for an empty sample.
     #[inline]
-    pub fn mean(&self) -> f64 {
+    pub fn mean(&self) -> T {
         self.avg.mean()
     }
 
@@ -72,7 +85,7 @@ impl Kurtosis {
     ///
     /// This is an unbiased estimator of the variance of the population.
     #[inline]
-    pub fn sample_variance(&self) -> f64 {
+    pub fn sample_variance(&self) -> T {
         self.avg.sample_variance()
     }
 
@@ -80,72 +93,127 @@ impl Kurtosis {
     ///
     /// This is a biased estimator of the variance of the population.
     #[inline]
-    pub fn population_variance(&self) -> f64 {
+    pub fn population_variance(&self) -> T {
         self.avg.population_variance()
     }
 
     /// Estimate the standard error of the mean of the population.
     #[inline]
-    pub fn error_mean(&self) -> f64 {
+    pub fn error_mean(&self) -> T {
         self.avg.error_mean()
     }
 
     /// Estimate the skewness of the population.
     #[inline]
-    pub fn skewness(&self) -> f64 {
+    pub fn skewness(&self) -> T {
         self.avg.skewness()
     }
 
     /// Estimate the excess kurtosis of the population.
     #[inline]
-    pub fn kurtosis(&self) -> f64 {
-        if self.sum_4 == 0. {
-            return 0.;
+    pub fn kurtosis(&self) -> T {
+        if self.sum_4 == T::zero() {
+            return T::zero();
         }
-        let n = self.len().to_f64().unwrap();
-        n * self.sum_4 / (self.avg.avg.sum_2 * self.avg.avg.sum_2) - 3.
+        let n = T::from(self.len()).unwrap();
+        n * self.sum_4 / (self.avg.avg.sum_2 * self.avg.avg.sum_2)
+            - T::from(3).unwrap()
     }
 
+    /// Remove an observation that was previously added.
+    ///
+    /// Panics if the sample is empty.
+    #[inline]
+    pub fn remove(&mut self, x: T) {
+        let mut single = Kurtosis::new_generic();
+        single.add(x);
+        self.unmerge(&single);
+    }
+
+    /// Remove a sample that was previously merged into this one.
+    ///
+    /// This is the inverse of `merge`. Panics if `other` has more samples
+    /// than this one.
+    ///
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use average::Kurtosis;
+    ///
+    /// let sequence: &[f64] = &[1., 2., 3., 4., 5., 6., 7., 8., 9.];
+    /// let (left, right) = sequence.split_at(4);
+    /// let mut k: Kurtosis = sequence.iter().collect();
+    /// let k_left: Kurtosis = left.iter().collect();
+    /// let k_right: Kurtosis = right.iter().collect();
+    /// k.unmerge(&k_right);
+    /// assert_eq!(k.len(), 4);
+    /// assert!((k.kurtosis() - k_left.kurtosis()).abs() < 1e-12);
+    /// ```
+    #[inline]
+    pub fn unmerge(&mut self, other: &Kurtosis<T>) {
+        let len_total = T::from(self.len()).unwrap();
+        let len_other = T::from(other.len()).unwrap();
+        self.avg.unmerge(&other.avg);
+        if self.len() < 2 {
+            // Avoid rounding errors, a single sample has no variance.
+            self.sum_4 = T::zero();
+            return;
+        }
+        let len_self = T::from(self.len()).unwrap();
+        let four = T::from(4).unwrap();
+        let six = T::from(6).unwrap();
+        let delta = other.mean() - self.mean();
+        let delta_n = delta / len_total;
+        let delta_n_sq = delta_n * delta_n;
+        self.sum_4 = self.sum_4 - other.sum_4
+            - delta * delta_n*delta_n_sq * len_self*len_other
+              * (len_self*len_self - len_self*len_other + len_other*len_other)
+            - six*delta_n_sq * (len_self*len_self * other.avg.avg.sum_2 + len_other*len_other * self.avg.avg.sum_2)
+            - four*delta_n * (len_self * other.avg.sum_3 - len_other * self.avg.sum_3);
+    }
 }
 
-impl core::default::Default for Kurtosis {
-    fn default() -> Kurtosis {
-        Kurtosis::new()
+impl<T: Float + FloatCore> core::default::Default for Kurtosis<T> {
+    fn default() -> Kurtosis<T> {
+        Kurtosis::new_generic()
     }
 }
 
-impl Estimate for Kurtosis {
+impl<T: Float + FloatCore> Estimate<T> for Kurtosis<T> {
     #[inline]
-    fn add(&mut self, x: f64) {
+    fn add(&mut self, x: T) {
         let delta = x - self.mean();
         self.increment();
-        let n = self.len().to_f64().unwrap();
+        let n = T::from(self.len()).unwrap();
         self.add_inner(delta, delta/n);
     }
 
     #[inline]
-    fn estimate(&self) -> f64 {
+    fn estimate(&self) -> T {
         self.kurtosis()
     }
 }
 
-impl Merge for Kurtosis {
+impl<T: Float + FloatCore> Merge for Kurtosis<T> {
     #[inline]
-    fn merge(&mut self, other: &Kurtosis) {
-        let len_self = self.len().to_f64().unwrap();
-        let len_other = other.len().to_f64().unwrap();
+    fn merge(&mut self, other: &Kurtosis<T>) {
+        let len_self = T::from(self.len()).unwrap();
+        let len_other = T::from(other.len()).unwrap();
         let len_total = len_self + len_other;
+        let four = T::from(4).unwrap();
+        let six = T::from(6).unwrap();
         let delta = other.mean() - self.mean();
         let delta_n = delta / len_total;
         let delta_n_sq = delta_n * delta_n;
-        self.sum_4 += other.sum_4
+        self.sum_4 = self.sum_4 + other.sum_4
             + delta * delta_n*delta_n_sq * len_self*len_other
               * (len_self*len_self - len_self*len_other + len_other*len_other)
-            + 6.*delta_n_sq * (len_self*len_self * other.avg.avg.sum_2 + len_other*len_other * self.avg.avg.sum_2)
-            + 4.*delta_n * (len_self * other.avg.sum_3 - len_other * self.avg.sum_3);
+            + six*delta_n_sq * (len_self*len_self * other.avg.avg.sum_2 + len_other*len_other * self.avg.avg.sum_2)
+            + four*delta_n * (len_self * other.avg.sum_3 - len_other * self.avg.sum_3);
         self.avg.merge(&other.avg);
     }
 }
 
-impl_from_iterator!(Kurtosis);
-impl_from_par_iterator!(Kurtosis);
+impl_from_iterator!(Kurtosis<T>);
+impl_from_par_iterator!(Kurtosis<T>);
@@ -0,0 +1,233 @@
+/// Estimate a simple linear regression `y = intercept + slope * x` of a
+/// sequence of number pairs ("population") by ordinary least squares.
+///
+/// The coefficient of determination and the standard errors of the estimated
+/// parameters are calculated as well.
+///
+///
+/// ## Example
+///
+/// ```
+/// use average::LinearRegression;
+///
+/// let a: LinearRegression = (1..6).map(|x| (f64::from(x), f64::from(2 * x + 1))).collect();
+/// assert_eq!(a.slope(), 2.);
+/// assert_eq!(a.intercept(), 1.);
+/// assert_eq!(a.r_squared(), 1.);
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+pub struct LinearRegression<T = f64> {
+    /// Estimator of the means, variances and covariance.
+    cov: Covariance<T>,
+}
+
+impl LinearRegression<f64> {
+    /// Create a new linear regression estimator.
+    ///
+    /// Use `new_generic` for other floating point types than `f64`.
+    #[inline]
+    pub fn new() -> LinearRegression<f64> {
+        LinearRegression::new_generic()
+    }
+}
+
+impl<T: FloatCore> LinearRegression<T> {
+    /// Create a new linear regression estimator.
+    #[inline]
+    pub fn new_generic() -> LinearRegression<T> {
+        LinearRegression { cov: Covariance::new_generic() }
+    }
+
+    /// Add an observation sampled from the population.
+    #[inline]
+    pub fn add(&mut self, x: T, y: T) {
+        self.cov.add(x, y);
+    }
+
+    /// Determine whether the sample is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.cov.is_empty()
+    }
+
+    /// Return the sample size.
+    #[inline]
+    pub fn len(&self) -> u64 {
+        self.cov.len()
+    }
+
+    /// Estimate the mean of the `x` values of the population.
+    ///
+    /// Returns 0 for an empty sample.
+    #[inline]
+    pub fn mean_x(&self) -> T {
+        self.cov.mean_x()
+    }
+
+    /// Estimate the mean of the `y` values of the population.
+    ///
+    /// Returns 0 for an empty sample.
+    #[inline]
+    pub fn mean_y(&self) -> T {
+        self.cov.mean_y()
+    }
+
+    /// Estimate the slope of the regression line.
+    ///
+    /// Returns `nan` if the `x` values of the sample have no variance.
+    #[inline]
+    pub fn slope(&self) -> T {
+        self.cov.sum_xy / self.cov.x.sum_2
+    }
+
+    /// Estimate the intercept of the regression line.
+    ///
+    /// Returns `nan` if the `x` values of the sample have no variance.
+    #[inline]
+    pub fn intercept(&self) -> T {
+        self.mean_y() - self.slope() * self.mean_x()
+    }
+
+    /// Calculate the coefficient of determination R².
+    ///
+    /// This is the fraction of the variance of the `y` values that is
+    /// explained by the regression line. Returns `nan` if the `x` or the `y`
+    /// values of the sample have no variance.
+    #[inline]
+    pub fn r_squared(&self) -> T {
+        let sum_xy = self.cov.sum_xy;
+        sum_xy * sum_xy / (self.cov.x.sum_2 * self.cov.y.sum_2)
+    }
+
+    /// Calculate the sum of the squared residuals.
+    #[inline]
+    fn residual_sum_2(&self) -> T {
+        let sum_xy = self.cov.sum_xy;
+        let sum_2 = self.cov.y.sum_2 - sum_xy * sum_xy / self.cov.x.sum_2;
+        // Avoid negative values due to rounding errors.
+        sum_2.max(T::zero())
+    }
+
+    /// Estimate the variance of the residuals.
+    ///
+    /// This is an unbiased estimator of the variance of the population around
+    /// the regression line. Returns 0 for less than 3 samples.
+    #[inline]
+    pub fn residual_variance(&self) -> T {
+        let n = self.len();
+        if n < 3 {
+            return T::zero();
+        }
+        self.residual_sum_2() / T::from(n - 2).unwrap()
+    }
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+impl<T: FloatCore + num_traits::Float> LinearRegression<T> {
+    /// Estimate the standard deviation of the residuals.
+    ///
+    /// Returns 0 for less than 3 samples.
+    #[cfg_attr(doc_cfg, doc(cfg(any(feature = "std", feature = "libm"))))]
+    #[inline]
+    pub fn residual_standard_error(&self) -> T {
+        num_traits::Float::sqrt(self.residual_variance())
+    }
+
+    /// Estimate the standard error of the slope.
+    ///
+    /// Returns 0 for less than 3 samples.
+    #[cfg_attr(doc_cfg, doc(cfg(any(feature = "std", feature = "libm"))))]
+    #[inline]
+    pub fn error_slope(&self) -> T {
+        if self.len() < 3 {
+            return T::zero();
+        }
+        num_traits::Float::sqrt(self.residual_variance() / self.cov.x.sum_2)
+    }
+
+    /// Estimate the standard error of the intercept.
+    ///
+    /// Returns 0 for less than 3 samples.
+    #[cfg_attr(doc_cfg, doc(cfg(any(feature = "std", feature = "libm"))))]
+    #[inline]
+    pub fn error_intercept(&self) -> T {
+        let n = self.len();
+        if n < 3 {
+            return T::zero();
+        }
+        let mean_x = self.mean_x();
+        num_traits::Float::sqrt(self.residual_variance()
+            * (T::one() / T::from(n).unwrap() + mean_x * mean_x / self.cov.x.sum_2))
+    }
+}
+
+impl<T: FloatCore> core::default::Default for LinearRegression<T> {
+    fn default() -> LinearRegression<T> {
+        LinearRegression::new_generic()
+    }
+}
+
+impl<T: FloatCore> Merge for LinearRegression<T> {
+    /// Merge another sample into this one.
+    ///
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use average::{LinearRegression, Merge};
+    ///
+    /// let sequence: &[(f64, f64)] = &[
+    ///     (1., 2.), (2., 3.), (3., 5.), (4., 4.), (5., 7.),
+    ///     (6., 9.), (7., 8.), (8., 10.), (9., 12.)];
+    /// let (left, right) = sequence.split_at(3);
+    /// let reg_total: LinearRegression = sequence.iter().collect();
+    /// let mut reg_left: LinearRegression = left.iter().collect();
+    /// let reg_right: LinearRegression = right.iter().collect();
+    /// reg_left.merge(&reg_right);
+    /// assert!((reg_total.slope() - reg_left.slope()).abs() < 1e-14);
+    /// assert!((reg_total.intercept() - reg_left.intercept()).abs() < 1e-14);
+    /// ```
+    #[inline]
+    fn merge(&mut self, other: &LinearRegression<T>) {
+        self.cov.merge(&other.cov);
+    }
+}
+
+impl<T: FloatCore> core::iter::FromIterator<(T, T)> for LinearRegression<T> {
+    fn from_iter<I>(iter: I) -> LinearRegression<T>
+        where I: IntoIterator<Item=(T, T)>
+    {
+        LinearRegression { cov: iter.into_iter().collect() }
+    }
+}
+
+impl<'a, T: FloatCore + 'a> core::iter::FromIterator<&'a (T, T)> for LinearRegression<T> {
+    fn from_iter<I>(iter: I) -> LinearRegression<T>
+        where I: IntoIterator<Item=&'a (T, T)>
+    {
+        LinearRegression { cov: iter.into_iter().collect() }
+    }
+}
+
+#[cfg(feature = "rayon")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "rayon")))]
+impl<T: FloatCore + Send> rayon::iter::FromParallelIterator<(T, T)> for LinearRegression<T> {
+    fn from_par_iter<I>(par_iter: I) -> LinearRegression<T>
+        where I: rayon::iter::IntoParallelIterator<Item = (T, T)>
+    {
+        LinearRegression { cov: Covariance::from_par_iter(par_iter) }
+    }
+}
+
+#[cfg(feature = "rayon")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "rayon")))]
+impl<'a, T: FloatCore + Send + Sync + 'a> rayon::iter::FromParallelIterator<&'a (T, T)>
+    for LinearRegression<T>
+{
+    fn from_par_iter<I>(par_iter: I) -> LinearRegression<T>
+        where I: rayon::iter::IntoParallelIterator<Item = &'a (T, T)>
+    {
+        LinearRegression { cov: Covariance::from_par_iter(par_iter) }
+    }
+}
@@ -9,20 +9,41 @@
 /// let a: Mean = (1..6).map(f64::from).collect();
 /// println!("The mean is {}.", a.mean());
 /// ```
+///
+/// The type parameter selects the floating point type, `f64` by default:
+///
+/// ```
+/// use average::Mean;
+///
+/// let a: Mean<f32> = (1..6).map(|x| x as f32).collect();
+/// assert_eq!(a.mean(), 3.0f32);
+/// let b = Mean::<f32>::new_generic();
+/// assert!(b.is_empty());
+/// ```
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
-pub struct Mean {
+pub struct Mean<T = f64> {
     /// Mean value.
-    avg: f64,
+    avg: T,
     /// Sample size.
     n: u64,
 }
 
-impl Mean {
+impl Mean<f64> {
     /// Create a new mean estimator.
+    ///
+    /// Use `new_generic` for other floating point types than `f64`.
     #[inline]
-    pub fn new() -> Mean {
-        Mean { avg: 0., n: 0 }
+    pub fn new() -> Mean<f64> {
+        Mean::new_generic()
+    }
+}
+
+impl<T: FloatCore> Mean<T> {
+    /// Create a new mean estimator.
+    #[inline]
+    pub fn new_generic() -> Mean<T> {
+        Mean { avg: T::zero(), n: 0 }
     }
 
     /// Increment the sample size.
@@ -39,12 +60,12 @@ impl Mean {
     ///
     /// This is useful for avoiding unnecessary divisions in the inner loop.
     #[inline]
-    fn add_inner(&mut self, delta_n: f64) {
+    fn add_inner(&mut self, delta_n: T) {
         // This algorithm introduced by Welford in 1962 trades numerical
         // stability for a division inside the loop.
         //
         // See https://en.wikipedia.org/wiki/Algorithms_for_calculating_variance.
-        self.avg += delta_n;
+        self.avg = self.avg + delta_n;
     }
 
     /// Determine whether the sample is empty.
@@ -57,7 +78,7 @@ impl Mean {
     ///
     /// Returns 0 for an empty sample.
     #[inline]
-    pub fn mean(&self) -> f64 {
+    pub fn mean(&self) -> T {
         self.avg
     }
 
@@ -67,29 +88,76 @@ impl Mean {
         self.n
     }
 
+    /// Remove an observation that was previously added.
+    ///
+    /// Panics if the sample is empty.
+    #[inline]
+    pub fn remove(&mut self, sample: T) {
+        assert!(self.n > 0, "Cannot remove a sample from an empty estimator");
+        self.n -= 1;
+        if self.n == 0 {
+            self.avg = T::zero();
+            return;
+        }
+        self.avg = self.avg - (sample - self.avg) / T::from(self.n).unwrap();
+    }
+
+    /// Remove a sample that was previously merged into this one.
+    ///
+    /// This is the inverse of `merge`. Panics if `other` has more samples
+    /// than this one.
+    ///
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use average::Mean;
+    ///
+    /// let sequence: &[f64] = &[1., 2., 3., 4., 5., 6., 7., 8., 9.];
+    /// let (left, right) = sequence.split_at(3);
+    /// let mut avg: Mean = sequence.iter().collect();
+    /// let avg_left: Mean = left.iter().collect();
+    /// let avg_right: Mean = right.iter().collect();
+    /// avg.unmerge(&avg_right);
+    /// assert_eq!(avg.len(), 3);
+    /// assert_eq!(avg.mean(), avg_left.mean());
+    /// ```
+    #[inline]
+    pub fn unmerge(&mut self, other: &Mean<T>) {
+        assert!(other.n <= self.n, "Cannot remove more samples than were added");
+        let len_total = T::from(self.n).unwrap();
+        let len_other = T::from(other.n).unwrap();
+        self.n -= other.n;
+        if self.n == 0 {
+            self.avg = T::zero();
+            return;
+        }
+        let len_self = T::from(self.n).unwrap();
+        self.avg = (len_total * self.avg - len_other * other.avg) / len_self;
+    }
 }
 
-impl core::default::Default for Mean {
-    fn default() -> Mean {
-        Mean::new()
+impl<T: FloatCore> core::default::Default for Mean<T> {
+    fn default() -> Mean<T> {
+        Mean::new_generic()
     }
 }
 
-impl Estimate for Mean {
+impl<T: FloatCore> Estimate<T> for Mean<T> {
     #[inline]
-    fn add(&mut self, sample: f64) {
+    fn add(&mut self, sample: T) {
         self.increment();
         let delta_n = (sample - self.avg)
-            / self.n.to_f64().unwrap();
+            / T::from(self.n).unwrap();
         self.add_inner(delta_n);
     }
 
-    fn estimate(&self) -> f64 {
+    fn estimate(&self) -> T {
         self.mean()
     }
 }
 
-impl Merge for Mean {
+impl<T: FloatCore> Merge for Mean<T> {
     /// Merge another sample into this one.
     ///
     ///
@@ -107,12 +175,12 @@ impl Merge for Mean {
     /// assert_eq!(avg_total.mean(), avg_left.mean());
     /// ```
     #[inline]
-    fn merge(&mut self, other: &Mean) {
+    fn merge(&mut self, other: &Mean<T>) {
         // This algorithm was proposed by Chan et al. in 1979.
         //
         // See https://en.wikipedia.org/wiki/Algorithms_for_calculating_variance.
-        let len_self = self.n.to_f64().unwrap();
-        let len_other = other.n.to_f64().unwrap();
+        let len_self = T::from(self.n).unwrap();
+        let len_other = T::from(other.n).unwrap();
         let len_total = len_self + len_other;
         self.n += other.n;
         self.avg = (len_self * self.avg + len_other * other.avg) / len_total;
@@ -124,5 +192,5 @@ impl Merge for Mean {
     }
 }
 
-impl_from_iterator!(Mean);
-impl_from_par_iterator!(Mean);
+impl_from_iterator!(Mean<T>);
+impl_from_par_iterator!(Mean<T>);
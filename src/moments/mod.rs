@@ -1,17 +1,19 @@
-use num_traits::ToPrimitive;
+use num_traits::float::FloatCore;
 #[cfg(feature = "serde1")] use serde::{Serialize, Deserialize};
 
 use super::{Estimate, Merge};
 
 include!("mean.rs");
 include!("variance.rs");
+include!("covariance.rs");
+include!("linear_regression.rs");
 #[cfg(any(feature = "std", feature = "libm"))]
 include!("skewness.rs");
 #[cfg(any(feature = "std", feature = "libm"))]
 include!("kurtosis.rs");
 
 /// Alias for `Variance`.
-pub type MeanWithError = Variance;
+pub type MeanWithError<T = f64> = Variance<T>;
 
 #[doc(hidden)]
 #[macro_export]
@@ -203,6 +205,64 @@ macro_rules! define_moments_common {
                     }
                 }
             }
+
+            /// Remove an observation that was previously added.
+            ///
+            /// Panics if the sample is empty.
+            #[inline]
+            pub fn remove(&mut self, x: f64) {
+                let mut single = $name::new();
+                single.add(x);
+                self.unmerge(&single);
+            }
+
+            /// Remove a sample that was previously merged into this one.
+            ///
+            /// This is the inverse of `merge`. Panics if `other` has more
+            /// samples than this one.
+            #[inline]
+            pub fn unmerge(&mut self, other: &$name) {
+                assert!(other.n <= self.n, "Cannot remove more samples than were added");
+                let n = self.n.to_f64().unwrap();
+                let n_b = other.n.to_f64().unwrap();
+                self.n -= other.n;
+                if self.n == 0 {
+                    *self = $name::new();
+                    return;
+                }
+                let n_a = self.n.to_f64().unwrap();
+                self.avg = (n * self.avg - n_b * other.avg) / n_a;
+                let delta = other.avg - self.avg;
+                let n_a_over_n = n_a / n;
+                let n_b_over_n = n_b / n;
+
+                // Solve the merge formula for the moments of the remaining
+                // sample, starting with the lowest order.
+                let factor_a = -n_b_over_n * delta;
+                let factor_b = n_a_over_n * delta;
+                let mut term_a = n_a * factor_a;
+                let mut term_b = n_b * factor_b;
+                for p in 2..=MAX_MOMENT {
+                    term_a *= factor_a;
+                    term_b *= factor_b;
+                    self.m[p - 2] -= other.m[p - 2] + term_a + term_b;
+
+                    let mut coeff_a = 1.;
+                    let mut coeff_b = 1.;
+                    let mut coeff_delta = 1.;
+                    let mut binom = IterBinomial::new(p as u64);
+                    binom.next().unwrap();
+                    for k in 1..(p - 1) {
+                        coeff_a *= -n_b_over_n;
+                        coeff_b *= n_a_over_n;
+                        coeff_delta *= delta;
+                        self.m[p - 2] -=
+                            binom.next().unwrap().to_f64().unwrap() *
+                            coeff_delta * (self.m[p - 2 - k] * coeff_a +
+                            other.m[p - 2 - k] * coeff_b);
+                    }
+                }
+            }
         }
 
         impl $crate::Merge for $name {
@@ -7,20 +7,30 @@ use num_traits::Float;
 #[cfg_attr(doc_cfg, doc(cfg(any(feature = "std", feature = "libm"))))]
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
-pub struct Skewness {
+pub struct Skewness<T = f64> {
     /// Estimator of mean and variance.
-    avg: MeanWithError,
+    avg: MeanWithError<T>,
     /// Intermediate sum of cubes for calculating the skewness.
-    sum_3: f64,
+    sum_3: T,
 }
 
-impl Skewness {
+impl Skewness<f64> {
     /// Create a new skewness estimator.
+    ///
+    /// Use `new_generic` for other floating point types than `f64`.
     #[inline]
-    pub fn new() -> Skewness {
+    pub fn new() -> Skewness<f64> {
+        Skewness::new_generic()
+    }
+}
+
+impl<T: Float + FloatCore> Skewness<T> {
+    /// Create a new skewness estimator.
+    #[inline]
+    pub fn new_generic() -> Skewness<T> {
         Skewness {
-            avg: MeanWithError::new(),
-            sum_3: 0.,
+            avg: MeanWithError::new_generic(),
+            sum_3: T::zero(),
         }
     }
 
@@ -38,14 +48,16 @@ impl Skewness {
     ///
     /// This is useful for avoiding unnecessary divisions in the inner loop.
     #[inline]
-    fn add_inner(&mut self, delta: f64, delta_n: f64) {
+    fn add_inner(&mut self, delta: T, delta_n: T) {
         // This algorithm was suggested by Terriberry.
         //
         // See https://en.wikipedia.org/wiki/Algorithms_for_calculating_variance.
-        let n = self.len().to_f64().unwrap();
-        let term = delta * delta_n * (n - 1.);
-        self.sum_3 += term * delta_n * (n - 2.)
-            - 3.*delta_n * self.avg.sum_2;
+        let n = T::from(self.len()).unwrap();
+        let two = T::from(2).unwrap();
+        let three = T::from(3).unwrap();
+        let term = delta * delta_n * (n - T::one());
+        self.sum_3 = self.sum_3 + term * delta_n * (n - two)
+            - three*delta_n * self.avg.sum_2;
         self.avg.add_inner(delta_n);
     }
 
@@ -59,7 +71,7 @@ impl Skewness {
     ///
     /// Returns 0 for an empty sample.
     #[inline]
-    pub fn mean(&self) -> f64 {
+    pub fn mean(&self) -> T {
         self.avg.mean()
     }
 
@@ -73,7 +85,7 @@ impl Skewness {
     ///
     /// This is an unbiased estimator of the variance of the population.
     #[inline]
-    pub fn sample_variance(&self) -> f64 {
+    pub fn sample_variance(&self) -> T {
         self.avg.sample_variance()
     }
 
@@ -81,64 +93,98 @@ impl Skewness {
     ///
     /// This is a biased estimator of the variance of the population.
     #[inline]
-    pub fn population_variance(&self) -> f64 {
+    pub fn population_variance(&self) -> T {
         self.avg.population_variance()
     }
 
     /// Estimate the standard error of the mean of the population.
     #[inline]
-    pub fn error_mean(&self) -> f64 {
+    pub fn error_mean(&self) -> T {
         self.avg.error()
     }
 
     /// Estimate the skewness of the population.
     #[inline]
-    pub fn skewness(&self) -> f64 {
-        if self.sum_3 == 0. {
-            return 0.;
+    pub fn skewness(&self) -> T {
+        if self.sum_3 == T::zero() {
+            return T::zero();
         }
-        let n = self.len().to_f64().unwrap();
+        let n = T::from(self.len()).unwrap();
         let sum_2 = self.avg.sum_2;
-        debug_assert_ne!(sum_2, 0.);
+        debug_assert!(sum_2 != T::zero());
         Float::sqrt(n) * self.sum_3 / Float::sqrt(sum_2*sum_2*sum_2)
     }
+
+    /// Remove an observation that was previously added.
+    ///
+    /// Panics if the sample is empty.
+    #[inline]
+    pub fn remove(&mut self, x: T) {
+        let mut single = Skewness::new_generic();
+        single.add(x);
+        self.unmerge(&single);
+    }
+
+    /// Remove a sample that was previously merged into this one.
+    ///
+    /// This is the inverse of `merge`. Panics if `other` has more samples
+    /// than this one.
+    #[inline]
+    pub fn unmerge(&mut self, other: &Skewness<T>) {
+        let len_total = T::from(self.len()).unwrap();
+        let len_other = T::from(other.len()).unwrap();
+        self.avg.unmerge(&other.avg);
+        if self.len() < 3 {
+            // Avoid rounding errors, less than 3 samples have no skewness.
+            self.sum_3 = T::zero();
+            return;
+        }
+        let len_self = T::from(self.len()).unwrap();
+        let three = T::from(3).unwrap();
+        let delta = other.mean() - self.mean();
+        let delta_n = delta / len_total;
+        self.sum_3 = self.sum_3 - other.sum_3
+            - delta*delta_n*delta_n * len_self*len_other*(len_self - len_other)
+            - three*delta_n * (len_self * other.avg.sum_2 - len_other * self.avg.sum_2);
+    }
 }
 
-impl Default for Skewness {
-    fn default() -> Skewness {
-        Skewness::new()
+impl<T: Float + FloatCore> Default for Skewness<T> {
+    fn default() -> Skewness<T> {
+        Skewness::new_generic()
     }
 }
 
-impl Estimate for Skewness {
+impl<T: Float + FloatCore> Estimate<T> for Skewness<T> {
     #[inline]
-    fn add(&mut self, x: f64) {
+    fn add(&mut self, x: T) {
         let delta = x - self.mean();
         self.increment();
-        let n = self.len().to_f64().unwrap();
+        let n = T::from(self.len()).unwrap();
         self.add_inner(delta, delta/n);
     }
 
     #[inline]
-    fn estimate(&self) -> f64 {
+    fn estimate(&self) -> T {
         self.skewness()
     }
 }
 
-impl Merge for Skewness {
+impl<T: Float + FloatCore> Merge for Skewness<T> {
     #[inline]
-    fn merge(&mut self, other: &Skewness) {
-        let len_self = self.len().to_f64().unwrap();
-        let len_other = other.len().to_f64().unwrap();
+    fn merge(&mut self, other: &Skewness<T>) {
+        let len_self = T::from(self.len()).unwrap();
+        let len_other = T::from(other.len()).unwrap();
         let len_total = len_self + len_other;
+        let three = T::from(3).unwrap();
         let delta = other.mean() - self.mean();
         let delta_n = delta / len_total;
-        self.sum_3 += other.sum_3
+        self.sum_3 = self.sum_3 + other.sum_3
             + delta*delta_n*delta_n * len_self*len_other*(len_self - len_other)
-            + 3.*delta_n * (len_self * other.avg.sum_2 - len_other * self.avg.sum_2);
+            + three*delta_n * (len_self * other.avg.sum_2 - len_other * self.avg.sum_2);
         self.avg.merge(&other.avg);
     }
 }
 
-impl_from_iterator!(Skewness);
-impl_from_par_iterator!(Skewness);
+impl_from_iterator!(Skewness<T>);
+impl_from_par_iterator!(Skewness<T>);
@@ -14,18 +14,28 @@
 /// ```
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
-pub struct Variance {
+pub struct Variance<T = f64> {
     /// Estimator of average.
-    avg: Mean,
+    avg: Mean<T>,
     /// Intermediate sum of squares for calculating the variance.
-    sum_2: f64,
+    sum_2: T,
 }
 
-impl Variance {
+impl Variance<f64> {
+    /// Create a new variance estimator.
+    ///
+    /// Use `new_generic` for other floating point types than `f64`.
+    #[inline]
+    pub fn new() -> Variance<f64> {
+        Variance::new_generic()
+    }
+}
+
+impl<T: FloatCore> Variance<T> {
     /// Create a new variance estimator.
     #[inline]
-    pub fn new() -> Variance {
-        Variance { avg: Mean::new(), sum_2: 0. }
+    pub fn new_generic() -> Variance<T> {
+        Variance { avg: Mean::new_generic(), sum_2: T::zero() }
     }
 
     /// Increment the sample size.
@@ -42,14 +52,14 @@ impl Variance {
     ///
     /// This is useful for avoiding unnecessary divisions in the inner loop.
     #[inline]
-    fn add_inner(&mut self, delta_n: f64) {
+    fn add_inner(&mut self, delta_n: T) {
         // This algorithm introduced by Welford in 1962 trades numerical
         // stability for a division inside the loop.
         //
         // See https://en.wikipedia.org/wiki/Algorithms_for_calculating_variance.
-        let n = self.avg.len().to_f64().unwrap();
+        let n = T::from(self.avg.len()).unwrap();
         self.avg.add_inner(delta_n);
-        self.sum_2 += delta_n * delta_n * n * (n - 1.);
+        self.sum_2 = self.sum_2 + delta_n * delta_n * n * (n - T::one());
     }
 
     /// Determine whether the sample is empty.
@@ -62,7 +72,7 @@ impl Variance {
     ///
     /// Returns 0 for an empty sample.
     #[inline]
-    pub fn mean(&self) -> f64 {
+    pub fn mean(&self) -> T {
         self.avg.mean()
     }
 
@@ -76,67 +86,126 @@ impl Variance {
     ///
     /// This is an unbiased estimator of the variance of the population.
     #[inline]
-    pub fn sample_variance(&self) -> f64 {
+    pub fn sample_variance(&self) -> T {
         if self.avg.len() < 2 {
-            return 0.;
+            return T::zero();
         }
-        self.sum_2 / (self.avg.len() - 1).to_f64().unwrap()
+        self.sum_2 / T::from(self.avg.len() - 1).unwrap()
     }
 
     /// Calculate the population variance of the sample.
     ///
     /// This is a biased estimator of the variance of the population.
     #[inline]
-    pub fn population_variance(&self) -> f64 {
+    pub fn population_variance(&self) -> T {
         let n = self.avg.len();
         if n < 2 {
-            return 0.;
+            return T::zero();
         }
-        self.sum_2 / n.to_f64().unwrap()
+        self.sum_2 / T::from(n).unwrap()
     }
 
     /// Estimate the variance of the mean of the population.
     #[inline]
-    pub fn variance_of_mean(&self) -> f64 {
+    pub fn variance_of_mean(&self) -> T {
         let n = self.avg.len();
         if n == 0 {
-            return 0.;
+            return T::zero();
         }
-        self.sample_variance() / n.to_f64().unwrap()
+        self.sample_variance() / T::from(n).unwrap()
     }
 
+    /// Remove an observation that was previously added.
+    ///
+    /// Panics if the sample is empty.
+    #[inline]
+    pub fn remove(&mut self, sample: T) {
+        // This is Welford's algorithm run backwards.
+        let prev_mean = self.mean();
+        self.avg.remove(sample);
+        if self.len() < 2 {
+            // Avoid rounding errors, a single sample has no variance.
+            self.sum_2 = T::zero();
+            return;
+        }
+        self.sum_2 = self.sum_2 - (sample - self.mean()) * (sample - prev_mean);
+        // Avoid negative values due to rounding errors.
+        self.sum_2 = self.sum_2.max(T::zero());
+    }
+
+    /// Remove a sample that was previously merged into this one.
+    ///
+    /// This is the inverse of `merge`. Panics if `other` has more samples
+    /// than this one.
+    ///
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use average::Variance;
+    ///
+    /// let sequence: &[f64] = &[1., 2., 3., 4., 5., 6., 7., 8., 9.];
+    /// let (left, right) = sequence.split_at(3);
+    /// let mut avg: Variance = sequence.iter().collect();
+    /// let avg_left: Variance = left.iter().collect();
+    /// let avg_right: Variance = right.iter().collect();
+    /// avg.unmerge(&avg_right);
+    /// assert_eq!(avg.len(), 3);
+    /// assert!((avg.mean() - avg_left.mean()).abs() < 1e-14);
+    /// assert!((avg.sample_variance() - avg_left.sample_variance()).abs() < 1e-13);
+    /// ```
+    #[inline]
+    pub fn unmerge(&mut self, other: &Variance<T>) {
+        // This is the algorithm by Chan et al. run backwards.
+        let len_total = T::from(self.len()).unwrap();
+        self.avg.unmerge(&other.avg);
+        if self.len() < 2 {
+            // Avoid rounding errors, a single sample has no variance.
+            self.sum_2 = T::zero();
+            return;
+        }
+        let len_self = T::from(self.len()).unwrap();
+        let len_other = T::from(other.len()).unwrap();
+        let delta = other.mean() - self.mean();
+        self.sum_2 = self.sum_2
+            - other.sum_2 - delta*delta * len_self * len_other / len_total;
+        // Avoid negative values due to rounding errors.
+        self.sum_2 = self.sum_2.max(T::zero());
+    }
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+impl<T: FloatCore + num_traits::Float> Variance<T> {
     /// Estimate the standard error of the mean of the population.
-    #[cfg(any(feature = "std", feature = "libm"))]
     #[cfg_attr(doc_cfg, doc(cfg(any(feature = "std", feature = "libm"))))]
     #[inline]
-    pub fn error(&self) -> f64 {
+    pub fn error(&self) -> T {
         num_traits::Float::sqrt(self.variance_of_mean())
     }
-
 }
 
-impl core::default::Default for Variance {
-    fn default() -> Variance {
-        Variance::new()
+impl<T: FloatCore> core::default::Default for Variance<T> {
+    fn default() -> Variance<T> {
+        Variance::new_generic()
     }
 }
 
-impl Estimate for Variance {
+impl<T: FloatCore> Estimate<T> for Variance<T> {
     #[inline]
-    fn add(&mut self, sample: f64) {
+    fn add(&mut self, sample: T) {
         self.increment();
         let delta_n = (sample - self.avg.mean())
-            / self.len().to_f64().unwrap();
+            / T::from(self.len()).unwrap();
         self.add_inner(delta_n);
     }
 
     #[inline]
-    fn estimate(&self) -> f64 {
+    fn estimate(&self) -> T {
         self.population_variance()
     }
 }
 
-impl Merge for Variance {
+impl<T: FloatCore> Merge for Variance<T> {
     /// Merge another sample into this one.
     ///
     ///
@@ -155,18 +224,19 @@ impl Merge for Variance {
     /// assert_eq!(avg_total.sample_variance(), avg_left.sample_variance());
     /// ```
     #[inline]
-    fn merge(&mut self, other: &Variance) {
+    fn merge(&mut self, other: &Variance<T>) {
         // This algorithm was proposed by Chan et al. in 1979.
         //
         // See https://en.wikipedia.org/wiki/Algorithms_for_calculating_variance.
-        let len_self = self.len().to_f64().unwrap();
-        let len_other = other.len().to_f64().unwrap();
+        let len_self = T::from(self.len()).unwrap();
+        let len_other = T::from(other.len()).unwrap();
         let len_total = len_self + len_other;
         let delta = other.mean() - self.mean();
         self.avg.merge(&other.avg);
-        self.sum_2 += other.sum_2 + delta*delta * len_self * len_other / len_total;
+        self.sum_2 = self.sum_2
+            + other.sum_2 + delta*delta * len_self * len_other / len_total;
     }
 }
 
-impl_from_iterator!(Variance);
-impl_from_par_iterator!(Variance);
+impl_from_iterator!(Variance<T>);
+impl_from_par_iterator!(Variance<T>);
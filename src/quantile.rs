@@ -1,115 +1,146 @@
 use core::cmp::min;
 
-use easy_cast::{Conv, ConvFloat};
-use num_traits::{ToPrimitive, Float};
-use float_ord::sort as sort_floats;
+use easy_cast::Conv;
+use num_traits::Float;
 #[cfg(feature = "serde1")] use serde::{Serialize, Deserialize};
 
-use super::Estimate;
+use super::{Estimate, Merge};
+
+/// Sort a slice of floats, placing `nan` after everything else.
+#[inline]
+fn sort_floats<T: Float>(v: &mut [T]) {
+    v.sort_unstable_by(|a, b| match (a.is_nan(), b.is_nan()) {
+        (false, false) => a.partial_cmp(b).unwrap(),
+        (a_nan, b_nan) => a_nan.cmp(&b_nan),
+    });
+}
+
+/// Round a float to the nearest integer.
+#[inline]
+pub(crate) fn round_to_i64<T: Float>(x: T) -> i64 {
+    x.round().to_i64().unwrap()
+}
 
 /// Estimate the p-quantile of a sequence of numbers ("population").
 ///
 /// The [P² algorithm][1] is employed. It uses constant space but the relative
 /// error of the quantile estimate is not bounded by a function of the number of
-/// samples. For algorithms that use growing space with bounded error, see the
-/// [`quantiles`][2] crate.
+/// samples. For bounded error, see [`TDigest`][2], which requires the `alloc`
+/// feature.
 ///
 /// It is recommended to use a different algorithm for discrete distributions
 /// and a small number of samples, or for quantiles close to a singularity in
 /// the distribution.
 ///
 /// [1]: http://www.cs.wustl.edu/~jain/papers/ftp/psqr.pdf
-/// [2]: https://crates.io/crates/quantiles
+/// [2]: ./struct.TDigest.html
 #[derive(Debug, Clone)]
 #[cfg_attr(doc_cfg, doc(cfg(any(feature = "std", feature = "libm"))))]
 #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
-pub struct Quantile {
+pub struct Quantile<T = f64> {
     /// Marker heights.
-    q: [f64; 5],
+    q: [T; 5],
     /// Marker positions.
     n: [i64; 5],
     /// Desired marker positions.
-    m: [f64; 5],
+    m: [T; 5],
     /// Increment in desired marker positions.
-    dm: [f64; 5],
+    dm: [T; 5],
+}
+
+impl Quantile<f64> {
+    /// Create a new p-quantile estimator.
+    ///
+    /// Panics if `p` is not between 0 and 1.
+    ///
+    /// Use `new_generic` for other floating point types than `f64`.
+    #[inline]
+    pub fn new(p: f64) -> Quantile<f64> {
+        Quantile::new_generic(p)
+    }
 }
 
-impl Quantile {
+impl<T: Float> Quantile<T> {
     /// Create a new p-quantile estimator.
     ///
     /// Panics if `p` is not between 0 and 1.
     #[inline]
-    pub fn new(p: f64) -> Quantile {
-        assert!((0. ..=  1.).contains(&p));
+    pub fn new_generic(p: T) -> Quantile<T> {
+        assert!(T::zero() <= p && p <= T::one());
+        let one = T::one();
+        let two = T::from(2).unwrap();
+        let three = T::from(3).unwrap();
+        let four = T::from(4).unwrap();
         Quantile {
-            q: [0.; 5],
+            q: [T::zero(); 5],
             n: [1, 2, 3, 4, 0],
-            m: [1., 1. + 2.*p, 1. + 4.*p, 3. + 2.*p, 5.],
-            dm: [0., p/2., p, (1. + p)/2., 1.],
+            m: [one, one + two*p, one + four*p, three + two*p, T::from(5).unwrap()],
+            dm: [T::zero(), p/two, p, (one + p)/two, one],
         }
     }
 
     /// Return the value of `p` for this p-quantile.
     #[inline]
-    pub fn p(&self) -> f64 {
+    pub fn p(&self) -> T {
         self.dm[2]
     }
 
     /// Parabolic prediction for marker height.
     #[inline]
-    fn parabolic(&self, i: usize, d: f64) -> f64 {
-        debug_assert_eq!(d.abs(), 1.);
-        let s = i64::conv_nearest(d);
-        self.q[i] + d / (self.n[i + 1] - self.n[i - 1]).to_f64().unwrap()
-            * ((self.n[i] - self.n[i - 1] + s).to_f64().unwrap()
+    fn parabolic(&self, i: usize, d: T) -> T {
+        debug_assert!(d.abs() == T::one());
+        let s = round_to_i64(d);
+        self.q[i] + d / T::from(self.n[i + 1] - self.n[i - 1]).unwrap()
+            * (T::from(self.n[i] - self.n[i - 1] + s).unwrap()
                * (self.q[i + 1] - self.q[i])
-               / (self.n[i + 1] - self.n[i]).to_f64().unwrap()
-               + (self.n[i + 1] - self.n[i] - s).to_f64().unwrap()
+               / T::from(self.n[i + 1] - self.n[i]).unwrap()
+               + T::from(self.n[i + 1] - self.n[i] - s).unwrap()
                * (self.q[i] - self.q[i - 1])
-               / (self.n[i] - self.n[i - 1]).to_f64().unwrap())
+               / T::from(self.n[i] - self.n[i - 1]).unwrap())
     }
 
     /// Linear prediction for marker height.
     #[inline]
-    fn linear(&self, i: usize, d: f64) -> f64 {
-        debug_assert_eq!(d.abs(), 1.);
-        let sum = if d < 0. { i - 1 } else { i + 1 };
+    fn linear(&self, i: usize, d: T) -> T {
+        debug_assert!(d.abs() == T::one());
+        let sum = if d < T::zero() { i - 1 } else { i + 1 };
         self.q[i] + d * (self.q[sum] - self.q[i])
-            / (self.n[sum] - self.n[i]).to_f64().unwrap()
+            / T::from(self.n[sum] - self.n[i]).unwrap()
     }
 
     /// Estimate the p-quantile of the population.
     ///
     /// Returns 0 for an empty sample.
     #[inline]
-    pub fn quantile(&self) -> f64 {
+    pub fn quantile(&self) -> T {
         if self.len() >= 5 {
             return self.q[2];
         }
 
         // Estimate quantile by sorting the sample.
         if self.is_empty() {
-            return 0.;
+            return T::zero();
         }
-        let mut heights: [f64; 4] = [
+        let mut heights: [T; 4] = [
             self.q[0], self.q[1], self.q[2], self.q[3]
         ];
         let len = usize::conv(self.len());
         debug_assert!(len < 5);
         sort_floats(&mut heights[..len]);
-        let desired_index = f64::conv(len) * self.p() - 1.;
+        let desired_index = T::from(len).unwrap() * self.p() - T::one();
         let mut index = desired_index.ceil();
-        if desired_index == index && index >= 0. {
-            let index = usize::conv_nearest(index);
+        if desired_index == index && index >= T::zero() {
+            let index = usize::conv(round_to_i64(index));
             debug_assert!(index < 5);
             if index < len - 1 {
                 // `q[index]` and `q[index + 1]` are equally valid estimates,
                 // by convention we take their average.
-                return 0.5*self.q[index] + 0.5*self.q[index + 1];
+                let half = T::from(0.5).unwrap();
+                return half*self.q[index] + half*self.q[index + 1];
             }
         }
-        index = index.max(0.);
-        let mut index = usize::conv_nearest(index);
+        index = index.max(T::zero());
+        let mut index = usize::conv(round_to_i64(index));
         debug_assert!(index < 5);
         index = min(index, len - 1);
         self.q[index]
@@ -127,18 +158,50 @@ impl Quantile {
     pub fn is_empty(&self) -> bool {
         self.len() == 0
     }
+
+    /// Approximate the number of samples less than or equal to `x` by linear
+    /// interpolation between the markers.
+    ///
+    /// Assumes that at least 5 samples were added.
+    #[inline]
+    fn rank(&self, x: T) -> T {
+        debug_assert!(self.len() >= 5);
+        if x < self.q[0] {
+            return T::zero();
+        }
+        if x >= self.q[4] {
+            return T::from(self.n[4]).unwrap();
+        }
+        let mut i = 1;
+        while x >= self.q[i] {
+            i += 1;
+        }
+        // Now `q[i - 1] <= x < q[i]`, so we cannot divide by zero.
+        let n_low = T::from(self.n[i - 1]).unwrap();
+        let n_high = T::from(self.n[i]).unwrap();
+        n_low + (n_high - n_low) * (x - self.q[i - 1]) / (self.q[i] - self.q[i - 1])
+    }
+
+    /// Add the samples stored by an estimator that saw less than 5 samples.
+    #[inline]
+    fn add_stored(&mut self, other: &Quantile<T>) {
+        debug_assert!(other.len() < 5);
+        for &x in &other.q[..usize::conv(other.len())] {
+            self.add(x);
+        }
+    }
 }
 
-impl core::default::Default for Quantile {
+impl<T: Float> core::default::Default for Quantile<T> {
     /// Create a new median estimator.
-    fn default() -> Quantile {
-        Quantile::new(0.5)
+    fn default() -> Quantile<T> {
+        Quantile::new_generic(T::from(0.5).unwrap())
     }
 }
 
-impl Estimate for Quantile {
+impl<T: Float> Estimate<T> for Quantile<T> {
     #[inline]
-    fn add(&mut self, x: f64) {
+    fn add(&mut self, x: T) {
         // n[4] is the sample size.
         if self.n[4] < 5 {
             self.q[usize::conv(self.n[4])] = x;
@@ -172,14 +235,14 @@ impl Estimate for Quantile {
             self.n[i] += 1;
         }
         for i in 0..5 {
-            self.m[i] += self.dm[i];
+            self.m[i] = self.m[i] + self.dm[i];
         }
 
         // Adjust height of markers.
         for i in 1..4 {
-            let d = self.m[i] - self.n[i].to_f64().unwrap();
-            if d >= 1. && self.n[i + 1] - self.n[i] > 1 ||
-               d <= -1. && self.n[i - 1] - self.n[i] < -1 {
+            let d = self.m[i] - T::from(self.n[i]).unwrap();
+            if d >= T::one() && self.n[i + 1] - self.n[i] > 1 ||
+               d <= -T::one() && self.n[i - 1] - self.n[i] < -1 {
                 let d = Float::signum(d);
                 let q_new = self.parabolic(i, d);
                 if self.q[i - 1] < q_new && q_new < self.q[i + 1] {
@@ -187,18 +250,107 @@ impl Estimate for Quantile {
                 } else {
                     self.q[i] = self.linear(i, d);
                 }
-                let delta = i64::conv_nearest(d);
+                let delta = round_to_i64(d);
                 debug_assert_eq!(delta.abs(), 1);
                 self.n[i] += delta;
             }
         }
     }
 
-    fn estimate(&self) -> f64 {
+    fn estimate(&self) -> T {
         self.quantile()
     }
 }
 
+impl<T: Float> Merge for Quantile<T> {
+    /// Merge another sample into this one.
+    ///
+    /// Panics if the estimators were created for different values of `p`.
+    ///
+    /// As long as one of the estimators saw less than 5 samples, it still
+    /// stores them exactly and they are simply added to the other estimator.
+    /// Otherwise, the number of samples less than a given value is
+    /// approximated for both estimators by linear interpolation between their
+    /// markers. The sum of both approximations is inverted at the desired
+    /// marker positions of the merged sample to obtain the new marker heights.
+    ///
+    /// This introduces an additional error of the same order as the error of
+    /// the P² algorithm itself, so the result generally differs from the
+    /// estimate obtained by adding all samples to one estimator. Like for the
+    /// P² algorithm, the error is not bounded.
+    ///
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use average::{Quantile, Merge, Estimate};
+    ///
+    /// let sequence: &[f64] = &[1., 2., 3., 4., 5., 6., 7., 8., 9.];
+    /// let (left, right) = sequence.split_at(3);
+    /// let mut q_left = Quantile::new(0.5);
+    /// for &x in left {
+    ///     q_left.add(x);
+    /// }
+    /// let mut q_right = Quantile::new(0.5);
+    /// for &x in right {
+    ///     q_right.add(x);
+    /// }
+    /// q_left.merge(&q_right);
+    /// assert_eq!(q_left.len(), 9);
+    /// assert!((q_left.quantile() - 5.).abs() < 0.5);
+    /// ```
+    fn merge(&mut self, other: &Quantile<T>) {
+        assert!(self.p() == other.p(),
+            "Both estimators must estimate the same quantile");
+        if other.len() < 5 {
+            self.add_stored(other);
+            return;
+        }
+        if self.len() < 5 {
+            let mut merged = other.clone();
+            merged.add_stored(self);
+            *self = merged;
+            return;
+        }
+
+        // Evaluate the combined approximate ranks at all marker heights.
+        let mut heights = [T::zero(); 10];
+        heights[..5].copy_from_slice(&self.q);
+        heights[5..].copy_from_slice(&other.q);
+        sort_floats(&mut heights);
+        let mut ranks = [T::zero(); 10];
+        for (r, &x) in ranks.iter_mut().zip(heights.iter()) {
+            *r = self.rank(x) + other.rank(x);
+        }
+
+        let len = self.n[4] + other.n[4];
+        let len_f = T::from(len).unwrap();
+        let mut n = [1, 0, 0, 0, len];
+        let mut q = [heights[0], T::zero(), T::zero(), T::zero(), heights[9]];
+        for i in 0..5 {
+            self.m[i] = T::one() + (len_f - T::one()) * self.dm[i];
+        }
+        for i in 1..4 {
+            // Marker positions have to be strictly increasing.
+            n[i] = round_to_i64(self.m[i])
+                .max(n[i - 1] + 1)
+                .min(len - 4 + i64::conv(i));
+
+            // Invert the combined ranks by linear interpolation.
+            let target = T::from(n[i]).unwrap();
+            let j = ranks.iter().position(|&r| r >= target).unwrap_or(9);
+            q[i] = if j == 0 {
+                heights[0]
+            } else {
+                heights[j - 1] + (heights[j] - heights[j - 1])
+                    * (target - ranks[j - 1]) / (ranks[j] - ranks[j - 1])
+            };
+        }
+        self.n = n;
+        self.q = q;
+    }
+}
+
 #[test]
 fn reference() {
     let observations = [
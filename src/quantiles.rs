@@ -0,0 +1,305 @@
+use core::cmp::min;
+
+use easy_cast::Conv;
+use num_traits::Float;
+#[cfg(feature = "serde1")] use serde::{Serialize, Deserialize};
+#[cfg(feature = "serde1")] use serde_big_array::BigArray;
+
+use super::quantile::round_to_i64;
+
+/// Determine whether `a` is sorted before `b`, placing `nan` after everything
+/// else.
+#[inline]
+fn less<T: Float>(a: T, b: T) -> bool {
+    a < b || (b.is_nan() && !a.is_nan())
+}
+
+/// A marker of the extended P² algorithm.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+struct Marker<T> {
+    /// Marker height.
+    q: T,
+    /// Marker position.
+    n: i64,
+    /// Desired marker position.
+    m: T,
+    /// Increment in desired marker position.
+    dm: T,
+}
+
+impl<T: Float> Marker<T> {
+    /// Create the `i`th of `len` markers, tracking the `f`-quantile.
+    #[inline]
+    fn new(i: usize, len: usize, f: T) -> Marker<T> {
+        Marker {
+            q: T::zero(),
+            n: i64::conv(i) + 1,
+            m: T::one() + T::from(len - 1).unwrap() * f,
+            dm: f,
+        }
+    }
+}
+
+/// Parabolic prediction for the height of marker `mid`.
+#[inline]
+fn parabolic<T: Float>(low: &Marker<T>, mid: &Marker<T>, high: &Marker<T>, d: T) -> T {
+    debug_assert!(d.abs() == T::one());
+    let s = round_to_i64(d);
+    mid.q + d / T::from(high.n - low.n).unwrap()
+        * (T::from(mid.n - low.n + s).unwrap()
+           * (high.q - mid.q)
+           / T::from(high.n - mid.n).unwrap()
+           + T::from(high.n - mid.n - s).unwrap()
+           * (mid.q - low.q)
+           / T::from(mid.n - low.n).unwrap())
+}
+
+/// Linear prediction for the height of marker `mid`.
+#[inline]
+fn linear<T: Float>(low: &Marker<T>, mid: &Marker<T>, high: &Marker<T>, d: T) -> T {
+    debug_assert!(d.abs() == T::one());
+    let other = if d < T::zero() { low } else { high };
+    mid.q + d * (other.q - mid.q) / T::from(other.n - mid.n).unwrap()
+}
+
+/// Estimate several p-quantiles of a sequence of numbers ("population") at
+/// once.
+///
+/// The [extended P² algorithm][1] is employed. It tracks `2K + 3` markers for
+/// `K` quantiles, which is cheaper than using `K` separate [`Quantile`]
+/// estimators with 5 markers each. Like for the P² algorithm, the error of the
+/// estimates is not bounded. For a single quantile, the estimates are identical
+/// to those of [`Quantile`] once five samples were added.
+///
+/// [1]: https://doi.org/10.1145/29380.29381
+/// [`Quantile`]: ./struct.Quantile.html
+///
+///
+/// ## Example
+///
+/// ```
+/// use average::Quantiles;
+///
+/// let mut q = Quantiles::new([0.5, 0.9, 0.99]);
+/// for i in 1..1001 {
+///     q.add(f64::from(i));
+/// }
+/// println!("The median is {}, the 99th percentile is {}.",
+///     q.quantile(0), q.quantile(2));
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(doc_cfg, doc(cfg(any(feature = "std", feature = "libm"))))]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde1", serde(bound(
+    serialize = "T: Serialize + serde::de::DeserializeOwned",
+    deserialize = "T: Serialize + Deserialize<'de>")))]
+pub struct Quantiles<const K: usize, T = f64> {
+    /// Marker of the minimum.
+    min: Marker<T>,
+    /// For each quantile, the marker halfway below it and its own marker.
+    #[cfg_attr(feature = "serde1", serde(with = "BigArray"))]
+    inner: [[Marker<T>; 2]; K],
+    /// Marker halfway between the largest quantile and the maximum.
+    upper: Marker<T>,
+    /// Marker of the maximum.
+    max: Marker<T>,
+    /// Sample size.
+    len: u64,
+}
+
+impl<const K: usize> Quantiles<K, f64> {
+    /// Create a new estimator of the given p-quantiles.
+    ///
+    /// Panics if `ps` is empty, not strictly increasing or not between 0 and 1.
+    ///
+    /// Use `new_generic` for other floating point types than `f64`.
+    #[inline]
+    pub fn new(ps: [f64; K]) -> Quantiles<K, f64> {
+        Quantiles::new_generic(ps)
+    }
+}
+
+impl<const K: usize, T: Float> Quantiles<K, T> {
+    /// The number of markers.
+    const MARKERS: usize = 2*K + 3;
+
+    /// Create a new estimator of the given p-quantiles.
+    ///
+    /// Panics if `ps` is empty, not strictly increasing or not between 0 and 1.
+    #[inline]
+    pub fn new_generic(ps: [T; K]) -> Quantiles<K, T> {
+        assert!(K > 0, "At least one quantile must be estimated");
+        let len = Self::MARKERS;
+        let half = T::from(0.5).unwrap();
+        let mut below = T::zero();
+        let mut inner = [[Marker::new(0, len, T::zero()); 2]; K];
+        for (j, (markers, &p)) in inner.iter_mut().zip(ps.iter()).enumerate() {
+            assert!(T::zero() <= p && p <= T::one());
+            assert!(j == 0 || below < p, "The quantiles must be strictly increasing");
+            markers[0] = Marker::new(2*j + 1, len, half * (below + p));
+            markers[1] = Marker::new(2*j + 2, len, p);
+            below = p;
+        }
+        Quantiles {
+            min: Marker::new(0, len, T::zero()),
+            inner,
+            upper: Marker::new(2*K + 1, len, half * (below + T::one())),
+            max: Marker::new(2*K + 2, len, T::one()),
+            len: 0,
+        }
+    }
+
+    /// Return the `i`th marker.
+    #[inline]
+    fn marker(&self, i: usize) -> &Marker<T> {
+        match i {
+            0 => &self.min,
+            i if i <= 2*K => &self.inner[(i - 1) / 2][(i - 1) % 2],
+            i if i == 2*K + 1 => &self.upper,
+            _ => &self.max,
+        }
+    }
+
+    /// Return the `i`th marker for modification.
+    #[inline]
+    fn marker_mut(&mut self, i: usize) -> &mut Marker<T> {
+        match i {
+            0 => &mut self.min,
+            i if i <= 2*K => &mut self.inner[(i - 1) / 2][(i - 1) % 2],
+            i if i == 2*K + 1 => &mut self.upper,
+            _ => &mut self.max,
+        }
+    }
+
+    /// Return the value of `p` for the `i`th p-quantile.
+    ///
+    /// Panics if `i` is not smaller than `K`.
+    #[inline]
+    pub fn p(&self, i: usize) -> T {
+        self.inner[i][1].dm
+    }
+
+    /// Add an observation sampled from the population.
+    #[inline]
+    pub fn add(&mut self, x: T) {
+        let markers = Self::MARKERS;
+        if self.len < u64::conv(markers) {
+            // Store the sample, keeping the stored samples sorted.
+            let mut i = usize::conv(self.len);
+            self.len += 1;
+            while i > 0 && less(x, self.marker(i - 1).q) {
+                self.marker_mut(i).q = self.marker(i - 1).q;
+                i -= 1;
+            }
+            self.marker_mut(i).q = x;
+            return;
+        }
+        self.len += 1;
+
+        // Find cell k.
+        let k = if x < self.min.q {
+            self.min.q = x;
+            0
+        } else {
+            let mut k = markers - 1;
+            for i in 1..markers {
+                if x < self.marker(i).q {
+                    k = i;
+                    break;
+                }
+            }
+            if self.max.q < x {
+                self.max.q = x;
+            }
+            k
+        };
+
+        // Increment all positions greater than k.
+        for i in 0..markers {
+            let marker = self.marker_mut(i);
+            if i >= k {
+                marker.n += 1;
+            }
+            marker.m = marker.m + marker.dm;
+        }
+
+        // Adjust height of markers.
+        for i in 1..(markers - 1) {
+            let low = *self.marker(i - 1);
+            let mid = *self.marker(i);
+            let high = *self.marker(i + 1);
+            let d = mid.m - T::from(mid.n).unwrap();
+            if d >= T::one() && high.n - mid.n > 1 ||
+               d <= -T::one() && low.n - mid.n < -1 {
+                let d = Float::signum(d);
+                let q_new = parabolic(&low, &mid, &high, d);
+                let q = if low.q < q_new && q_new < high.q {
+                    q_new
+                } else {
+                    linear(&low, &mid, &high, d)
+                };
+                let delta = round_to_i64(d);
+                debug_assert_eq!(delta.abs(), 1);
+                let marker = self.marker_mut(i);
+                marker.q = q;
+                marker.n += delta;
+            }
+        }
+    }
+
+    /// Estimate the `i`th p-quantile of the population.
+    ///
+    /// Returns 0 for an empty sample. Panics if `i` is not smaller than `K`.
+    #[inline]
+    pub fn quantile(&self, i: usize) -> T {
+        let markers = self.inner[i];
+        if self.len >= u64::conv(Self::MARKERS) {
+            return markers[1].q;
+        }
+
+        // Estimate quantile from the sorted sample.
+        if self.is_empty() {
+            return T::zero();
+        }
+        let len = usize::conv(self.len);
+        let desired_index = T::from(len).unwrap() * self.p(i) - T::one();
+        let mut index = desired_index.ceil();
+        if desired_index == index && index >= T::zero() {
+            let index = usize::conv(round_to_i64(index));
+            if index < len - 1 {
+                // `q[index]` and `q[index + 1]` are equally valid estimates,
+                // by convention we take their average.
+                let half = T::from(0.5).unwrap();
+                return half*self.marker(index).q + half*self.marker(index + 1).q;
+            }
+        }
+        index = index.max(T::zero());
+        let index = min(usize::conv(round_to_i64(index)), len - 1);
+        self.marker(index).q
+    }
+
+    /// Estimate all p-quantiles of the population.
+    ///
+    /// Returns zeros for an empty sample.
+    #[inline]
+    pub fn quantiles(&self) -> [T; K] {
+        let mut result = [T::zero(); K];
+        for (i, q) in result.iter_mut().enumerate() {
+            *q = self.quantile(i);
+        }
+        result
+    }
+
+    /// Return the sample size.
+    #[inline]
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Determine whether the sample is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
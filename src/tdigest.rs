@@ -0,0 +1,413 @@
+use alloc::vec::Vec;
+
+use num_traits::Float;
+#[cfg(feature = "serde1")] use serde::{Serialize, Deserialize};
+
+use super::{Estimate, Merge};
+
+/// A cluster of samples, represented by their mean and their number.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+struct Centroid<T> {
+    /// Mean of the samples.
+    mean: T,
+    /// Number of samples.
+    weight: u64,
+}
+
+impl<T: Float> Centroid<T> {
+    /// Merge another centroid into this one.
+    #[inline]
+    fn absorb(&mut self, other: &Centroid<T>) {
+        self.weight += other.weight;
+        self.mean = self.mean + (other.mean - self.mean)
+            * T::from(other.weight).unwrap() / T::from(self.weight).unwrap();
+    }
+}
+
+/// Estimate quantiles and the cumulative distribution function of a sequence
+/// of numbers ("population") using a t-digest.
+///
+/// The [t-digest][1] clusters the samples into centroids, which are small near
+/// the tails of the distribution and large near the median. The size of the
+/// clusters is controlled by the compression parameter: a larger compression
+/// results in more centroids, using more memory but giving more accurate
+/// estimates. In contrast to [`Quantile`], the relative error of quantile
+/// estimates close to 0 or 1 is bounded, and all quantiles can be queried
+/// after the samples were added.
+///
+/// Samples are buffered and merged into the centroids in batches. The memory
+/// usage is proportional to the compression and grows logarithmically with
+/// the number of samples, because the scale function is normalized by
+/// `ln(n / compression)`. `nan` samples are ignored.
+///
+/// Collecting an iterator into a `TDigest` uses the default compression of
+/// 100, and [`Estimate::estimate`] estimates the median.
+///
+/// [1]: https://arxiv.org/abs/1902.04023
+/// [`Quantile`]: ./struct.Quantile.html
+/// [`Estimate::estimate`]: ./trait.Estimate.html#tymethod.estimate
+///
+///
+/// ## Example
+///
+/// ```
+/// use average::{TDigest, Estimate};
+///
+/// let mut d = TDigest::new();
+/// for i in 1..1001 {
+///     d.add(f64::from(i));
+/// }
+/// println!("The 99th percentile is {}.", d.quantile(0.99));
+/// println!("{}% of the samples are at most 250.", 100. * d.cdf(250.));
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(doc_cfg, doc(cfg(all(feature = "alloc", any(feature = "std", feature = "libm")))))]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+pub struct TDigest<T = f64> {
+    /// Compression parameter.
+    compression: T,
+    /// Merged centroids, sorted by their means.
+    centroids: Vec<Centroid<T>>,
+    /// Centroids that were not merged yet, not sorted.
+    buffer: Vec<Centroid<T>>,
+    /// Smallest sample.
+    min: T,
+    /// Largest sample.
+    max: T,
+    /// Sample size.
+    len: u64,
+}
+
+impl TDigest<f64> {
+    /// Create a new t-digest with a compression of 100.
+    ///
+    /// Use `new_generic` for other floating point types than `f64`.
+    #[inline]
+    pub fn new() -> TDigest<f64> {
+        TDigest::new_generic()
+    }
+}
+
+impl<T: Float> TDigest<T> {
+    /// Create a new t-digest with a compression of 100.
+    #[inline]
+    pub fn new_generic() -> TDigest<T> {
+        TDigest::with_compression(T::from(100).unwrap())
+    }
+
+    /// Create a new t-digest with the given compression.
+    ///
+    /// The number of centroids is roughly proportional to the compression.
+    /// Panics if the compression is not positive and finite.
+    #[inline]
+    pub fn with_compression(compression: T) -> TDigest<T> {
+        assert!(compression > T::zero() && compression.is_finite(),
+            "Compression must be positive and finite");
+        TDigest {
+            compression,
+            centroids: Vec::new(),
+            buffer: Vec::new(),
+            min: T::infinity(),
+            max: T::neg_infinity(),
+            len: 0,
+        }
+    }
+
+    /// Return the compression parameter.
+    #[inline]
+    pub fn compression(&self) -> T {
+        self.compression
+    }
+
+    /// Return the sample size.
+    #[inline]
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Determine whether the sample is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Return the number of buffered centroids that trigger a compression.
+    #[inline]
+    fn buffer_capacity(&self) -> usize {
+        (T::from(5).unwrap() * self.compression).ceil().to_usize().unwrap()
+    }
+
+    /// Map the quantile `q` to the scales of the centroid sizes.
+    ///
+    /// Adjacent centroids may be merged as long as they span at most one unit
+    /// on both scales. The first one is the scale function `k_1` by Dunning,
+    /// which keeps the centroids near the median small. The second one is the
+    /// logarithmic scale function `k_3`, which makes the centroid sizes
+    /// proportional to the distance of their quantiles to 0 or 1 and thus
+    /// bounds the relative error in the tails.
+    #[inline]
+    fn scale(&self, q: T) -> (T, T) {
+        let one = T::one();
+        let two = T::from(2).unwrap();
+        let pi = T::from(core::f64::consts::PI).unwrap();
+        let q = q.min(one);
+        let k_1 = self.compression / (two * pi) * (two * q - one).asin();
+
+        let n = T::from(self.len).unwrap();
+        let normalizer = T::from(4).unwrap() * (n / self.compression).max(one).ln()
+            + T::from(21).unwrap();
+        let k_3 = if q <= T::from(0.5).unwrap() {
+            (two * q).ln()
+        } else {
+            -(two * (one - q)).ln()
+        };
+        (k_1, self.compression / normalizer * k_3)
+    }
+
+    /// Merge all buffered samples into the centroids.
+    ///
+    /// This happens automatically when the buffer is full. Estimating a
+    /// statistic while samples are buffered compresses a temporary copy, which
+    /// can be avoided by calling this method first.
+    pub fn compress(&mut self) {
+        if self.buffer.is_empty() {
+            return;
+        }
+        let mut all = core::mem::take(&mut self.centroids);
+        all.append(&mut self.buffer);
+        // `nan` is never added, so the means can be compared.
+        all.sort_by(|a, b| a.mean.partial_cmp(&b.mean).unwrap());
+
+        let total = T::from(self.len).unwrap();
+        let mut all = all.into_iter();
+        let mut current = all.next().unwrap();
+        let mut weight_before = 0;
+        let mut k_low = self.scale(T::zero());
+        for c in all {
+            let q = T::from(weight_before + current.weight + c.weight).unwrap() / total;
+            let k = self.scale(q);
+            if k.0 - k_low.0 <= T::one() && k.1 - k_low.1 <= T::one() {
+                current.absorb(&c);
+            } else {
+                weight_before += current.weight;
+                self.centroids.push(current);
+                k_low = self.scale(T::from(weight_before).unwrap() / total);
+                current = c;
+            }
+        }
+        self.centroids.push(current);
+    }
+
+    /// Call `f` with a version of this t-digest without buffered samples.
+    #[inline]
+    fn compressed<R>(&self, f: impl FnOnce(&TDigest<T>) -> R) -> R {
+        if self.buffer.is_empty() {
+            f(self)
+        } else {
+            let mut compressed = self.clone();
+            compressed.compress();
+            f(&compressed)
+        }
+    }
+
+    /// Estimate the p-quantile of the population.
+    ///
+    /// Returns 0 for an empty sample. Panics if `p` is not between 0 and 1.
+    pub fn quantile(&self, p: T) -> T {
+        assert!(T::zero() <= p && p <= T::one());
+        if self.is_empty() {
+            return T::zero();
+        }
+        self.compressed(|d| d.quantile_compressed(p))
+    }
+
+    /// Estimate the p-quantile, assuming no samples are buffered.
+    fn quantile_compressed(&self, p: T) -> T {
+        debug_assert!(self.buffer.is_empty());
+        let one = T::one();
+        let half = T::from(0.5).unwrap();
+        let n = T::from(self.len).unwrap();
+        let index = p * n;
+        if index < one {
+            return self.min;
+        }
+        if index > n - one {
+            return self.max;
+        }
+
+        // The extreme samples are known exactly, interpolate between them and
+        // the outermost centroids.
+        let first = self.centroids[0];
+        let first_weight = T::from(first.weight).unwrap();
+        if first.weight > 1 && index < half * first_weight {
+            return self.min + (index - one) / (half * first_weight - one)
+                * (first.mean - self.min);
+        }
+        let last = self.centroids[self.centroids.len() - 1];
+        let last_weight = T::from(last.weight).unwrap();
+        if last.weight > 1 && n - index < half * last_weight {
+            return self.max - (n - index - one) / (half * last_weight - one)
+                * (self.max - last.mean);
+        }
+
+        // Otherwise, interpolate between the centers of adjacent centroids.
+        let mut weight_so_far = half * first_weight;
+        for pair in self.centroids.windows(2) {
+            let dw = half * T::from(pair[0].weight + pair[1].weight).unwrap();
+            if weight_so_far + dw > index {
+                let z_low = index - weight_so_far;
+                let z_high = weight_so_far + dw - index;
+                return (pair[0].mean * z_high + pair[1].mean * z_low) / dw;
+            }
+            weight_so_far = weight_so_far + dw;
+        }
+        last.mean
+    }
+
+    /// Estimate the cumulative distribution function of the population at
+    /// `x`, i.e. the fraction of the population less than or equal to `x`.
+    ///
+    /// Returns 0 for an empty sample and `nan` if `x` is `nan`.
+    pub fn cdf(&self, x: T) -> T {
+        if x.is_nan() {
+            return x;
+        }
+        if self.is_empty() || x < self.min {
+            return T::zero();
+        }
+        if x >= self.max {
+            return T::one();
+        }
+        self.compressed(|d| d.cdf_compressed(x))
+    }
+
+    /// Estimate the cumulative distribution function at `x`, assuming no
+    /// samples are buffered and `min <= x < max`.
+    fn cdf_compressed(&self, x: T) -> T {
+        debug_assert!(self.buffer.is_empty());
+        let one = T::one();
+        let half = T::from(0.5).unwrap();
+        let n = T::from(self.len).unwrap();
+
+        // This inverts the interpolation done by `quantile`.
+        let first = self.centroids[0];
+        if x < first.mean {
+            let w = half * T::from(first.weight).unwrap();
+            return (one + (x - self.min) / (first.mean - self.min) * (w - one)) / n;
+        }
+        let last = self.centroids[self.centroids.len() - 1];
+        if x >= last.mean {
+            let w = half * T::from(last.weight).unwrap();
+            return one - (one + (self.max - x) / (self.max - last.mean) * (w - one)) / n;
+        }
+        let mut weight_so_far = half * T::from(first.weight).unwrap();
+        for pair in self.centroids.windows(2) {
+            let dw = half * T::from(pair[0].weight + pair[1].weight).unwrap();
+            if x < pair[1].mean {
+                // `pair[0].mean <= x < pair[1].mean`, so we cannot divide by
+                // zero.
+                return (weight_so_far
+                    + dw * (x - pair[0].mean) / (pair[1].mean - pair[0].mean)) / n;
+            }
+            weight_so_far = weight_so_far + dw;
+        }
+        unreachable!()
+    }
+
+    /// Estimate the mean of the population, excluding the samples below the
+    /// `lower` quantile and above the `upper` quantile.
+    ///
+    /// Returns 0 for an empty sample. Panics unless
+    /// `0 <= lower < upper <= 1`.
+    pub fn trimmed_mean(&self, lower: T, upper: T) -> T {
+        assert!(T::zero() <= lower && lower < upper && upper <= T::one());
+        if self.is_empty() {
+            return T::zero();
+        }
+        self.compressed(|d| d.trimmed_mean_compressed(lower, upper))
+    }
+
+    /// Estimate the trimmed mean, assuming no samples are buffered.
+    fn trimmed_mean_compressed(&self, lower: T, upper: T) -> T {
+        debug_assert!(self.buffer.is_empty());
+        let n = T::from(self.len).unwrap();
+        let low = lower * n;
+        let high = upper * n;
+        let mut sum = T::zero();
+        let mut weight = T::zero();
+        let mut weight_before = T::zero();
+        for c in &self.centroids {
+            let weight_after = weight_before + T::from(c.weight).unwrap();
+            let overlap = weight_after.min(high) - weight_before.max(low);
+            if overlap > T::zero() {
+                sum = sum + overlap * c.mean;
+                weight = weight + overlap;
+            }
+            weight_before = weight_after;
+        }
+        sum / weight
+    }
+}
+
+impl<T: Float> core::default::Default for TDigest<T> {
+    fn default() -> TDigest<T> {
+        TDigest::new_generic()
+    }
+}
+
+impl<T: Float> Estimate<T> for TDigest<T> {
+    #[inline]
+    fn add(&mut self, x: T) {
+        if x.is_nan() {
+            return;
+        }
+        self.min = self.min.min(x);
+        self.max = self.max.max(x);
+        self.len += 1;
+        self.buffer.push(Centroid { mean: x, weight: 1 });
+        if self.buffer.len() >= self.buffer_capacity() {
+            self.compress();
+        }
+    }
+
+    #[inline]
+    fn estimate(&self) -> T {
+        self.quantile(T::from(0.5).unwrap())
+    }
+}
+
+impl<T: Float> Merge for TDigest<T> {
+    /// Merge another sample into this one.
+    ///
+    /// The compression of this t-digest is kept.
+    ///
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use average::{TDigest, Merge};
+    ///
+    /// let sequence: &[f64] = &[1., 2., 3., 4., 5., 6., 7., 8., 9.];
+    /// let (left, right) = sequence.split_at(3);
+    /// let mut d_left: TDigest = left.iter().collect();
+    /// let d_right: TDigest = right.iter().collect();
+    /// d_left.merge(&d_right);
+    /// assert_eq!(d_left.len(), 9);
+    /// assert_eq!(d_left.quantile(0.5), 5.);
+    /// ```
+    fn merge(&mut self, other: &TDigest<T>) {
+        if other.is_empty() {
+            return;
+        }
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.len += other.len;
+        self.buffer.extend_from_slice(&other.centroids);
+        self.buffer.extend_from_slice(&other.buffer);
+        self.compress();
+    }
+}
+
+impl_from_iterator!(TDigest<T>);
+impl_from_par_iterator!(TDigest<T>);
@@ -1,10 +1,13 @@
 /// Estimate a statistic of a sequence of numbers ("population").
-pub trait Estimate {
+///
+/// The type parameter is the floating point type of the observations and
+/// defaults to `f64`.
+pub trait Estimate<T = f64> {
     /// Add an observation sampled from the population.
-    fn add(&mut self, x: f64);
+    fn add(&mut self, x: T);
 
     /// Estimate the statistic of the population.
-    fn estimate(&self) -> f64;
+    fn estimate(&self) -> T;
 }
 
 /// Merge with another estimator.
@@ -54,13 +57,22 @@ pub trait Histogram:
     /// Return the bins of the histogram.
     fn bins(&self) -> &[u64];
 
+    /// Return the number of samples that did not fall into any bin but were
+    /// counted nonetheless.
+    ///
+    /// These samples are included in the normalization of the variances.
+    #[inline]
+    fn outliers(&self) -> u64 {
+        0
+    }
+
     /// Estimate the variance for the given bin.
     ///
     /// The square root of this estimates the error of the bin count.
     #[inline]
     fn variance(&self, bin: usize) -> f64 {
         let count = self.bins()[bin];
-        let sum: u64 = self.bins().iter().sum();
+        let sum: u64 = self.bins().iter().sum::<u64>() + self.outliers();
         multinomal_variance(count as f64, 1./(sum as f64))
     }
 
@@ -87,12 +99,138 @@ pub trait Histogram:
     /// This is more efficient than calling `variance()` for each bin.
     #[inline]
     fn variances(&self) -> IterVariances<<&Self as IntoIterator>::IntoIter> {
-        let sum: u64 = self.bins().iter().sum();
+        let sum: u64 = self.bins().iter().sum::<u64>() + self.outliers();
         IterVariances {
             histogram_iter: self.into_iter(),
             sum_inv: 1./(sum as f64)
         }
     }
+
+    /// Return the number of samples in the bins.
+    ///
+    /// Outliers are not included.
+    #[inline]
+    fn total(&self) -> u64 {
+        self.bins().iter().sum()
+    }
+
+    /// Estimate the mean of the samples in the bins, assuming they are at the
+    /// bin centers.
+    ///
+    /// The result is not finite if there are samples in bins of infinite
+    /// width. Returns 0 if the bins are empty.
+    fn mean(&self) -> f64 {
+        let total = self.total();
+        if total == 0 {
+            return 0.;
+        }
+        let sum: f64 = self.into_iter()
+            .filter(|&(_, count)| count > 0)
+            .map(|((a, b), count)| 0.5 * (a + b) * count as f64)
+            .sum();
+        sum / total as f64
+    }
+
+    /// Estimate the sample variance of the samples in the bins, assuming they
+    /// are at the bin centers.
+    ///
+    /// This is named `sample_variance` rather than `variance`, because
+    /// `variance(bin)` already estimates the error of a bin count. Returns 0 if
+    /// there are less than two samples.
+    fn sample_variance(&self) -> f64 {
+        let total = self.total();
+        if total < 2 {
+            return 0.;
+        }
+        let mean = self.mean();
+        let sum: f64 = self.into_iter()
+            .filter(|&(_, count)| count > 0)
+            .map(|((a, b), count)| {
+                let delta = 0.5 * (a + b) - mean;
+                delta * delta * count as f64
+            })
+            .sum();
+        sum / (total - 1) as f64
+    }
+
+    /// Estimate the p-quantile of the samples in the bins, interpolating
+    /// linearly within the bins.
+    ///
+    /// For bins of infinite width, their finite edge is returned. Returns 0 if
+    /// the bins are empty. Panics if `p` is not between 0 and 1.
+    fn quantile(&self, p: f64) -> f64 {
+        assert!((0. ..=1.).contains(&p), "p must be between 0 and 1");
+        let target = p * self.total() as f64;
+        let mut cumulative = 0.;
+        for ((a, b), count) in self {
+            if count == 0 {
+                continue;
+            }
+            let count = count as f64;
+            if cumulative + count >= target {
+                if !(b - a).is_finite() {
+                    // Interpolating is not possible, use the finite edge.
+                    return if a.is_finite() { a } else { b };
+                }
+                return a + (target - cumulative) / count * (b - a);
+            }
+            cumulative += count;
+        }
+        0.
+    }
+
+    /// Estimate the fraction of the samples in the bins that are at most `x`,
+    /// interpolating linearly within the bins.
+    ///
+    /// Bins of infinite width only contribute if `x` is beyond them. Returns 0
+    /// if the bins are empty.
+    fn cdf(&self, x: f64) -> f64 {
+        let total = self.total();
+        if total == 0 {
+            return 0.;
+        }
+        let mut cumulative = 0.;
+        for ((a, b), count) in self {
+            if b <= x {
+                cumulative += count as f64;
+            } else if a < x {
+                let fraction = (x - a) / (b - a);
+                if fraction.is_finite() {
+                    cumulative += fraction * count as f64;
+                }
+            }
+        }
+        cumulative / total as f64
+    }
+
+    /// Return the index of the bin with the largest count.
+    ///
+    /// If several bins have the largest count, the first one is returned.
+    /// Returns `None` if the bins are empty.
+    fn mode_bin(&self) -> Option<usize> {
+        let mut mode = None;
+        let mut max = 0;
+        for (i, &count) in self.bins().iter().enumerate() {
+            if count > max {
+                mode = Some(i);
+                max = count;
+            }
+        }
+        mode
+    }
+
+    /// Render the histogram as text, with one line per bin.
+    ///
+    /// See [`HistogramDisplay`](struct.HistogramDisplay.html) for the
+    /// available options.
+    #[cfg(all(feature = "alloc", any(feature = "std", feature = "libm")))]
+    #[cfg_attr(doc_cfg, doc(cfg(all(feature = "alloc", any(feature = "std", feature = "libm")))))]
+    #[inline]
+    fn display(&self) -> crate::HistogramDisplay<'_, Self>
+        where Self: Sized
+    {
+        crate::HistogramDisplay::new(self)
+    }
 }
 
 /// Iterate over the bins normalized by bin width.
@@ -0,0 +1,297 @@
+use alloc::vec;
+use alloc::vec::Vec;
+#[cfg(feature = "serde1")] use core::convert::TryFrom;
+
+#[cfg(feature = "serde1")] use serde::{Serialize, Deserialize};
+
+use super::{Merge, InvalidRangeError, SampleOutOfRangeError};
+use super::histogram::{self, Spacing};
+
+/// A histogram of weighted samples with a number of bins chosen at runtime.
+///
+/// Every bin accumulates the sum of the weights and the sum of the squared
+/// weights of its samples. The latter estimates the variance of the bin
+/// content, as is customary for weighted Monte Carlo samples. For unit weights,
+/// this is the Poissonian variance of the count.
+///
+///
+/// ## Example
+///
+/// ```
+/// use average::WeightedHistogram;
+///
+/// let mut h = WeightedHistogram::with_const_width(0., 10., 2);
+/// h.add(1., 0.5).unwrap();
+/// h.add(2., 1.5).unwrap();
+/// h.add(7., 2.).unwrap();
+/// assert_eq!(h.bins(), &[2., 2.]);
+/// assert_eq!(h.variance(0), 0.5 * 0.5 + 1.5 * 1.5);
+/// assert_eq!(h.variance(1), 4.);
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde1", serde(try_from = "WeightedHistogramData"))]
+pub struct WeightedHistogram {
+    /// The ranges defining the bins of the histogram.
+    range: Vec<f64>,
+    /// The sum of the weights for each bin.
+    bin: Vec<f64>,
+    /// The sum of the squared weights for each bin.
+    sum_weights_sq: Vec<f64>,
+    /// The spacing of the ranges.
+    #[cfg_attr(feature = "serde1", serde(skip))]
+    spacing: Spacing,
+}
+
+/// The serialized fields of a `WeightedHistogram`, which are validated before
+/// deserializing the histogram.
+#[cfg(feature = "serde1")]
+#[derive(Deserialize)]
+struct WeightedHistogramData {
+    range: Vec<f64>,
+    bin: Vec<f64>,
+    sum_weights_sq: Vec<f64>,
+}
+
+#[cfg(feature = "serde1")]
+impl TryFrom<WeightedHistogramData> for WeightedHistogram {
+    type Error = InvalidRangeError;
+
+    fn try_from(data: WeightedHistogramData) -> Result<WeightedHistogram, InvalidRangeError> {
+        if data.range.len() < 2 || data.range.len() != data.bin.len() + 1
+            || data.bin.len() != data.sum_weights_sq.len()
+        {
+            return Err(InvalidRangeError::NotEnoughRanges);
+        }
+        let mut range = vec![0.; data.range.len()];
+        histogram::fill_ranges(&mut range, data.range)?;
+        Ok(WeightedHistogram {
+            range,
+            bin: data.bin,
+            sum_weights_sq: data.sum_weights_sq,
+            spacing: Spacing::Arbitrary,
+        })
+    }
+}
+
+impl WeightedHistogram {
+    /// Construct a histogram with `len` bins of constant width.
+    ///
+    /// Panics if `len` is zero.
+    #[inline]
+    pub fn with_const_width(start: f64, end: f64, len: usize) -> WeightedHistogram {
+        assert!(len > 0, "At least one bin is required");
+        let mut range = vec![0.; len + 1];
+        histogram::fill_const_width(&mut range, start, end);
+        WeightedHistogram {
+            range,
+            bin: vec![0.; len],
+            sum_weights_sq: vec![0.; len],
+            spacing: Spacing::linear(start, end),
+        }
+    }
+
+    /// Construct a histogram from given ranges.
+    ///
+    /// The ranges are given by an iterator of floats where neighboring
+    /// pairs `(a, b)` define a bin for all `x` where `a <= x < b`. The number
+    /// of bins is one less than the number of ranges.
+    ///
+    /// Fails if the iterator is too short (less than 2 ranges), is not sorted
+    /// or contains `nan`. `inf` and empty ranges are allowed.
+    #[inline]
+    pub fn from_ranges<T>(ranges: T) -> Result<WeightedHistogram, InvalidRangeError>
+        where T: IntoIterator<Item = f64>
+    {
+        let ranges: Vec<f64> = ranges.into_iter().collect();
+        if ranges.len() < 2 {
+            return Err(InvalidRangeError::NotEnoughRanges);
+        }
+        let mut range = vec![0.; ranges.len()];
+        histogram::fill_ranges(&mut range, ranges)?;
+        let len = range.len() - 1;
+        Ok(WeightedHistogram {
+            range,
+            bin: vec![0.; len],
+            sum_weights_sq: vec![0.; len],
+            spacing: Spacing::Arbitrary,
+        })
+    }
+
+    /// Return the number of bins.
+    #[inline]
+    pub fn num_bins(&self) -> usize {
+        self.bin.len()
+    }
+
+    /// Determine whether a sample with a nonzero weight was added.
+    #[inline]
+    pub fn has_samples(&self) -> bool {
+        self.sum_weights_sq.iter().any(|&w| w != 0.)
+    }
+
+    /// Find the index of the bin corresponding to the given sample.
+    ///
+    /// Fails if the sample is out of range of the histogram.
+    #[inline]
+    pub fn find(&self, x: f64) -> Result<usize, SampleOutOfRangeError> {
+        histogram::find(&self.range, self.spacing, x)
+    }
+
+    /// Add a sample with the given weight to the histogram.
+    ///
+    /// Fails if the sample is out of range of the histogram.
+    #[inline]
+    pub fn add(&mut self, x: f64, weight: f64) -> Result<(), SampleOutOfRangeError> {
+        let i = self.find(x)?;
+        self.bin[i] += weight;
+        self.sum_weights_sq[i] += weight * weight;
+        Ok(())
+    }
+
+    /// Return the ranges of the histogram.
+    #[inline]
+    pub fn ranges(&self) -> &[f64] {
+        &self.range[..]
+    }
+
+    /// Return the sum of the weights for each bin.
+    #[inline]
+    pub fn bins(&self) -> &[f64] {
+        &self.bin[..]
+    }
+
+    /// Return the sum of the squared weights for each bin.
+    #[inline]
+    pub fn sum_weights_sq(&self) -> &[f64] {
+        &self.sum_weights_sq[..]
+    }
+
+    /// Estimate the variance for the given bin.
+    ///
+    /// This is the sum of the squared weights. The square root of this
+    /// estimates the error of the bin content.
+    #[inline]
+    pub fn variance(&self, bin: usize) -> f64 {
+        self.sum_weights_sq[bin]
+    }
+
+    /// Return an iterator over the bin variances.
+    #[inline]
+    pub fn variances(&self) -> impl Iterator<Item = f64> + '_ {
+        self.sum_weights_sq.iter().cloned()
+    }
+
+    /// Return an iterator over the bins normalized by the bin widths.
+    #[inline]
+    pub fn normalized_bins(&self) -> impl Iterator<Item = f64> + '_ {
+        self.iter().map(|((a, b), w)| w / (b - a))
+    }
+
+    /// Return an iterator over the bins and corresponding ranges:
+    /// `((lower, upper), sum of weights)`
+    #[inline]
+    pub fn iter(&self) -> IterWeightedHistogram<'_> {
+        self.into_iter()
+    }
+
+    /// Reset all bins to zero.
+    #[inline]
+    pub fn reset(&mut self) {
+        for w in self.bin.iter_mut().chain(self.sum_weights_sq.iter_mut()) {
+            *w = 0.;
+        }
+    }
+
+    /// Return the lower range limit.
+    ///
+    /// (The corresponding bin might be empty.)
+    #[inline]
+    pub fn range_min(&self) -> f64 {
+        self.range[0]
+    }
+
+    /// Return the upper range limit.
+    ///
+    /// (The corresponding bin might be empty.)
+    #[inline]
+    pub fn range_max(&self) -> f64 {
+        self.range[self.bin.len()]
+    }
+}
+
+/// Iterate over all `(range, sum of weights)` pairs in a weighted histogram.
+#[derive(Debug, Clone)]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+pub struct IterWeightedHistogram<'a> {
+    remaining_bin: &'a [f64],
+    remaining_range: &'a [f64],
+}
+
+impl<'a> Iterator for IterWeightedHistogram<'a> {
+    type Item = ((f64, f64), f64);
+    fn next(&mut self) -> Option<((f64, f64), f64)> {
+        if let Some((&bin, rest)) = self.remaining_bin.split_first() {
+            let left = self.remaining_range[0];
+            let right = self.remaining_range[1];
+            self.remaining_bin = rest;
+            self.remaining_range = &self.remaining_range[1..];
+            return Some(((left, right), bin));
+        }
+        None
+    }
+}
+
+impl<'a> IntoIterator for &'a WeightedHistogram {
+    type Item = ((f64, f64), f64);
+    type IntoIter = IterWeightedHistogram<'a>;
+    fn into_iter(self) -> IterWeightedHistogram<'a> {
+        IterWeightedHistogram {
+            remaining_bin: self.bins(),
+            remaining_range: self.ranges(),
+        }
+    }
+}
+
+impl core::ops::AddAssign<&Self> for WeightedHistogram {
+    /// Add the bins of another histogram with the same ranges.
+    ///
+    /// Panics if the ranges are different.
+    #[inline]
+    fn add_assign(&mut self, other: &Self) {
+        self.merge(other);
+    }
+}
+
+impl core::ops::MulAssign<f64> for WeightedHistogram {
+    /// Scale all weights by the given factor.
+    #[inline]
+    fn mul_assign(&mut self, other: f64) {
+        for w in &mut self.bin {
+            *w *= other;
+        }
+        for w in &mut self.sum_weights_sq {
+            *w *= other * other;
+        }
+    }
+}
+
+impl Merge for WeightedHistogram {
+    /// Merge another histogram into this one.
+    ///
+    /// Panics if the ranges are different.
+    fn merge(&mut self, other: &Self) {
+        assert_eq!(self.range.len(), other.range.len(),
+            "Both histograms must have the same number of bins");
+        for (a, b) in self.range.iter().zip(other.range.iter()) {
+            assert_eq!(a, b, "Both histograms must have the same ranges");
+        }
+        for (a, b) in self.bin.iter_mut().zip(other.bin.iter()) {
+            *a += *b;
+        }
+        for (a, b) in self.sum_weights_sq.iter_mut().zip(other.sum_weights_sq.iter()) {
+            *a += *b;
+        }
+    }
+}
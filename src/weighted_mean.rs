@@ -1,3 +1,4 @@
+use num_traits::float::FloatCore;
 #[cfg(feature = "serde1")] use serde::{Serialize, Deserialize};
 use super::{MeanWithError, Estimate, Merge};
 
@@ -16,31 +17,40 @@ use super::{MeanWithError, Estimate, Merge};
 /// ```
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
-pub struct WeightedMean  {
+pub struct WeightedMean<T = f64> {
     /// Sum of the weights.
-    weight_sum: f64,
+    weight_sum: T,
     /// Weighted mean value.
-    weighted_avg: f64,
+    weighted_avg: T,
 }
 
-impl WeightedMean {
+impl WeightedMean<f64> {
     /// Create a new weighted and unweighted mean estimator.
-    pub fn new() -> WeightedMean {
+    ///
+    /// Use `new_generic` for other floating point types than `f64`.
+    pub fn new() -> WeightedMean<f64> {
+        WeightedMean::new_generic()
+    }
+}
+
+impl<T: FloatCore> WeightedMean<T> {
+    /// Create a new weighted and unweighted mean estimator.
+    pub fn new_generic() -> WeightedMean<T> {
         WeightedMean {
-            weight_sum: 0., weighted_avg: 0.,
+            weight_sum: T::zero(), weighted_avg: T::zero(),
         }
     }
 
     /// Add an observation sampled from the population.
     #[inline]
-    pub fn add(&mut self, sample: f64, weight: f64) {
+    pub fn add(&mut self, sample: T, weight: T) {
         // The algorithm for the unweighted mean was suggested by Welford in 1962.
         //
         // See
         // https://en.wikipedia.org/wiki/Algorithms_for_calculating_variance
         // and
         // http://people.ds.cam.ac.uk/fanf2/hermes/doc/antiforgery/stats.pdf.
-        self.weight_sum += weight;
+        self.weight_sum = self.weight_sum + weight;
 
         let prev_avg = self.weighted_avg;
         self.weighted_avg = prev_avg + (weight / self.weight_sum) * (sample - prev_avg);
@@ -51,14 +61,14 @@ impl WeightedMean {
     /// Might be a false positive if the sum of weights is zero.
     #[inline]
     pub fn is_empty(&self) -> bool {
-        self.weight_sum == 0.
+        self.weight_sum == T::zero()
     }
 
     /// Return the sum of the weights.
     ///
     /// Returns 0 for an empty sample.
     #[inline]
-    pub fn sum_weights(&self) -> f64 {
+    pub fn sum_weights(&self) -> T {
         self.weight_sum
     }
 
@@ -66,22 +76,22 @@ impl WeightedMean {
     ///
     /// Returns 0 for an empty sample.
     #[inline]
-    pub fn mean(&self) -> f64 {
+    pub fn mean(&self) -> T {
         self.weighted_avg
     }
 }
 
-impl core::default::Default for WeightedMean {
-    fn default() -> WeightedMean {
-        WeightedMean::new()
+impl<T: FloatCore> core::default::Default for WeightedMean<T> {
+    fn default() -> WeightedMean<T> {
+        WeightedMean::new_generic()
     }
 }
 
-impl core::iter::FromIterator<(f64, f64)> for WeightedMean {
-    fn from_iter<T>(iter: T) -> WeightedMean
-        where T: IntoIterator<Item=(f64, f64)>
+impl<T: FloatCore> core::iter::FromIterator<(T, T)> for WeightedMean<T> {
+    fn from_iter<I>(iter: I) -> WeightedMean<T>
+        where I: IntoIterator<Item=(T, T)>
     {
-        let mut a = WeightedMean::new();
+        let mut a = WeightedMean::new_generic();
         for (i, w) in iter {
             a.add(i, w);
         }
@@ -89,11 +99,11 @@ impl core::iter::FromIterator<(f64, f64)> for WeightedMean {
     }
 }
 
-impl<'a> core::iter::FromIterator<&'a (f64, f64)> for WeightedMean {
-    fn from_iter<T>(iter: T) -> WeightedMean
-        where T: IntoIterator<Item=&'a (f64, f64)>
+impl<'a, T: FloatCore + 'a> core::iter::FromIterator<&'a (T, T)> for WeightedMean<T> {
+    fn from_iter<I>(iter: I) -> WeightedMean<T>
+        where I: IntoIterator<Item=&'a (T, T)>
     {
-        let mut a = WeightedMean::new();
+        let mut a = WeightedMean::new_generic();
         for &(i, w) in iter {
             a.add(i, w);
         }
@@ -101,7 +111,7 @@ impl<'a> core::iter::FromIterator<&'a (f64, f64)> for WeightedMean {
     }
 }
 
-impl Merge for WeightedMean {
+impl<T: FloatCore> Merge for WeightedMean<T> {
     /// Merge another sample into this one.
     ///
     ///
@@ -121,7 +131,7 @@ impl Merge for WeightedMean {
     /// assert!((avg_total.mean() - avg_left.mean()).abs() < 1e-15);
     /// ```
     #[inline]
-    fn merge(&mut self, other: &WeightedMean) {
+    fn merge(&mut self, other: &WeightedMean<T>) {
         let total_weight_sum = self.weight_sum + other.weight_sum;
         self.weighted_avg = (self.weight_sum * self.weighted_avg
                              + other.weight_sum * other.weighted_avg)
@@ -147,29 +157,39 @@ impl Merge for WeightedMean {
 /// ```
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
-pub struct WeightedMeanWithError {
+pub struct WeightedMeanWithError<T = f64> {
     /// Sum of the squares of the weights.
-    weight_sum_sq: f64,
+    weight_sum_sq: T,
     /// Estimator of the weighted mean.
-    weighted_avg: WeightedMean,
+    weighted_avg: WeightedMean<T>,
     /// Estimator of unweighted mean and its variance.
-    unweighted_avg: MeanWithError,
+    unweighted_avg: MeanWithError<T>,
+}
+
+impl WeightedMeanWithError<f64> {
+    /// Create a new weighted and unweighted mean estimator.
+    ///
+    /// Use `new_generic` for other floating point types than `f64`.
+    #[inline]
+    pub fn new() -> WeightedMeanWithError<f64> {
+        WeightedMeanWithError::new_generic()
+    }
 }
 
-impl WeightedMeanWithError {
+impl<T: FloatCore> WeightedMeanWithError<T> {
     /// Create a new weighted and unweighted mean estimator.
     #[inline]
-    pub fn new() -> WeightedMeanWithError {
+    pub fn new_generic() -> WeightedMeanWithError<T> {
         WeightedMeanWithError {
-            weight_sum_sq: 0.,
-            weighted_avg: WeightedMean::new(),
-            unweighted_avg: MeanWithError::new(),
+            weight_sum_sq: T::zero(),
+            weighted_avg: WeightedMean::new_generic(),
+            unweighted_avg: MeanWithError::new_generic(),
         }
     }
 
     /// Add an observation sampled from the population.
     #[inline]
-    pub fn add(&mut self, sample: f64, weight: f64) {
+    pub fn add(&mut self, sample: T, weight: T) {
         // The algorithm for the unweighted mean was suggested by Welford in 1962.
         // The algorithm for the weighted mean was suggested by West in 1979.
         //
@@ -177,7 +197,7 @@ impl WeightedMeanWithError {
         // https://en.wikipedia.org/wiki/Algorithms_for_calculating_variance
         // and
         // http://people.ds.cam.ac.uk/fanf2/hermes/doc/antiforgery/stats.pdf.
-        self.weight_sum_sq += weight*weight;
+        self.weight_sum_sq = self.weight_sum_sq + weight*weight;
         self.weighted_avg.add(sample, weight);
         self.unweighted_avg.add(sample);
     }
@@ -192,7 +212,7 @@ impl WeightedMeanWithError {
     ///
     /// Returns 0 for an empty sample.
     #[inline]
-    pub fn sum_weights(&self) -> f64 {
+    pub fn sum_weights(&self) -> T {
         self.weighted_avg.sum_weights()
     }
 
@@ -200,7 +220,7 @@ impl WeightedMeanWithError {
     ///
     /// Returns 0 for an empty sample.
     #[inline]
-    pub fn sum_weights_sq(&self) -> f64 {
+    pub fn sum_weights_sq(&self) -> T {
         self.weight_sum_sq
     }
 
@@ -208,7 +228,7 @@ impl WeightedMeanWithError {
     ///
     /// Returns 0 for an empty sample.
     #[inline]
-    pub fn weighted_mean(&self) -> f64 {
+    pub fn weighted_mean(&self) -> T {
         self.weighted_avg.mean()
     }
 
@@ -216,7 +236,7 @@ impl WeightedMeanWithError {
     ///
     /// Returns 0 for an empty sample.
     #[inline]
-    pub fn unweighted_mean(&self) -> f64 {
+    pub fn unweighted_mean(&self) -> T {
         self.unweighted_avg.mean()
     }
 
@@ -228,9 +248,9 @@ impl WeightedMeanWithError {
 
     /// Calculate the effective sample size.
     #[inline]
-    pub fn effective_len(&self) -> f64 {
+    pub fn effective_len(&self) -> T {
         if self.is_empty() {
-            return 0.
+            return T::zero()
         }
         let weight_sum = self.weighted_avg.sum_weights();
         weight_sum * weight_sum / self.weight_sum_sq
@@ -240,7 +260,7 @@ impl WeightedMeanWithError {
     ///
     /// This is a biased estimator of the variance of the population.
     #[inline]
-    pub fn population_variance(&self) -> f64 {
+    pub fn population_variance(&self) -> T {
         self.unweighted_avg.population_variance()
     }
 
@@ -248,7 +268,7 @@ impl WeightedMeanWithError {
     ///
     /// This is an unbiased estimator of the variance of the population.
     #[inline]
-    pub fn sample_variance(&self) -> f64 {
+    pub fn sample_variance(&self) -> T {
         self.unweighted_avg.sample_variance()
     }
 
@@ -259,35 +279,37 @@ impl WeightedMeanWithError {
     /// This unbiased estimator assumes that the samples were independently
     /// drawn from the same population with constant variance.
     #[inline]
-    pub fn variance_of_weighted_mean(&self) -> f64 {
+    pub fn variance_of_weighted_mean(&self) -> T {
         // This uses the same estimate as WinCross, which should provide better
         // results than the ones used by SPSS or Mentor.
         //
         // See http://www.analyticalgroup.com/download/WEIGHTED_VARIANCE.pdf.
 
         let weight_sum = self.weighted_avg.sum_weights();
-        if weight_sum == 0. {
-            return 0.;
+        if weight_sum == T::zero() {
+            return T::zero();
         }
         let inv_effective_len = self.weight_sum_sq / (weight_sum * weight_sum);
         self.sample_variance() * inv_effective_len
     }
+}
 
+#[cfg(any(feature = "std", feature = "libm"))]
+impl<T: FloatCore + num_traits::Float> WeightedMeanWithError<T> {
     /// Estimate the standard error of the *weighted* mean of the population.
     ///
     /// Returns 0 if the sum of weights is 0.
     ///
     /// This unbiased estimator assumes that the samples were independently
     /// drawn from the same population with constant variance.
-    #[cfg(any(feature = "std", feature = "libm"))]
     #[cfg_attr(doc_cfg, doc(cfg(any(feature = "std", feature = "libm"))))]
     #[inline]
-    pub fn error(&self) -> f64 {
+    pub fn error(&self) -> T {
         num_traits::Float::sqrt(self.variance_of_weighted_mean())
     }
 }
 
-impl Merge for WeightedMeanWithError {
+impl<T: FloatCore> Merge for WeightedMeanWithError<T> {
     /// Merge another sample into this one.
     ///
     ///
@@ -308,24 +330,24 @@ impl Merge for WeightedMeanWithError {
     /// assert!((avg_total.error() - avg_left.error()).abs() < 1e-15);
     /// ```
     #[inline]
-    fn merge(&mut self, other: &WeightedMeanWithError) {
-        self.weight_sum_sq += other.weight_sum_sq;
+    fn merge(&mut self, other: &WeightedMeanWithError<T>) {
+        self.weight_sum_sq = self.weight_sum_sq + other.weight_sum_sq;
         self.weighted_avg.merge(&other.weighted_avg);
         self.unweighted_avg.merge(&other.unweighted_avg);
     }
 }
 
-impl core::default::Default for WeightedMeanWithError {
-    fn default() -> WeightedMeanWithError {
-        WeightedMeanWithError::new()
+impl<T: FloatCore> core::default::Default for WeightedMeanWithError<T> {
+    fn default() -> WeightedMeanWithError<T> {
+        WeightedMeanWithError::new_generic()
     }
 }
 
-impl core::iter::FromIterator<(f64, f64)> for WeightedMeanWithError {
-    fn from_iter<T>(iter: T) -> WeightedMeanWithError
-        where T: IntoIterator<Item=(f64, f64)>
+impl<T: FloatCore> core::iter::FromIterator<(T, T)> for WeightedMeanWithError<T> {
+    fn from_iter<I>(iter: I) -> WeightedMeanWithError<T>
+        where I: IntoIterator<Item=(T, T)>
     {
-        let mut a = WeightedMeanWithError::new();
+        let mut a = WeightedMeanWithError::new_generic();
         for (i, w) in iter {
             a.add(i, w);
         }
@@ -333,11 +355,13 @@ impl core::iter::FromIterator<(f64, f64)> for WeightedMeanWithError {
     }
 }
 
-impl<'a> core::iter::FromIterator<&'a (f64, f64)> for WeightedMeanWithError {
-    fn from_iter<T>(iter: T) -> WeightedMeanWithError
-        where T: IntoIterator<Item=&'a (f64, f64)>
+impl<'a, T: FloatCore + 'a> core::iter::FromIterator<&'a (T, T)>
+    for WeightedMeanWithError<T>
+{
+    fn from_iter<I>(iter: I) -> WeightedMeanWithError<T>
+        where I: IntoIterator<Item=&'a (T, T)>
     {
-        let mut a = WeightedMeanWithError::new();
+        let mut a = WeightedMeanWithError::new_generic();
         for &(i, w) in iter {
             a.add(i, w);
         }
@@ -0,0 +1,761 @@
+use num_traits::float::FloatCore;
+#[cfg(any(feature = "std", feature = "libm"))]
+use num_traits::Float;
+#[cfg(feature = "serde1")] use serde::{Serialize, Deserialize};
+
+use super::Merge;
+
+/// The meaning of the weights, which determines the bias correction of
+/// sample variances.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WeightKind {
+    /// The weights count how often an observation occurred.
+    ///
+    /// The sum of the weights is the sample size.
+    Frequency,
+    /// The weights describe the relative importance of the observations, for
+    /// example inverse variances or importance sampling weights.
+    ///
+    /// The scale of the weights does not matter.
+    Reliability,
+}
+
+/// Estimate the weighted arithmetic mean and the weighted variance of a
+/// sequence of numbers ("population").
+///
+/// This can be used to estimate the standard error of the weighted mean.
+///
+///
+/// ## Example
+///
+/// ```
+/// use average::{WeightedVariance, WeightKind};
+///
+/// let a: WeightedVariance = (1..6).zip(1..6)
+///     .map(|(x, w)| (f64::from(x), f64::from(w))).collect();
+/// println!("The weighted mean is {} ± {}.",
+///     a.mean(), a.error_mean(WeightKind::Reliability));
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+pub struct WeightedVariance<T = f64> {
+    /// Sum of the weights.
+    weight_sum: T,
+    /// Sum of the squares of the weights.
+    weight_sum_sq: T,
+    /// Weighted mean value.
+    avg: T,
+    /// Intermediate weighted sum of squares for calculating the variance.
+    sum_2: T,
+}
+
+impl WeightedVariance<f64> {
+    /// Create a new weighted variance estimator.
+    ///
+    /// Use `new_generic` for other floating point types than `f64`.
+    #[inline]
+    pub fn new() -> WeightedVariance<f64> {
+        WeightedVariance::new_generic()
+    }
+}
+
+impl<T: FloatCore> WeightedVariance<T> {
+    /// Create a new weighted variance estimator.
+    #[inline]
+    pub fn new_generic() -> WeightedVariance<T> {
+        WeightedVariance {
+            weight_sum: T::zero(),
+            weight_sum_sq: T::zero(),
+            avg: T::zero(),
+            sum_2: T::zero(),
+        }
+    }
+
+    /// Increment the sum of the weights.
+    ///
+    /// This does not update anything else.
+    #[inline]
+    fn increment(&mut self, weight: T) {
+        self.weight_sum = self.weight_sum + weight;
+        self.weight_sum_sq = self.weight_sum_sq + weight * weight;
+    }
+
+    /// Add an observation given an already calculated difference from the mean
+    /// divided by the sum of the weights, assuming the sums of the weights
+    /// were already updated.
+    ///
+    /// This is useful for avoiding unnecessary divisions in the inner loop.
+    #[inline]
+    fn add_inner(&mut self, delta: T, delta_n: T, weight: T) {
+        // This algorithm was suggested by West in 1979.
+        //
+        // See https://en.wikipedia.org/wiki/Algorithms_for_calculating_variance.
+        let prev_weight_sum = self.weight_sum - weight;
+        self.avg = self.avg + weight * delta_n;
+        self.sum_2 = self.sum_2 + prev_weight_sum * weight * delta * delta_n;
+    }
+
+    /// Add an observation sampled from the population.
+    ///
+    /// Observations with zero weight are ignored.
+    #[inline]
+    pub fn add(&mut self, sample: T, weight: T) {
+        if weight == T::zero() {
+            return;
+        }
+        let delta = sample - self.avg;
+        self.increment(weight);
+        let delta_n = delta / self.weight_sum;
+        self.add_inner(delta, delta_n, weight);
+    }
+
+    /// Determine whether the sample is empty.
+    ///
+    /// Might be a false positive if the sum of weights is zero.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.weight_sum == T::zero()
+    }
+
+    /// Return the sum of the weights.
+    ///
+    /// Returns 0 for an empty sample.
+    #[inline]
+    pub fn sum_weights(&self) -> T {
+        self.weight_sum
+    }
+
+    /// Return the sum of the squared weights.
+    ///
+    /// Returns 0 for an empty sample.
+    #[inline]
+    pub fn sum_weights_sq(&self) -> T {
+        self.weight_sum_sq
+    }
+
+    /// Calculate the effective sample size.
+    #[inline]
+    pub fn effective_len(&self) -> T {
+        if self.is_empty() {
+            return T::zero();
+        }
+        self.weight_sum * self.weight_sum / self.weight_sum_sq
+    }
+
+    /// Estimate the weighted mean of the population.
+    ///
+    /// Returns 0 for an empty sample.
+    #[inline]
+    pub fn mean(&self) -> T {
+        self.avg
+    }
+
+    /// Calculate the weighted population variance of the sample.
+    ///
+    /// This is a biased estimator of the variance of the population.
+    #[inline]
+    pub fn population_variance(&self) -> T {
+        if self.is_empty() {
+            return T::zero();
+        }
+        self.sum_2 / self.weight_sum
+    }
+
+    /// Calculate the weighted sample variance.
+    ///
+    /// This is an unbiased estimator of the variance of the population,
+    /// assuming the weights are of the given kind. Returns 0 if the sample is
+    /// too small to estimate the variance.
+    #[inline]
+    pub fn sample_variance(&self, kind: WeightKind) -> T {
+        let denominator = match kind {
+            WeightKind::Frequency => self.weight_sum - T::one(),
+            WeightKind::Reliability => {
+                if self.is_empty() {
+                    return T::zero();
+                }
+                self.weight_sum - self.weight_sum_sq / self.weight_sum
+            },
+        };
+        if denominator <= T::zero() {
+            return T::zero();
+        }
+        self.sum_2 / denominator
+    }
+
+    /// Estimate the variance of the weighted mean of the population.
+    ///
+    /// Returns 0 if the sample is too small to estimate the variance.
+    #[inline]
+    pub fn variance_of_mean(&self, kind: WeightKind) -> T {
+        if self.is_empty() {
+            return T::zero();
+        }
+        let weight_sum = self.weight_sum;
+        let inv_effective_len = match kind {
+            WeightKind::Frequency => T::one() / weight_sum,
+            WeightKind::Reliability => self.weight_sum_sq / (weight_sum * weight_sum),
+        };
+        self.sample_variance(kind) * inv_effective_len
+    }
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+impl<T: FloatCore + Float> WeightedVariance<T> {
+    /// Estimate the standard error of the weighted mean of the population.
+    ///
+    /// Returns 0 if the sample is too small to estimate the variance.
+    #[cfg_attr(doc_cfg, doc(cfg(any(feature = "std", feature = "libm"))))]
+    #[inline]
+    pub fn error_mean(&self, kind: WeightKind) -> T {
+        Float::sqrt(self.variance_of_mean(kind))
+    }
+}
+
+impl<T: FloatCore> core::default::Default for WeightedVariance<T> {
+    fn default() -> WeightedVariance<T> {
+        WeightedVariance::new_generic()
+    }
+}
+
+impl<T: FloatCore> Merge for WeightedVariance<T> {
+    /// Merge another sample into this one.
+    ///
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use average::{WeightedVariance, WeightKind, Merge};
+    ///
+    /// let weighted_sequence: &[(f64, f64)] = &[
+    ///     (1., 0.1), (2., 0.2), (3., 0.3), (4., 0.4), (5., 0.5),
+    ///     (6., 0.6), (7., 0.7), (8., 0.8), (9., 0.9)];
+    /// let (left, right) = weighted_sequence.split_at(3);
+    /// let var_total: WeightedVariance = weighted_sequence.iter().collect();
+    /// let mut var_left: WeightedVariance = left.iter().collect();
+    /// let var_right: WeightedVariance = right.iter().collect();
+    /// var_left.merge(&var_right);
+    /// assert!((var_total.mean() - var_left.mean()).abs() < 1e-15);
+    /// assert!((var_total.sample_variance(WeightKind::Reliability)
+    ///     - var_left.sample_variance(WeightKind::Reliability)).abs() < 1e-14);
+    /// ```
+    #[inline]
+    fn merge(&mut self, other: &WeightedVariance<T>) {
+        // This algorithm was proposed by Chan et al. in 1979.
+        //
+        // See https://en.wikipedia.org/wiki/Algorithms_for_calculating_variance.
+        let weight_self = self.weight_sum;
+        let weight_other = other.weight_sum;
+        let weight_total = weight_self + weight_other;
+        if weight_other == T::zero() {
+            return;
+        }
+        let delta = other.avg - self.avg;
+        self.weight_sum = weight_total;
+        self.weight_sum_sq = self.weight_sum_sq + other.weight_sum_sq;
+        self.avg = (weight_self * self.avg + weight_other * other.avg) / weight_total;
+        self.sum_2 = self.sum_2 + other.sum_2
+            + delta*delta * weight_self * weight_other / weight_total;
+    }
+}
+
+impl<T: FloatCore> core::iter::FromIterator<(T, T)> for WeightedVariance<T> {
+    fn from_iter<I>(iter: I) -> WeightedVariance<T>
+        where I: IntoIterator<Item=(T, T)>
+    {
+        let mut a = WeightedVariance::new_generic();
+        for (i, w) in iter {
+            a.add(i, w);
+        }
+        a
+    }
+}
+
+impl<'a, T: FloatCore + 'a> core::iter::FromIterator<&'a (T, T)> for WeightedVariance<T> {
+    fn from_iter<I>(iter: I) -> WeightedVariance<T>
+        where I: IntoIterator<Item=&'a (T, T)>
+    {
+        let mut a = WeightedVariance::new_generic();
+        for &(i, w) in iter {
+            a.add(i, w);
+        }
+        a
+    }
+}
+
+/// Estimate the weighted arithmetic mean, the weighted variance and the
+/// weighted skewness of a sequence of numbers ("population").
+///
+/// This can be used to estimate the standard error of the weighted mean.
+#[cfg(any(feature = "std", feature = "libm"))]
+#[cfg_attr(doc_cfg, doc(cfg(any(feature = "std", feature = "libm"))))]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+pub struct WeightedSkewness<T = f64> {
+    /// Estimator of weighted mean and variance.
+    avg: WeightedVariance<T>,
+    /// Intermediate weighted sum of cubes for calculating the skewness.
+    sum_3: T,
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+impl WeightedSkewness<f64> {
+    /// Create a new weighted skewness estimator.
+    ///
+    /// Use `new_generic` for other floating point types than `f64`.
+    #[inline]
+    pub fn new() -> WeightedSkewness<f64> {
+        WeightedSkewness::new_generic()
+    }
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+impl<T: FloatCore + Float> WeightedSkewness<T> {
+    /// Create a new weighted skewness estimator.
+    #[inline]
+    pub fn new_generic() -> WeightedSkewness<T> {
+        WeightedSkewness {
+            avg: WeightedVariance::new_generic(),
+            sum_3: T::zero(),
+        }
+    }
+
+    /// Increment the sum of the weights.
+    ///
+    /// This does not update anything else.
+    #[inline]
+    fn increment(&mut self, weight: T) {
+        self.avg.increment(weight);
+    }
+
+    /// Add an observation given an already calculated difference from the mean
+    /// divided by the sum of the weights, assuming the sums of the weights
+    /// were already updated.
+    ///
+    /// This is useful for avoiding unnecessary divisions in the inner loop.
+    #[inline]
+    fn add_inner(&mut self, delta: T, delta_n: T, weight: T) {
+        // This is the special case of merging with a single observation of
+        // the algorithm by Pébay et al.
+        //
+        // See https://doi.org/10.1007/s00180-015-0637-z.
+        let prev_weight_sum = self.sum_weights() - weight;
+        let three = T::from(3).unwrap();
+        self.sum_3 = self.sum_3
+            + delta * delta_n * delta_n * prev_weight_sum * weight * (prev_weight_sum - weight)
+            - three * weight * delta_n * self.avg.sum_2;
+        self.avg.add_inner(delta, delta_n, weight);
+    }
+
+    /// Add an observation sampled from the population.
+    ///
+    /// Observations with zero weight are ignored.
+    #[inline]
+    pub fn add(&mut self, sample: T, weight: T) {
+        if weight == T::zero() {
+            return;
+        }
+        let delta = sample - self.mean();
+        self.increment(weight);
+        let delta_n = delta / self.sum_weights();
+        self.add_inner(delta, delta_n, weight);
+    }
+
+    /// Determine whether the sample is empty.
+    ///
+    /// Might be a false positive if the sum of weights is zero.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.avg.is_empty()
+    }
+
+    /// Return the sum of the weights.
+    ///
+    /// Returns 0 for an empty sample.
+    #[inline]
+    pub fn sum_weights(&self) -> T {
+        self.avg.sum_weights()
+    }
+
+    /// Return the sum of the squared weights.
+    ///
+    /// Returns 0 for an empty sample.
+    #[inline]
+    pub fn sum_weights_sq(&self) -> T {
+        self.avg.sum_weights_sq()
+    }
+
+    /// Calculate the effective sample size.
+    #[inline]
+    pub fn effective_len(&self) -> T {
+        self.avg.effective_len()
+    }
+
+    /// Estimate the weighted mean of the population.
+    ///
+    /// Returns 0 for an empty sample.
+    #[inline]
+    pub fn mean(&self) -> T {
+        self.avg.mean()
+    }
+
+    /// Calculate the weighted population variance of the sample.
+    ///
+    /// This is a biased estimator of the variance of the population.
+    #[inline]
+    pub fn population_variance(&self) -> T {
+        self.avg.population_variance()
+    }
+
+    /// Calculate the weighted sample variance.
+    ///
+    /// This is an unbiased estimator of the variance of the population,
+    /// assuming the weights are of the given kind.
+    #[inline]
+    pub fn sample_variance(&self, kind: WeightKind) -> T {
+        self.avg.sample_variance(kind)
+    }
+
+    /// Estimate the standard error of the weighted mean of the population.
+    #[inline]
+    pub fn error_mean(&self, kind: WeightKind) -> T {
+        self.avg.error_mean(kind)
+    }
+
+    /// Estimate the weighted skewness of the population.
+    #[inline]
+    pub fn skewness(&self) -> T {
+        if self.sum_3 == T::zero() {
+            return T::zero();
+        }
+        let sum_2 = self.avg.sum_2;
+        debug_assert!(sum_2 != T::zero());
+        Float::sqrt(self.sum_weights()) * self.sum_3 / Float::sqrt(sum_2*sum_2*sum_2)
+    }
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+impl<T: FloatCore + Float> core::default::Default for WeightedSkewness<T> {
+    fn default() -> WeightedSkewness<T> {
+        WeightedSkewness::new_generic()
+    }
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+impl<T: FloatCore + Float> Merge for WeightedSkewness<T> {
+    /// Merge another sample into this one.
+    ///
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use average::{WeightedSkewness, Merge};
+    ///
+    /// let weighted_sequence: &[(f64, f64)] = &[
+    ///     (1., 0.1), (2., 0.2), (3., 0.3), (4., 0.4), (5., 0.5),
+    ///     (6., 0.6), (7., 0.7), (8., 0.8), (9., 0.9)];
+    /// let (left, right) = weighted_sequence.split_at(3);
+    /// let total: WeightedSkewness = weighted_sequence.iter().collect();
+    /// let mut merged: WeightedSkewness = left.iter().collect();
+    /// let right: WeightedSkewness = right.iter().collect();
+    /// merged.merge(&right);
+    /// assert!((total.mean() - merged.mean()).abs() < 1e-15);
+    /// assert!((total.skewness() - merged.skewness()).abs() < 1e-14);
+    /// ```
+    #[inline]
+    fn merge(&mut self, other: &WeightedSkewness<T>) {
+        let weight_self = self.sum_weights();
+        let weight_other = other.sum_weights();
+        let weight_total = weight_self + weight_other;
+        if weight_other == T::zero() {
+            return;
+        }
+        let three = T::from(3).unwrap();
+        let delta = other.mean() - self.mean();
+        let delta_n = delta / weight_total;
+        self.sum_3 = self.sum_3 + other.sum_3
+            + delta*delta_n*delta_n * weight_self*weight_other*(weight_self - weight_other)
+            + three*delta_n * (weight_self * other.avg.sum_2 - weight_other * self.avg.sum_2);
+        self.avg.merge(&other.avg);
+    }
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+impl<T: FloatCore + Float> core::iter::FromIterator<(T, T)> for WeightedSkewness<T> {
+    fn from_iter<I>(iter: I) -> WeightedSkewness<T>
+        where I: IntoIterator<Item=(T, T)>
+    {
+        let mut a = WeightedSkewness::new_generic();
+        for (i, w) in iter {
+            a.add(i, w);
+        }
+        a
+    }
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+impl<'a, T: FloatCore + Float + 'a> core::iter::FromIterator<&'a (T, T)>
+    for WeightedSkewness<T>
+{
+    fn from_iter<I>(iter: I) -> WeightedSkewness<T>
+        where I: IntoIterator<Item=&'a (T, T)>
+    {
+        let mut a = WeightedSkewness::new_generic();
+        for &(i, w) in iter {
+            a.add(i, w);
+        }
+        a
+    }
+}
+
+/// Estimate the weighted arithmetic mean, the weighted variance, the weighted
+/// skewness and the weighted kurtosis of a sequence of numbers ("population").
+///
+/// This can be used to estimate the standard error of the weighted mean.
+///
+///
+/// ## Example
+///
+/// ```
+/// use average::{WeightedKurtosis, Kurtosis};
+///
+/// // Integer weights can be interpreted as repetitions.
+/// let a: WeightedKurtosis = [(1., 1.), (2., 3.), (4., 2.)].iter().cloned().collect();
+/// let b: Kurtosis = [1., 2., 2., 2., 4., 4.].iter().collect();
+/// assert!((a.kurtosis() - b.kurtosis()).abs() < 1e-14);
+/// ```
+#[cfg(any(feature = "std", feature = "libm"))]
+#[cfg_attr(doc_cfg, doc(cfg(any(feature = "std", feature = "libm"))))]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+pub struct WeightedKurtosis<T = f64> {
+    /// Estimator of weighted mean, variance and skewness.
+    avg: WeightedSkewness<T>,
+    /// Intermediate weighted sum of terms to the fourth for calculating the
+    /// kurtosis.
+    sum_4: T,
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+impl WeightedKurtosis<f64> {
+    /// Create a new weighted kurtosis estimator.
+    ///
+    /// Use `new_generic` for other floating point types than `f64`.
+    #[inline]
+    pub fn new() -> WeightedKurtosis<f64> {
+        WeightedKurtosis::new_generic()
+    }
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+impl<T: FloatCore + Float> WeightedKurtosis<T> {
+    /// Create a new weighted kurtosis estimator.
+    #[inline]
+    pub fn new_generic() -> WeightedKurtosis<T> {
+        WeightedKurtosis {
+            avg: WeightedSkewness::new_generic(),
+            sum_4: T::zero(),
+        }
+    }
+
+    /// Increment the sum of the weights.
+    ///
+    /// This does not update anything else.
+    #[inline]
+    fn increment(&mut self, weight: T) {
+        self.avg.increment(weight);
+    }
+
+    /// Add an observation given an already calculated difference from the mean
+    /// divided by the sum of the weights, assuming the sums of the weights
+    /// were already updated.
+    ///
+    /// This is useful for avoiding unnecessary divisions in the inner loop.
+    #[inline]
+    fn add_inner(&mut self, delta: T, delta_n: T, weight: T) {
+        // This is the special case of merging with a single observation of
+        // the algorithm by Pébay et al.
+        //
+        // See https://doi.org/10.1007/s00180-015-0637-z.
+        let prev_weight_sum = self.sum_weights() - weight;
+        let four = T::from(4).unwrap();
+        let six = T::from(6).unwrap();
+        let delta_n_sq = delta_n * delta_n;
+        self.sum_4 = self.sum_4
+            + delta * delta_n * delta_n_sq * prev_weight_sum * weight
+              * (prev_weight_sum*prev_weight_sum - prev_weight_sum*weight + weight*weight)
+            + six * weight*weight * delta_n_sq * self.avg.avg.sum_2
+            - four * weight * delta_n * self.avg.sum_3;
+        self.avg.add_inner(delta, delta_n, weight);
+    }
+
+    /// Add an observation sampled from the population.
+    ///
+    /// Observations with zero weight are ignored.
+    #[inline]
+    pub fn add(&mut self, sample: T, weight: T) {
+        if weight == T::zero() {
+            return;
+        }
+        let delta = sample - self.mean();
+        self.increment(weight);
+        let delta_n = delta / self.sum_weights();
+        self.add_inner(delta, delta_n, weight);
+    }
+
+    /// Determine whether the sample is empty.
+    ///
+    /// Might be a false positive if the sum of weights is zero.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.avg.is_empty()
+    }
+
+    /// Return the sum of the weights.
+    ///
+    /// Returns 0 for an empty sample.
+    #[inline]
+    pub fn sum_weights(&self) -> T {
+        self.avg.sum_weights()
+    }
+
+    /// Return the sum of the squared weights.
+    ///
+    /// Returns 0 for an empty sample.
+    #[inline]
+    pub fn sum_weights_sq(&self) -> T {
+        self.avg.sum_weights_sq()
+    }
+
+    /// Calculate the effective sample size.
+    #[inline]
+    pub fn effective_len(&self) -> T {
+        self.avg.effective_len()
+    }
+
+    /// Estimate the weighted mean of the population.
+    ///
+    /// Returns 0 for an empty sample.
+    #[inline]
+    pub fn mean(&self) -> T {
+        self.avg.mean()
+    }
+
+    /// Calculate the weighted population variance of the sample.
+    ///
+    /// This is a biased estimator of the variance of the population.
+    #[inline]
+    pub fn population_variance(&self) -> T {
+        self.avg.population_variance()
+    }
+
+    /// Calculate the weighted sample variance.
+    ///
+    /// This is an unbiased estimator of the variance of the population,
+    /// assuming the weights are of the given kind.
+    #[inline]
+    pub fn sample_variance(&self, kind: WeightKind) -> T {
+        self.avg.sample_variance(kind)
+    }
+
+    /// Estimate the standard error of the weighted mean of the population.
+    #[inline]
+    pub fn error_mean(&self, kind: WeightKind) -> T {
+        self.avg.error_mean(kind)
+    }
+
+    /// Estimate the weighted skewness of the population.
+    #[inline]
+    pub fn skewness(&self) -> T {
+        self.avg.skewness()
+    }
+
+    /// Estimate the weighted excess kurtosis of the population.
+    #[inline]
+    pub fn kurtosis(&self) -> T {
+        if self.sum_4 == T::zero() {
+            return T::zero();
+        }
+        let sum_2 = self.avg.avg.sum_2;
+        self.sum_weights() * self.sum_4 / (sum_2 * sum_2) - T::from(3).unwrap()
+    }
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+impl<T: FloatCore + Float> core::default::Default for WeightedKurtosis<T> {
+    fn default() -> WeightedKurtosis<T> {
+        WeightedKurtosis::new_generic()
+    }
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+impl<T: FloatCore + Float> Merge for WeightedKurtosis<T> {
+    /// Merge another sample into this one.
+    ///
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use average::{WeightedKurtosis, Merge};
+    ///
+    /// let weighted_sequence: &[(f64, f64)] = &[
+    ///     (1., 0.1), (2., 0.2), (3., 0.3), (4., 0.4), (5., 0.5),
+    ///     (6., 0.6), (7., 0.7), (8., 0.8), (9., 0.9)];
+    /// let (left, right) = weighted_sequence.split_at(3);
+    /// let total: WeightedKurtosis = weighted_sequence.iter().collect();
+    /// let mut merged: WeightedKurtosis = left.iter().collect();
+    /// let right: WeightedKurtosis = right.iter().collect();
+    /// merged.merge(&right);
+    /// assert!((total.mean() - merged.mean()).abs() < 1e-15);
+    /// assert!((total.kurtosis() - merged.kurtosis()).abs() < 1e-14);
+    /// ```
+    #[inline]
+    fn merge(&mut self, other: &WeightedKurtosis<T>) {
+        let weight_self = self.sum_weights();
+        let weight_other = other.sum_weights();
+        let weight_total = weight_self + weight_other;
+        if weight_other == T::zero() {
+            return;
+        }
+        let four = T::from(4).unwrap();
+        let six = T::from(6).unwrap();
+        let delta = other.mean() - self.mean();
+        let delta_n = delta / weight_total;
+        let delta_n_sq = delta_n * delta_n;
+        self.sum_4 = self.sum_4 + other.sum_4
+            + delta * delta_n*delta_n_sq * weight_self*weight_other
+              * (weight_self*weight_self - weight_self*weight_other + weight_other*weight_other)
+            + six*delta_n_sq * (weight_self*weight_self * other.avg.avg.sum_2
+                                + weight_other*weight_other * self.avg.avg.sum_2)
+            + four*delta_n * (weight_self * other.avg.sum_3 - weight_other * self.avg.sum_3);
+        self.avg.merge(&other.avg);
+    }
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+impl<T: FloatCore + Float> core::iter::FromIterator<(T, T)> for WeightedKurtosis<T> {
+    fn from_iter<I>(iter: I) -> WeightedKurtosis<T>
+        where I: IntoIterator<Item=(T, T)>
+    {
+        let mut a = WeightedKurtosis::new_generic();
+        for (i, w) in iter {
+            a.add(i, w);
+        }
+        a
+    }
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+impl<'a, T: FloatCore + Float + 'a> core::iter::FromIterator<&'a (T, T)>
+    for WeightedKurtosis<T>
+{
+    fn from_iter<I>(iter: I) -> WeightedKurtosis<T>
+        where I: IntoIterator<Item=&'a (T, T)>
+    {
+        let mut a = WeightedKurtosis::new_generic();
+        for &(i, w) in iter {
+            a.add(i, w);
+        }
+        a
+    }
+}
@@ -0,0 +1,699 @@
+use num_traits::float::FloatCore;
+#[cfg(any(feature = "std", feature = "libm"))]
+use num_traits::Float;
+#[cfg(feature = "serde1")] use core::convert::TryFrom;
+#[cfg(feature = "serde1")] use serde::{Serialize, Deserialize};
+#[cfg(feature = "serde1")] use serde_big_array::BigArray;
+
+use super::Estimate;
+
+/// Ring buffer storing the last `N` samples.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde1", serde(bound(
+    serialize = "T: Serialize + serde::de::DeserializeOwned",
+    deserialize = "T: Serialize + Deserialize<'de>")))]
+#[cfg_attr(feature = "serde1", serde(try_from = "WindowData<T, N>"))]
+struct Window<T, const N: usize> {
+    /// Stored samples.
+    #[cfg_attr(feature = "serde1", serde(with = "BigArray"))]
+    buf: [T; N],
+    /// Index where the next sample will be stored.
+    next: usize,
+    /// Number of stored samples.
+    len: usize,
+}
+
+/// The serialized fields of a `Window`, which are validated before
+/// deserializing the window.
+#[cfg(feature = "serde1")]
+#[derive(Deserialize)]
+#[serde(bound(deserialize = "T: Serialize + Deserialize<'de>"))]
+struct WindowData<T, const N: usize> {
+    #[serde(with = "BigArray")]
+    buf: [T; N],
+    next: usize,
+    len: usize,
+}
+
+#[cfg(feature = "serde1")]
+impl<T, const N: usize> TryFrom<WindowData<T, N>> for Window<T, N> {
+    type Error = &'static str;
+
+    fn try_from(data: WindowData<T, N>) -> Result<Window<T, N>, &'static str> {
+        // The window is filled from the start of the buffer, so the next index
+        // equals the length until the window is full.
+        if N == 0 || data.next >= N || data.len > N
+            || (data.len < N && data.next != data.len)
+        {
+            return Err("invalid window state");
+        }
+        Ok(Window { buf: data.buf, next: data.next, len: data.len })
+    }
+}
+
+impl<T: FloatCore, const N: usize> Window<T, N> {
+    #[inline]
+    fn new() -> Window<T, N> {
+        assert!(N > 0, "The window must not be empty");
+        Window { buf: [T::zero(); N], next: 0, len: 0 }
+    }
+
+    /// Store a sample, returning the evicted sample if the window was full.
+    #[inline]
+    fn push(&mut self, x: T) -> Option<T> {
+        let evicted = if self.len == N {
+            Some(self.buf[self.next])
+        } else {
+            self.len += 1;
+            None
+        };
+        self.buf[self.next] = x;
+        self.next = (self.next + 1) % N;
+        evicted
+    }
+
+    /// Determine whether the oldest stored sample is at the start of the
+    /// buffer, which happens once every `N` samples.
+    #[inline]
+    fn has_wrapped(&self) -> bool {
+        self.next == 0
+    }
+
+    /// Return the stored samples in no particular order.
+    #[inline]
+    fn samples(&self) -> &[T] {
+        &self.buf[..self.len]
+    }
+
+    /// Calculate the mean of the stored samples from scratch.
+    #[inline]
+    fn mean(&self) -> T {
+        let mut sum = T::zero();
+        for &x in self.samples() {
+            sum = sum + x;
+        }
+        sum / T::from(self.len).unwrap()
+    }
+}
+
+/// Estimate the arithmetic mean of the last `N` numbers of a sequence
+/// ("sliding window").
+///
+/// The samples are stored in a ring buffer, so no allocations are required.
+/// The mean is updated in constant time and periodically recalculated from the
+/// stored samples, such that rounding errors do not accumulate.
+///
+///
+/// ## Example
+///
+/// ```
+/// use average::{WindowedMean, Estimate};
+///
+/// let mut a: WindowedMean<3> = WindowedMean::new();
+/// for x in 1..6 {
+///     a.add(f64::from(x));
+/// }
+/// assert_eq!(a.mean(), 4.);
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde1", serde(bound(
+    serialize = "T: Serialize + serde::de::DeserializeOwned",
+    deserialize = "T: Serialize + Deserialize<'de>")))]
+pub struct WindowedMean<const N: usize, T = f64> {
+    /// Samples in the window.
+    window: Window<T, N>,
+    /// Mean value.
+    avg: T,
+}
+
+impl<const N: usize> WindowedMean<N, f64> {
+    /// Create a new sliding window mean estimator.
+    ///
+    /// Panics if `N` is 0.
+    ///
+    /// Use `new_generic` for other floating point types than `f64`.
+    #[inline]
+    pub fn new() -> WindowedMean<N, f64> {
+        WindowedMean::new_generic()
+    }
+}
+
+impl<const N: usize, T: FloatCore> WindowedMean<N, T> {
+    /// Create a new sliding window mean estimator.
+    ///
+    /// Panics if `N` is 0.
+    #[inline]
+    pub fn new_generic() -> WindowedMean<N, T> {
+        WindowedMean { window: Window::new(), avg: T::zero() }
+    }
+
+    /// Determine whether the window is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.window.len == 0
+    }
+
+    /// Determine whether the window is full, such that adding a sample
+    /// evicts the oldest one.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.window.len == N
+    }
+
+    /// Return the number of samples in the window.
+    #[inline]
+    pub fn len(&self) -> u64 {
+        self.window.len as u64
+    }
+
+    /// Estimate the mean of the samples in the window.
+    ///
+    /// Returns 0 for an empty window.
+    #[inline]
+    pub fn mean(&self) -> T {
+        self.avg
+    }
+}
+
+impl<const N: usize, T: FloatCore> core::default::Default for WindowedMean<N, T> {
+    fn default() -> WindowedMean<N, T> {
+        WindowedMean::new_generic()
+    }
+}
+
+impl<const N: usize, T: FloatCore> Estimate<T> for WindowedMean<N, T> {
+    #[inline]
+    fn add(&mut self, sample: T) {
+        match self.window.push(sample) {
+            Some(evicted) => {
+                if self.window.has_wrapped() {
+                    self.avg = self.window.mean();
+                } else {
+                    self.avg = self.avg + (sample - evicted) / T::from(N).unwrap();
+                }
+            },
+            None => {
+                let len = T::from(self.window.len).unwrap();
+                self.avg = self.avg + (sample - self.avg) / len;
+            },
+        }
+    }
+
+    #[inline]
+    fn estimate(&self) -> T {
+        self.mean()
+    }
+}
+
+impl_from_iterator!(WindowedMean<const N, T>);
+
+/// Estimate the arithmetic mean and the variance of the last `N` numbers of a
+/// sequence ("sliding window").
+///
+/// The samples are stored in a ring buffer, so no allocations are required.
+/// The estimates are updated in constant time and periodically recalculated
+/// from the stored samples, such that rounding errors do not accumulate.
+///
+///
+/// ## Example
+///
+/// ```
+/// use average::{WindowedVariance, Estimate};
+///
+/// let mut a: WindowedVariance<3> = WindowedVariance::new();
+/// for &x in &[10., 1., 2., 3.] {
+///     a.add(x);
+/// }
+/// assert!((a.mean() - 2.).abs() < 1e-15);
+/// assert!((a.sample_variance() - 1.).abs() < 1e-14);
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde1", serde(bound(
+    serialize = "T: Serialize + serde::de::DeserializeOwned",
+    deserialize = "T: Serialize + Deserialize<'de>")))]
+pub struct WindowedVariance<const N: usize, T = f64> {
+    /// Samples in the window.
+    window: Window<T, N>,
+    /// Mean value.
+    avg: T,
+    /// Intermediate sum of squares for calculating the variance.
+    sum_2: T,
+}
+
+impl<const N: usize> WindowedVariance<N, f64> {
+    /// Create a new sliding window variance estimator.
+    ///
+    /// Panics if `N` is 0.
+    ///
+    /// Use `new_generic` for other floating point types than `f64`.
+    #[inline]
+    pub fn new() -> WindowedVariance<N, f64> {
+        WindowedVariance::new_generic()
+    }
+}
+
+impl<const N: usize, T: FloatCore> WindowedVariance<N, T> {
+    /// Create a new sliding window variance estimator.
+    ///
+    /// Panics if `N` is 0.
+    #[inline]
+    pub fn new_generic() -> WindowedVariance<N, T> {
+        WindowedVariance { window: Window::new(), avg: T::zero(), sum_2: T::zero() }
+    }
+
+    /// Recalculate the mean and the sum of squares from the stored samples.
+    #[inline]
+    fn recalculate(&mut self) {
+        self.avg = self.window.mean();
+        let mut sum_2 = T::zero();
+        for &x in self.window.samples() {
+            let delta = x - self.avg;
+            sum_2 = sum_2 + delta * delta;
+        }
+        self.sum_2 = sum_2;
+    }
+
+    /// Determine whether the window is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.window.len == 0
+    }
+
+    /// Determine whether the window is full, such that adding a sample
+    /// evicts the oldest one.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.window.len == N
+    }
+
+    /// Return the number of samples in the window.
+    #[inline]
+    pub fn len(&self) -> u64 {
+        self.window.len as u64
+    }
+
+    /// Estimate the mean of the samples in the window.
+    ///
+    /// Returns 0 for an empty window.
+    #[inline]
+    pub fn mean(&self) -> T {
+        self.avg
+    }
+
+    /// Calculate the sample variance of the window.
+    ///
+    /// This is an unbiased estimator of the variance of the population.
+    /// Returns 0 for less than 2 samples.
+    #[inline]
+    pub fn sample_variance(&self) -> T {
+        let n = self.window.len;
+        if n < 2 {
+            return T::zero();
+        }
+        self.sum_2 / T::from(n - 1).unwrap()
+    }
+
+    /// Calculate the population variance of the window.
+    ///
+    /// This is a biased estimator of the variance of the population.
+    /// Returns 0 for an empty window.
+    #[inline]
+    pub fn population_variance(&self) -> T {
+        let n = self.window.len;
+        if n == 0 {
+            return T::zero();
+        }
+        self.sum_2 / T::from(n).unwrap()
+    }
+
+    /// Estimate the variance of the mean of the population.
+    ///
+    /// Returns 0 for an empty window.
+    #[inline]
+    pub fn variance_of_mean(&self) -> T {
+        let n = self.window.len;
+        if n == 0 {
+            return T::zero();
+        }
+        self.sample_variance() / T::from(n).unwrap()
+    }
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+impl<const N: usize, T: FloatCore + Float> WindowedVariance<N, T> {
+    /// Estimate the standard error of the mean of the population.
+    ///
+    /// Returns 0 for an empty window.
+    #[cfg_attr(doc_cfg, doc(cfg(any(feature = "std", feature = "libm"))))]
+    #[inline]
+    pub fn error(&self) -> T {
+        Float::sqrt(self.variance_of_mean())
+    }
+}
+
+impl<const N: usize, T: FloatCore> core::default::Default for WindowedVariance<N, T> {
+    fn default() -> WindowedVariance<N, T> {
+        WindowedVariance::new_generic()
+    }
+}
+
+impl<const N: usize, T: FloatCore> Estimate<T> for WindowedVariance<N, T> {
+    #[inline]
+    fn add(&mut self, sample: T) {
+        match self.window.push(sample) {
+            Some(evicted) => {
+                if self.window.has_wrapped() {
+                    self.recalculate();
+                    return;
+                }
+                // Replace the evicted sample by the new one.
+                let prev_avg = self.avg;
+                self.avg = self.avg + (sample - evicted) / T::from(N).unwrap();
+                self.sum_2 = self.sum_2
+                    + (sample - evicted) * (sample - self.avg + evicted - prev_avg);
+                // Avoid negative values due to rounding errors.
+                self.sum_2 = self.sum_2.max(T::zero());
+            },
+            None => {
+                // This algorithm was suggested by Welford in 1962.
+                //
+                // See https://en.wikipedia.org/wiki/Algorithms_for_calculating_variance.
+                let len = T::from(self.window.len).unwrap();
+                let delta = sample - self.avg;
+                self.avg = self.avg + delta / len;
+                self.sum_2 = self.sum_2 + delta * (sample - self.avg);
+            },
+        }
+    }
+
+    #[inline]
+    fn estimate(&self) -> T {
+        self.population_variance()
+    }
+}
+
+impl_from_iterator!(WindowedVariance<const N, T>);
+
+/// Double-ended queue of the samples in a window that can still become the
+/// extremum, ordered by the time they were added.
+///
+/// The extremum of the window is always at the front.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde1", serde(bound(
+    serialize = "T: Serialize + serde::de::DeserializeOwned",
+    deserialize = "T: Serialize + Deserialize<'de>")))]
+#[cfg_attr(feature = "serde1", serde(try_from = "MonotonicDequeData<T, N>"))]
+struct MonotonicDeque<T, const N: usize> {
+    /// Stored samples.
+    #[cfg_attr(feature = "serde1", serde(with = "BigArray"))]
+    values: [T; N],
+    /// Positions of the stored samples in the sequence.
+    #[cfg_attr(feature = "serde1", serde(with = "BigArray"))]
+    positions: [u64; N],
+    /// Index of the front of the queue.
+    head: usize,
+    /// Number of stored samples.
+    len: usize,
+    /// Number of samples seen.
+    n: u64,
+}
+
+/// The serialized fields of a `MonotonicDeque`, which are validated before
+/// deserializing the queue.
+#[cfg(feature = "serde1")]
+#[derive(Deserialize)]
+#[serde(bound(deserialize = "T: Serialize + Deserialize<'de>"))]
+struct MonotonicDequeData<T, const N: usize> {
+    #[serde(with = "BigArray")]
+    values: [T; N],
+    #[serde(with = "BigArray")]
+    positions: [u64; N],
+    head: usize,
+    len: usize,
+    n: u64,
+}
+
+#[cfg(feature = "serde1")]
+impl<T, const N: usize> TryFrom<MonotonicDequeData<T, N>> for MonotonicDeque<T, N> {
+    type Error = &'static str;
+
+    fn try_from(data: MonotonicDequeData<T, N>) -> Result<MonotonicDeque<T, N>, &'static str> {
+        if N == 0 || data.head >= N || data.len > N {
+            return Err("invalid window state");
+        }
+        // All stored samples must be among the last `N` samples seen.
+        let in_window = (0..data.len).all(|i| {
+            let position = data.positions[(data.head + i) % N];
+            position < data.n && data.n - position <= N as u64
+        });
+        if !in_window {
+            return Err("invalid window state");
+        }
+        Ok(MonotonicDeque {
+            values: data.values,
+            positions: data.positions,
+            head: data.head,
+            len: data.len,
+            n: data.n,
+        })
+    }
+}
+
+impl<T: FloatCore, const N: usize> MonotonicDeque<T, N> {
+    #[inline]
+    fn new() -> MonotonicDeque<T, N> {
+        assert!(N > 0, "The window must not be empty");
+        MonotonicDeque { values: [T::zero(); N], positions: [0; N], head: 0, len: 0, n: 0 }
+    }
+
+    /// Return the number of samples in the window.
+    #[inline]
+    fn window_len(&self) -> u64 {
+        self.n.min(N as u64)
+    }
+
+    /// Return the front of the queue, if any.
+    #[inline]
+    fn front(&self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        Some(self.values[self.head])
+    }
+
+    /// Add a sample, dropping all stored samples that are not `preferred`
+    /// over it.
+    ///
+    /// `nan` is not stored, but still occupies a position in the window.
+    #[inline]
+    fn push(&mut self, x: T, preferred: fn(T, T) -> bool) {
+        let position = self.n;
+        self.n += 1;
+        // Drop the front if it left the window.
+        if self.len > 0 && self.positions[self.head] + N as u64 <= position {
+            self.head = (self.head + 1) % N;
+            self.len -= 1;
+        }
+        if x.is_nan() {
+            return;
+        }
+        // Drop samples from the back that can no longer become the extremum.
+        while self.len > 0 {
+            let back = (self.head + self.len - 1) % N;
+            if preferred(self.values[back], x) {
+                break;
+            }
+            self.len -= 1;
+        }
+        // All stored samples are in the window, so there is room for one more.
+        debug_assert!(self.len < N);
+        let back = (self.head + self.len) % N;
+        self.values[back] = x;
+        self.positions[back] = position;
+        self.len += 1;
+    }
+}
+
+/// Estimate the minimum of the last `N` numbers of a sequence ("sliding
+/// window").
+///
+/// A monotonic queue stored in fixed-size buffers is used, so no allocations
+/// are required and adding a sample takes amortized constant time. `nan` is
+/// ignored, but still takes up room in the window.
+///
+///
+/// ## Example
+///
+/// ```
+/// use average::{WindowedMin, Estimate};
+///
+/// let mut a: WindowedMin<3> = WindowedMin::new();
+/// for &x in &[1., 5., 3., 4.] {
+///     a.add(x);
+/// }
+/// assert_eq!(a.min(), 3.);
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde1", serde(bound(
+    serialize = "T: Serialize + serde::de::DeserializeOwned",
+    deserialize = "T: Serialize + Deserialize<'de>")))]
+pub struct WindowedMin<const N: usize, T = f64> {
+    deque: MonotonicDeque<T, N>,
+}
+
+impl<const N: usize> WindowedMin<N, f64> {
+    /// Create a new sliding window minimum estimator.
+    ///
+    /// Panics if `N` is 0.
+    ///
+    /// Use `new_generic` for other floating point types than `f64`.
+    #[inline]
+    pub fn new() -> WindowedMin<N, f64> {
+        WindowedMin::new_generic()
+    }
+}
+
+impl<const N: usize, T: FloatCore> WindowedMin<N, T> {
+    /// Create a new sliding window minimum estimator.
+    ///
+    /// Panics if `N` is 0.
+    #[inline]
+    pub fn new_generic() -> WindowedMin<N, T> {
+        WindowedMin { deque: MonotonicDeque::new() }
+    }
+
+    /// Determine whether the window is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.deque.n == 0
+    }
+
+    /// Return the number of samples in the window.
+    #[inline]
+    pub fn len(&self) -> u64 {
+        self.deque.window_len()
+    }
+
+    /// Estimate the minimum of the samples in the window.
+    ///
+    /// Returns `inf` for an empty window.
+    #[inline]
+    pub fn min(&self) -> T {
+        self.deque.front().unwrap_or_else(T::infinity)
+    }
+}
+
+impl<const N: usize, T: FloatCore> core::default::Default for WindowedMin<N, T> {
+    fn default() -> WindowedMin<N, T> {
+        WindowedMin::new_generic()
+    }
+}
+
+impl<const N: usize, T: FloatCore> Estimate<T> for WindowedMin<N, T> {
+    #[inline]
+    fn add(&mut self, x: T) {
+        self.deque.push(x, |stored, new| stored < new);
+    }
+
+    #[inline]
+    fn estimate(&self) -> T {
+        self.min()
+    }
+}
+
+impl_from_iterator!(WindowedMin<const N, T>);
+
+/// Estimate the maximum of the last `N` numbers of a sequence ("sliding
+/// window").
+///
+/// A monotonic queue stored in fixed-size buffers is used, so no allocations
+/// are required and adding a sample takes amortized constant time. `nan` is
+/// ignored, but still takes up room in the window.
+///
+///
+/// ## Example
+///
+/// ```
+/// use average::{WindowedMax, Estimate};
+///
+/// let mut a: WindowedMax<3> = WindowedMax::new();
+/// for &x in &[5., 1., 3., 2.] {
+///     a.add(x);
+/// }
+/// assert_eq!(a.max(), 3.);
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde1", serde(bound(
+    serialize = "T: Serialize + serde::de::DeserializeOwned",
+    deserialize = "T: Serialize + Deserialize<'de>")))]
+pub struct WindowedMax<const N: usize, T = f64> {
+    deque: MonotonicDeque<T, N>,
+}
+
+impl<const N: usize> WindowedMax<N, f64> {
+    /// Create a new sliding window maximum estimator.
+    ///
+    /// Panics if `N` is 0.
+    ///
+    /// Use `new_generic` for other floating point types than `f64`.
+    #[inline]
+    pub fn new() -> WindowedMax<N, f64> {
+        WindowedMax::new_generic()
+    }
+}
+
+impl<const N: usize, T: FloatCore> WindowedMax<N, T> {
+    /// Create a new sliding window maximum estimator.
+    ///
+    /// Panics if `N` is 0.
+    #[inline]
+    pub fn new_generic() -> WindowedMax<N, T> {
+        WindowedMax { deque: MonotonicDeque::new() }
+    }
+
+    /// Determine whether the window is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.deque.n == 0
+    }
+
+    /// Return the number of samples in the window.
+    #[inline]
+    pub fn len(&self) -> u64 {
+        self.deque.window_len()
+    }
+
+    /// Estimate the maximum of the samples in the window.
+    ///
+    /// Returns `-inf` for an empty window.
+    #[inline]
+    pub fn max(&self) -> T {
+        self.deque.front().unwrap_or_else(T::neg_infinity)
+    }
+}
+
+impl<const N: usize, T: FloatCore> core::default::Default for WindowedMax<N, T> {
+    fn default() -> WindowedMax<N, T> {
+        WindowedMax::new_generic()
+    }
+}
+
+impl<const N: usize, T: FloatCore> Estimate<T> for WindowedMax<N, T> {
+    #[inline]
+    fn add(&mut self, x: T) {
+        self.deque.push(x, |stored, new| stored > new);
+    }
+
+    #[inline]
+    fn estimate(&self) -> T {
+        self.max()
+    }
+}
+
+impl_from_iterator!(WindowedMax<const N, T>);
@@ -0,0 +1,17 @@
+use average::{concatenate, Estimate, Max, Min};
+
+concatenate!(MinMax: Merge; [Min, min], [Max, max]);
+
+#[test]
+fn concatenate_rayon() {
+    use average::rayon::prelude::*;
+
+    let s: MinMax = (1..1000).into_par_iter().map(f64::from).collect();
+    assert_eq!(s.min(), 1.0);
+    assert_eq!(s.max(), 999.0);
+
+    let a: Vec<f64> = (1..1000).map(f64::from).collect();
+    let s: MinMax = a.par_iter().collect();
+    assert_eq!(s.min(), 1.0);
+    assert_eq!(s.max(), 999.0);
+}
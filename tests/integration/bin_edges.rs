@@ -0,0 +1,151 @@
+use average::{Estimate, Histogram, Quantile, Variance, define_histogram};
+use average::bin_edges::{BinEdges, MAX_BINS, freedman_diaconis, knuth, scott, sturges};
+
+define_histogram!(hist8, 8);
+define_histogram!(hist11, 11);
+
+#[test]
+fn bin_edges() {
+    let mut edges = BinEdges::new(0., 1., 4);
+    assert_eq!(edges.bins(), 4);
+    assert_eq!(edges.width(), 0.25);
+    assert_eq!(edges.len(), 5);
+    assert_eq!(edges.next(), Some(0.));
+    assert_eq!(edges.len(), 4);
+    let rest: Vec<f64> = edges.collect();
+    assert_eq!(&rest, &[0.25, 0.5, 0.75, 1.]);
+}
+
+#[test]
+#[should_panic]
+fn bin_edges_no_bins() {
+    BinEdges::new(0., 1., 0);
+}
+
+#[test]
+#[should_panic]
+fn bin_edges_too_many_bins() {
+    BinEdges::new(0., 1., usize::MAX);
+}
+
+#[test]
+fn bin_edges_size_hint() {
+    let edges = BinEdges::new(0., 1., usize::MAX - 1);
+    assert_eq!(edges.size_hint(), (usize::MAX, Some(usize::MAX)));
+}
+
+#[test]
+fn sturges_rule() {
+    let edges = sturges(0., 10., 1000);
+    assert_eq!(edges.bins(), 11);
+    let mut h = hist11::Histogram::from_ranges(edges).unwrap();
+    assert_eq!(h.range_min(), 0.);
+    assert!(h.range_max() > 10.);
+    h.add(0.).unwrap();
+    h.add(10.).unwrap();
+    assert_eq!(h.bins()[0], 1);
+    assert_eq!(h.bins()[10], 1);
+
+    assert_eq!(sturges(0., 10., 0).bins(), 1);
+    assert_eq!(sturges(0., 10., 1).bins(), 1);
+    assert_eq!(sturges(0., 10., 2).bins(), 2);
+    assert_eq!(sturges(-1., -1., 5).collect::<Vec<_>>().len(), 5);
+}
+
+#[test]
+fn scott_rule() {
+    let data: Vec<f64> = (0..1000).map(|i| f64::from(i % 100)).collect();
+    let variance: Variance = data.iter().collect();
+    let edges = scott(0., 99., &variance);
+    let width = 3.49 * variance.sample_variance().sqrt() / 10.;
+    assert_eq!(edges.bins(), (99. / width).ceil() as usize);
+    assert!(edges.width() <= width);
+
+    let constant: Variance = [1., 1., 1.].iter().collect();
+    let edges: Vec<f64> = scott(1., 1., &constant).collect();
+    assert_eq!(edges.len(), 2);
+    assert!(edges[0] == 1. && edges[1] > 1.);
+}
+
+#[test]
+fn freedman_diaconis_rule() {
+    let mut lower = Quantile::new(0.25);
+    let mut upper = Quantile::new(0.75);
+    for i in 0..1000 {
+        lower.add(f64::from(i));
+        upper.add(f64::from(i));
+    }
+    let edges = freedman_diaconis(0., 999., &lower, &upper);
+    let width = 2. * (upper.quantile() - lower.quantile()) / 10.;
+    assert_eq!(edges.bins(), (999. / width).ceil() as usize);
+    assert_eq!(edges.bins(), 10);
+}
+
+#[test]
+fn infinite_range() {
+    let variance: Variance = [1., 2., 3.].iter().collect();
+    let edges = scott(-1e300, 1e300, &variance);
+    assert_eq!(edges.bins(), MAX_BINS);
+    assert_eq!(edges.count(), MAX_BINS + 1);
+
+    // The width of the range overflows.
+    let edges = scott(-std::f64::MAX, std::f64::MAX, &variance);
+    assert_eq!(edges.bins(), 1);
+    let edges: Vec<f64> = edges.collect();
+    assert_eq!(&edges, &[-std::f64::MAX, std::f64::INFINITY]);
+}
+
+#[test]
+fn outlier() {
+    // A single outlier and a tiny interquartile range would require about
+    // 10^16 bins.
+    let mut lower = Quantile::new(0.25);
+    let mut upper = Quantile::new(0.75);
+    for i in 0..1000 {
+        let x = 1. + f64::from(i) * 1e-9;
+        lower.add(x);
+        upper.add(x);
+    }
+    lower.add(1e9);
+    upper.add(1e9);
+    let edges = freedman_diaconis(1., 1e9, &lower, &upper);
+    assert_eq!(edges.bins(), MAX_BINS);
+    assert_eq!(edges.count(), MAX_BINS + 1);
+
+    let variance: Variance = [0., 1e-6].iter().collect();
+    assert_eq!(scott(0., 1e9, &variance).bins(), MAX_BINS);
+}
+
+#[test]
+#[should_panic]
+fn freedman_diaconis_swapped() {
+    freedman_diaconis(0., 1., &Quantile::new(0.75), &Quantile::new(0.25));
+}
+
+#[test]
+fn knuth_rule() {
+    let mut h = hist8::Histogram::with_const_width(0., 8.);
+    for i in 0..400 {
+        h.add(f64::from(i % 4)).unwrap();
+    }
+    let edges: Vec<f64> = knuth(&h).collect();
+    assert_eq!(&edges, &[0., 4., 8.]);
+
+    let mut h = hist8::Histogram::with_const_width(0., 8.);
+    for i in 0..800 {
+        h.add(f64::from(i % 8)).unwrap();
+    }
+    assert_eq!(knuth(&h).bins(), 1);
+
+    let mut h = hist8::Histogram::with_const_width(0., 8.);
+    for i in 0..800 {
+        let x = i % 8;
+        for _ in 0..x * x {
+            h.add(f64::from(x)).unwrap();
+        }
+    }
+    assert_eq!(knuth(&h).bins(), 8);
+
+    let h = hist8::Histogram::with_const_width(0., 8.);
+    assert_eq!(knuth(&h).bins(), 1);
+}
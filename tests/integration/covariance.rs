@@ -0,0 +1,106 @@
+use core::iter::Iterator;
+
+use average::{Covariance, Merge, assert_almost_eq};
+
+#[test]
+fn trivial() {
+    let mut a = Covariance::new();
+    assert_eq!(a.len(), 0);
+    assert!(a.is_empty());
+    a.add(1.0, 2.0);
+    assert_eq!(a.len(), 1);
+    assert_eq!(a.mean_x(), 1.0);
+    assert_eq!(a.mean_y(), 2.0);
+    assert_eq!(a.sample_covariance(), 0.0);
+    assert_eq!(a.population_covariance(), 0.0);
+    a.add(1.0, 2.0);
+    assert_eq!(a.len(), 2);
+    assert_eq!(a.mean_x(), 1.0);
+    assert_eq!(a.mean_y(), 2.0);
+    assert_eq!(a.sample_covariance(), 0.0);
+    assert_eq!(a.population_covariance(), 0.0);
+}
+
+#[test]
+fn simple() {
+    let a: Covariance = [(1., 2.), (2., 4.), (3., 5.), (4., 4.), (5., 5.)]
+        .iter().collect();
+    assert_eq!(a.len(), 5);
+    assert_eq!(a.mean_x(), 3.0);
+    assert_eq!(a.mean_y(), 4.0);
+    assert_eq!(a.sample_covariance(), 1.5);
+    assert_eq!(a.population_covariance(), 1.2);
+    #[cfg(any(feature = "std", feature = "libm"))]
+    assert_almost_eq!(a.pearson(), 0.7745966692414834, 1e-15);
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+#[test]
+fn perfect_correlation() {
+    let a: Covariance = (1..6).map(|x| (f64::from(x), f64::from(3 - 2*x))).collect();
+    assert_eq!(a.sample_covariance(), -5.0);
+    assert_eq!(a.pearson(), -1.0);
+}
+
+#[cfg(feature = "serde1")]
+#[test]
+fn simple_serde() {
+    let a: Covariance = (1..6).map(|x| (f64::from(x), f64::from(2 * x))).collect();
+    let b = serde_json::to_string(&a).unwrap();
+    assert_eq!(&b, "{\"x\":{\"avg\":{\"avg\":3.0,\"n\":5},\"sum_2\":10.0},\"y\":{\"avg\":{\"avg\":6.0,\"n\":5},\"sum_2\":40.0},\"sum_xy\":20.0}");
+    let c: Covariance = serde_json::from_str(&b).unwrap();
+    assert_eq!(c.len(), 5);
+    assert_eq!(c.sample_covariance(), 5.0);
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn simple_rayon() {
+    use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+    let a: Covariance = (1..6).into_par_iter()
+        .map(|x| (f64::from(x), f64::from(2 * x))).collect();
+    assert_eq!(a.len(), 5);
+    assert_eq!(a.mean_x(), 3.0);
+    assert_eq!(a.mean_y(), 6.0);
+    assert_eq!(a.sample_covariance(), 5.0);
+}
+
+#[test]
+fn simple_f32() {
+    let a: Covariance<f32> = [(1., 2.), (2., 4.), (3., 5.), (4., 4.), (5., 5.)]
+        .iter().collect();
+    assert_eq!(a.mean_x(), 3.0f32);
+    assert_eq!(a.mean_y(), 4.0f32);
+    assert_eq!(a.sample_covariance(), 1.5f32);
+}
+
+#[test]
+fn numerically_unstable() {
+    // The naive algorithm fails for this example due to cancelation.
+    let big = 1e9;
+    let sample = &[(big + 4., big + 7.), (big + 7., big + 13.),
+                   (big + 13., big + 16.), (big + 16., big + 4.)];
+    let a: Covariance = sample.iter().collect();
+    assert_eq!(a.sample_covariance(), -3.);
+}
+
+#[test]
+fn merge() {
+    let sequence: &[(f64, f64)] = &[
+        (1., 2.), (2., 3.), (3., 5.), (4., 4.), (5., 7.),
+        (6., 9.), (7., 8.), (8., 10.), (9., 12.)];
+    for mid in 0..sequence.len() {
+        let (left, right) = sequence.split_at(mid);
+        let cov_total: Covariance = sequence.iter().collect();
+        let mut cov_left: Covariance = left.iter().collect();
+        let cov_right: Covariance = right.iter().collect();
+        cov_left.merge(&cov_right);
+        assert_eq!(cov_total.len(), cov_left.len());
+        assert_almost_eq!(cov_total.mean_x(), cov_left.mean_x(), 1e-14);
+        assert_almost_eq!(cov_total.mean_y(), cov_left.mean_y(), 1e-14);
+        assert_almost_eq!(cov_total.sample_covariance(), cov_left.sample_covariance(), 1e-14);
+        #[cfg(any(feature = "std", feature = "libm"))]
+        assert_almost_eq!(cov_total.pearson(), cov_left.pearson(), 1e-14);
+    }
+}
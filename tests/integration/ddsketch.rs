@@ -0,0 +1,165 @@
+use rand::SeedableRng;
+use rand_distr::Distribution;
+use rand_xoshiro::Xoshiro256StarStar;
+
+use average::{DdSketch, Estimate, Merge, assert_almost_eq};
+
+/// Check that the estimated quantiles of the sketch have at most the given
+/// relative error, given the sorted sample.
+fn check_relative_error(s: &DdSketch, sorted: &[f64], alpha: f64) {
+    let n = sorted.len();
+    for i in 1..100 {
+        let p = f64::from(i) / 100.;
+        let exact = sorted[(p * (n - 1) as f64) as usize];
+        let estimate = s.quantile(p);
+        assert!((estimate - exact).abs() <= alpha * exact.abs() * (1. + 1e-12),
+            "p = {}: {} differs from {}", p, estimate, exact);
+    }
+}
+
+#[test]
+fn few_observations() {
+    let mut s = DdSketch::new();
+    assert!(s.is_empty());
+    assert_eq!(s.quantile(0.5), 0.);
+    s.add(1.);
+    assert_eq!(s.len(), 1);
+    assert_eq!(s.quantile(0.5), 1.);
+    s.add(-2.);
+    s.add(0.);
+    s.add(f64::NAN);
+    s.add(f64::INFINITY);
+    assert_eq!(s.len(), 3);
+    assert_eq!(s.buckets(), 3);
+    assert_eq!(s.quantile(0.), -2.);
+    assert_eq!(s.quantile(0.5), 0.);
+    assert_eq!(s.estimate(), 0.);
+    assert_eq!(s.quantile(1.), 1.);
+    assert_almost_eq!(s.quantile(0.1), -2., 0.02 * 2.);
+    assert_eq!(s.quantile(0.9), 0.);
+}
+
+#[test]
+fn few_observations_f32() {
+    let s: DdSketch<f32> = (1..6).map(|i| i as f32).collect();
+    assert_eq!(s.relative_accuracy(), 0.01f32);
+    assert_almost_eq!(s.quantile(0.5), 3f32, 0.03);
+}
+
+#[test]
+#[should_panic]
+fn invalid_accuracy() {
+    DdSketch::with_relative_accuracy(1.);
+}
+
+#[test]
+#[should_panic(expected = "too small")]
+fn too_small_accuracy() {
+    DdSketch::<f32>::with_relative_accuracy(1e-9);
+}
+
+#[test]
+fn extreme_magnitudes() {
+    let mut s = DdSketch::with_relative_accuracy(1e-8);
+    s.add(1e300);
+    s.add(1e-300);
+    s.add(f64::MAX);
+    s.add(f64::MIN_POSITIVE);
+    // Only the nonempty buckets are stored.
+    assert_eq!(s.buckets(), 4);
+    assert_almost_eq!(s.quantile(0.4) / 1e-300, 1., 1e-8);
+    assert_almost_eq!(s.quantile(0.9) / 1e300, 1., 1e-8);
+}
+
+#[test]
+fn relative_error() {
+    // Latencies spanning six orders of magnitude.
+    let mut rng = Xoshiro256StarStar::seed_from_u64(42);
+    let lognormal = rand_distr::LogNormal::<f64>::new(0., 3.).unwrap();
+    let mut samples: Vec<f64> = (0..100_000).map(|_| lognormal.sample(&mut rng)).collect();
+    for &alpha in &[0.05, 0.01, 0.001] {
+        let mut s = DdSketch::with_relative_accuracy(alpha);
+        for &x in &samples {
+            s.add(x);
+        }
+        samples.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        check_relative_error(&s, &samples, alpha);
+    }
+
+    // Negative samples.
+    let negated: DdSketch = samples.iter().map(|x| -x).collect();
+    assert_almost_eq!(negated.quantile(0.5), -samples[50_000], 0.01 * samples[50_000]);
+}
+
+#[test]
+fn collapsing() {
+    let samples: Vec<f64> = (0..10_000).map(|i| 1.01f64.powi(i) * 1e-20).collect();
+    let mut s = DdSketch::with_max_buckets(0.01, 100);
+    assert_eq!(s.max_buckets(), Some(100));
+    for &x in &samples {
+        s.add(x);
+    }
+    assert!(s.buckets() <= 100);
+    assert_eq!(s.len(), 10_000);
+    // The largest samples are still accurate.
+    for &p in &[0.995, 0.999] {
+        let exact = samples[(p * 9999.) as usize];
+        assert_almost_eq!(s.quantile(p) / exact, 1., 0.01);
+    }
+    // The smallest ones are not.
+    assert!(s.quantile(0.5) > 2. * samples[5_000]);
+
+    // Adding samples below the collapsed buckets does not grow the sketch.
+    s.add(1e-300);
+    assert!(s.buckets() <= 100);
+    assert_eq!(s.quantile(0.), 1e-300);
+}
+
+#[test]
+fn merge() {
+    let sequence: &[f64] = &[-4., -3., -2., -1., 0., 1., 2., 3., 4.];
+    for mid in 0..sequence.len() {
+        let (left, right) = sequence.split_at(mid);
+        let mut s: DdSketch = left.iter().collect();
+        let t: DdSketch = right.iter().collect();
+        s.merge(&t);
+        assert_eq!(s.len(), 9);
+        assert_eq!(s.quantile(0.), -4.);
+        assert_eq!(s.quantile(0.5), 0.);
+        assert_eq!(s.quantile(1.), 4.);
+        assert_almost_eq!(s.quantile(0.25), -2., 0.02);
+        assert_almost_eq!(s.quantile(0.75), 2., 0.02);
+    }
+}
+
+#[test]
+fn merge_collapsing() {
+    let mut s = DdSketch::with_max_buckets(0.01, 50);
+    let t: DdSketch = (0..1000).map(|i| 1.02f64.powi(i)).collect();
+    s.merge(&t);
+    assert_eq!(s.len(), 1000);
+    assert!(s.buckets() <= 50);
+    assert_almost_eq!(s.quantile(0.999) / 1.02f64.powi(998), 1., 0.01);
+}
+
+#[test]
+#[should_panic]
+fn merge_different_accuracy() {
+    let mut s: DdSketch = DdSketch::with_relative_accuracy(0.01);
+    s.merge(&DdSketch::with_relative_accuracy(0.02));
+}
+
+#[cfg(feature = "serde1")]
+#[test]
+fn simple_serde() {
+    let mut s: DdSketch = [1., 0., -1.].iter().collect();
+    let b = serde_json::to_string(&s).unwrap();
+    assert_eq!(&b, "{\"alpha\":0.01,\"gamma\":1.02020202020202,\
+        \"max_buckets\":null,\"positive\":{\"counts\":{\"0\":1}},\
+        \"negative\":{\"counts\":{\"0\":1}},\"zero\":1,\"min\":-1.0,\
+        \"max\":1.0,\"len\":3}");
+    let mut t: DdSketch = serde_json::from_str(&b).unwrap();
+    s.add(10.);
+    t.add(10.);
+    assert_eq!(t.quantile(0.9), s.quantile(0.9));
+}
@@ -0,0 +1,251 @@
+use average::{DynHistogram, Histogram, Merge, define_histogram};
+use average::{IncompatibleRangesError, InvalidRangeError, SampleOutOfRangeError};
+
+define_histogram!(hist10, 10);
+
+#[test]
+fn with_const_width() {
+    let mut h = DynHistogram::with_const_width(-30., 70., 10);
+    for i in -30..70 {
+        h.add(f64::from(i)).unwrap();
+    }
+    assert_eq!(h.num_bins(), 10);
+    assert_eq!(h.bins(), &[10, 10, 10, 10, 10, 10, 10, 10, 10, 10]);
+}
+
+#[test]
+#[should_panic]
+fn with_const_width_no_bins() {
+    DynHistogram::with_const_width(0., 1., 0);
+}
+
+#[test]
+fn same_as_macro() {
+    let ranges = [0., 0.1, 0.2, 0.3, 0.4, 0.5, 0.7, 0.8, 0.9, 1.0, 2.0];
+    let mut a = DynHistogram::from_ranges(ranges.iter().cloned()).unwrap();
+    let mut b = hist10::Histogram::from_ranges(ranges.iter().cloned()).unwrap();
+    for &i in &[0.05, 0.7, 1.0, 1.5, 2.0, -1.] {
+        assert_eq!(a.add(i), b.add(i));
+    }
+    assert_eq!(a.ranges(), b.ranges());
+    assert_eq!(a.bins(), &[1, 0, 0, 0, 0, 0, 1, 0, 0, 2]);
+    assert!(a.iter().eq(b.iter()));
+    assert!(a.centers().eq(b.centers()));
+}
+
+#[test]
+fn from_ranges_invalid() {
+    assert_eq!(
+        DynHistogram::from_ranges([0.].iter().cloned()).unwrap_err(),
+        InvalidRangeError::NotEnoughRanges
+    );
+    assert_eq!(
+        DynHistogram::from_ranges([0., std::f64::NAN].iter().cloned()).unwrap_err(),
+        InvalidRangeError::NaN
+    );
+    assert_eq!(
+        DynHistogram::from_ranges([0., 2., 1.].iter().cloned()).unwrap_err(),
+        InvalidRangeError::NotSorted
+    );
+    let h = DynHistogram::from_ranges([0., 1.].iter().cloned()).unwrap();
+    assert_eq!(h.bins(), &[0]);
+}
+
+#[test]
+fn out_of_range() {
+    let mut h = DynHistogram::with_const_width(0., 100., 7);
+    assert_eq!(h.add(-0.1), Err(SampleOutOfRangeError));
+    assert_eq!(h.add(0.0), Ok(()));
+    assert_eq!(h.add(99.9), Ok(()));
+    assert_eq!(h.add(100.0), Err(SampleOutOfRangeError));
+    assert_eq!(h.range_min(), 0.);
+    assert_eq!(h.range_max(), 100.);
+}
+
+#[test]
+fn reset() {
+    let mut h = DynHistogram::with_const_width(0., 100., 4);
+    for i in 0..100 {
+        h.add(f64::from(i)).unwrap();
+    }
+    assert!(h.has_samples());
+    h.reset();
+    assert!(!h.has_samples());
+    assert_eq!(h.bins(), &[0, 0, 0, 0]);
+}
+
+#[test]
+fn add_mul_merge() {
+    let mut h1 = DynHistogram::with_const_width(0., 100., 5);
+    let mut h2 = h1.clone();
+    for i in 0..50 {
+        h1.add(f64::from(i)).unwrap();
+    }
+    for i in 50..100 {
+        h2.add(f64::from(i)).unwrap();
+    }
+    let mut h3 = h1.clone();
+    h1 += &h2;
+    h3.merge(&h2);
+    assert_eq!(h1.bins(), &[20, 20, 20, 20, 20]);
+    assert_eq!(h1.bins(), h3.bins());
+    h1 *= 3;
+    assert_eq!(h1.bins(), &[60, 60, 60, 60, 60]);
+}
+
+#[test]
+#[should_panic]
+fn merge_different_bins() {
+    let mut a = DynHistogram::with_const_width(0., 1., 2);
+    let b = DynHistogram::with_const_width(0., 1., 3);
+    a.merge(&b);
+}
+
+#[cfg(feature = "serde1")]
+#[test]
+fn simple_serde() {
+    let mut a = DynHistogram::from_ranges([0., 0.5, 1., 2.].iter().cloned()).unwrap();
+    for &i in &[0.05, 0.7, 1.0, 1.5] {
+        a.add(i).unwrap();
+    }
+    let b = serde_json::to_string(&a).unwrap();
+    assert_eq!(&b, "{\"range\":[0.0,0.5,1.0,2.0],\"bin\":[1,1,2]}");
+    let c: DynHistogram = serde_json::from_str(&b).unwrap();
+    assert_eq!(c.bins(), &[1, 1, 2]);
+}
+
+#[cfg(feature = "serde1")]
+#[test]
+fn serde_invalid() {
+    for json in &[
+        "{\"range\":[0.0,1.0],\"bin\":[1,2,3]}",
+        "{\"range\":[0.0],\"bin\":[]}",
+        "{\"range\":[1.0,0.0],\"bin\":[1]}",
+    ] {
+        assert!(serde_json::from_str::<DynHistogram>(json).is_err(), "{}", json);
+    }
+}
+
+#[test]
+fn count_outliers() {
+    let mut h = DynHistogram::with_const_width(0., 1., 2).count_outliers();
+    for &x in &[-1., 0.2, 1., std::f64::NAN] {
+        assert_eq!(h.add(x), Ok(()));
+    }
+    assert_eq!(h.bins(), &[1, 0]);
+    assert_eq!(h.outliers(), 3);
+    let iterated: Vec<((f64, f64), u64)> = h.iter_with_outliers().collect();
+    assert_eq!(&iterated, &[
+        ((-std::f64::INFINITY, 0.), 1), ((0., 0.5), 1), ((0.5, 1.), 0),
+        ((1., std::f64::INFINITY), 1),
+    ]);
+    let mut g = h.clone();
+    g += &h;
+    assert_eq!(g.outlier_counts().unwrap().nan(), 2);
+}
+
+#[test]
+fn spaced_constructors() {
+    let h = DynHistogram::with_geometric_ratio(1., 10., 3);
+    assert_eq!(h.ranges(), &[1., 10., 100., 1000.]);
+    assert_eq!(h.find(99.), Ok(1));
+    #[cfg(any(feature = "std", feature = "libm"))]
+    {
+        let h = DynHistogram::with_log_width(1., 1000., 3);
+        assert_eq!(h.find(100.), Ok(2));
+        assert_eq!(h.find(1000.), Err(SampleOutOfRangeError));
+    }
+    let h = DynHistogram::from_quantile_edges(|p| 4. * p, 4).unwrap();
+    assert_eq!(h.ranges(), &[0., 1., 2., 3., 4.]);
+    assert!(DynHistogram::from_quantile_edges(|p| p, 0).is_err());
+}
+
+#[test]
+fn rebin() {
+    let mut h = DynHistogram::with_const_width(0., 6., 6);
+    for i in 0..6 {
+        for _ in 0..=i {
+            h.add(f64::from(i)).unwrap();
+        }
+    }
+    let r = h.rebin(3);
+    assert_eq!(r.ranges(), &[0., 2., 4., 6.]);
+    assert_eq!(r.bins(), &[3, 7, 11]);
+    assert_eq!(r.find(3.), Ok(1));
+    assert_eq!(h.rebin(1).bins(), &[21]);
+    assert_eq!(h.rebin(6).bins(), h.bins());
+}
+
+#[test]
+#[should_panic]
+fn rebin_not_multiple() {
+    DynHistogram::with_const_width(0., 6., 6).rebin(4);
+}
+
+#[test]
+fn slice() {
+    let mut h = DynHistogram::with_const_width(0., 6., 6).count_outliers();
+    for i in 0..6 {
+        h.add(f64::from(i) + 0.5).unwrap();
+    }
+    h.add(-1.).unwrap();
+    let s = h.slice(1.5, 3.9).unwrap();
+    assert_eq!(s.ranges(), &[1., 2., 3., 4.]);
+    assert_eq!(s.bins(), &[1, 1, 1]);
+    let outliers = s.outlier_counts().unwrap();
+    assert_eq!(outliers.underflow(), 2);
+    assert_eq!(outliers.overflow(), 2);
+    assert_eq!(h.slice(1.5, 4.).unwrap().ranges(), &[1., 2., 3., 4., 5.]);
+    assert_eq!(s.total() + s.outliers(), h.total() + h.outliers());
+
+    assert_eq!(h.slice(-10., 10.).unwrap().bins(), h.bins());
+    assert_eq!(h.slice(2., 2.).unwrap().ranges(), &[2., 3.]);
+    assert_eq!(h.slice(3., 2.).unwrap_err(), InvalidRangeError::NotSorted);
+    assert_eq!(h.slice(6., 7.).unwrap_err(), InvalidRangeError::NotEnoughRanges);
+    assert_eq!(h.slice(std::f64::NAN, 7.).unwrap_err(), InvalidRangeError::NaN);
+}
+
+#[test]
+fn try_merge() {
+    let mut coarse = DynHistogram::from_ranges([0., 2., 4.].iter().cloned()).unwrap();
+    let mut fine = DynHistogram::from_ranges([0., 1., 2., 3., 4.].iter().cloned()).unwrap();
+    coarse.add(0.5).unwrap();
+    for &x in &[0.5, 1.5, 3.5] {
+        fine.add(x).unwrap();
+    }
+
+    let mut a = coarse.clone();
+    a.try_merge(&fine).unwrap();
+    assert_eq!(a.ranges(), &[0., 2., 4.]);
+    assert_eq!(a.bins(), &[3, 1]);
+
+    let mut b = fine.clone();
+    b.try_merge(&coarse).unwrap();
+    assert_eq!(b.ranges(), a.ranges());
+    assert_eq!(b.bins(), a.bins());
+
+    let shifted = DynHistogram::from_ranges([0.5, 1.5, 2.5].iter().cloned()).unwrap();
+    let mut c = coarse.clone();
+    assert_eq!(c.try_merge(&shifted), Err(IncompatibleRangesError));
+    assert_eq!(c.bins(), coarse.bins());
+    assert_eq!(c.try_merge(&coarse.clone().count_outliers()), Err(IncompatibleRangesError));
+}
+
+#[test]
+fn try_merge_outliers() {
+    let mut coarse = DynHistogram::with_const_width(0., 10., 5).count_outliers();
+    let mut fine = DynHistogram::with_const_width(2., 4., 2).count_outliers();
+    for &x in &[1., 3., 5.] {
+        coarse.add(x).unwrap();
+        fine.add(x).unwrap();
+    }
+    // The outliers of the finer histogram would fall into the coarser bins.
+    let mut c = coarse.clone();
+    assert_eq!(c.try_merge(&fine), Err(IncompatibleRangesError));
+    assert_eq!(c.bins(), coarse.bins());
+    assert_eq!(fine.clone().try_merge(&coarse), Err(IncompatibleRangesError));
+
+    let fine = DynHistogram::with_const_width(0., 10., 10).count_outliers();
+    c.try_merge(&fine).unwrap();
+    assert_eq!(c.ranges(), coarse.ranges());
+}
@@ -0,0 +1,109 @@
+use average::{ExpMean, ExpVariance, Estimate, assert_almost_eq};
+
+#[test]
+fn trivial() {
+    let mut a = ExpVariance::new(0.1);
+    assert!(a.is_empty());
+    assert_eq!(a.len(), 0);
+    assert_eq!(a.mean(), 0.);
+    assert_eq!(a.variance(), 0.);
+    a.add(2.);
+    assert_eq!(a.len(), 1);
+    assert_eq!(a.mean(), 2.);
+    assert_eq!(a.variance(), 0.);
+    a.add(2.);
+    assert_eq!(a.mean(), 2.);
+    assert_eq!(a.variance(), 0.);
+}
+
+#[test]
+fn simple() {
+    let alpha: f64 = 0.25;
+    let samples = [3., 1., 4., 1., 5., 9., 2., 6.];
+    let mut a = ExpMean::new(alpha);
+    let mut b = ExpVariance::new(alpha);
+    for &x in &samples {
+        a.add(x);
+        b.add(x);
+    }
+    // Compare to the explicitly weighted sums, where the first sample
+    // carries the remaining weight.
+    let n = samples.len();
+    let weights: Vec<f64> = (0..n).map(|i| if i == 0 {
+        (1. - alpha).powi(n as i32 - 1)
+    } else {
+        alpha * (1. - alpha).powi((n - 1 - i) as i32)
+    }).collect();
+    assert_almost_eq!(weights.iter().sum::<f64>(), 1., 1e-15);
+    let mean: f64 = samples.iter().zip(&weights).map(|(x, w)| x * w).sum();
+    let var: f64 = samples.iter().zip(&weights)
+        .map(|(x, w)| w * (x - mean) * (x - mean)).sum();
+    assert_eq!(a.len(), 8);
+    assert_almost_eq!(a.mean(), mean, 1e-14);
+    assert_almost_eq!(a.estimate(), mean, 1e-14);
+    assert_eq!(b.mean(), a.mean());
+    assert_almost_eq!(b.variance(), var, 1e-14);
+    assert_almost_eq!(b.estimate(), var, 1e-14);
+}
+
+#[test]
+fn simple_f32() {
+    let mut a = ExpVariance::<f32>::new_generic(0.5);
+    a.add(1.);
+    a.add(3.);
+    assert_eq!(a.mean(), 2.0f32);
+    assert_eq!(a.variance(), 1.0f32);
+}
+
+#[test]
+fn alpha_one() {
+    let mut a = ExpVariance::new(1.);
+    for &x in &[1., 7., 3.] {
+        a.add(x);
+        assert_eq!(a.mean(), x);
+        assert_eq!(a.variance(), 0.);
+    }
+}
+
+#[test]
+fn forgets_history() {
+    let mut a: ExpMean = ExpMean::new(0.2);
+    for _ in 0..1000 {
+        a.add(100.);
+    }
+    for _ in 0..200 {
+        a.add(1.);
+    }
+    assert_almost_eq!(a.mean(), 1., 1e-15);
+}
+
+#[test]
+#[should_panic]
+fn invalid_alpha() {
+    ExpMean::new(0.);
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+#[test]
+fn half_life() {
+    let mut a: ExpVariance = ExpVariance::with_half_life(10.);
+    assert_almost_eq!((1. - a.alpha()).powi(10), 0.5, 1e-15);
+    a.add(1.);
+    a.add(3.);
+    assert_almost_eq!(a.standard_deviation(), a.variance().sqrt(), 1e-15);
+    let b = ExpMean::<f32>::with_half_life(1.);
+    assert_eq!(b.alpha(), 0.5);
+}
+
+#[cfg(feature = "serde1")]
+#[test]
+fn simple_serde() {
+    let mut a = ExpVariance::new(0.5);
+    a.add(1.);
+    a.add(3.);
+    let b = serde_json::to_string(&a).unwrap();
+    assert_eq!(&b, "{\"avg\":{\"alpha\":0.5,\"avg\":2.0,\"n\":2},\"var\":1.0}");
+    let c: ExpVariance = serde_json::from_str(&b).unwrap();
+    assert_eq!(c.mean(), 2.);
+    assert_eq!(c.variance(), 1.);
+}
@@ -0,0 +1,104 @@
+use rand::SeedableRng;
+use rand::seq::SliceRandom;
+use rand_xoshiro::Xoshiro256StarStar;
+
+use average::{Estimate, GkQuantiles};
+
+/// Check that the rank of the estimated p-quantile of `0..n` is within the
+/// error bound.
+fn check_rank_error(q: &GkQuantiles, n: u32) {
+    let bound = q.epsilon() * f64::from(n);
+    for i in 0..=100 {
+        let p = f64::from(i) / 100.;
+        // The rank of sample `x` is `x + 1`.
+        let rank = q.quantile(p) + 1.;
+        assert!((rank - p * f64::from(n)).abs() <= bound + 1.,
+            "p = {}: rank {} exceeds error bound", p, rank);
+    }
+}
+
+#[test]
+fn few_observations() {
+    let mut q = GkQuantiles::new();
+    assert!(q.is_empty());
+    assert_eq!(q.quantile(0.5), 0.);
+    q.add(3.);
+    assert_eq!(q.len(), 1);
+    assert_eq!(q.quantile(0.5), 3.);
+    q.add(1.);
+    q.add(2.);
+    assert_eq!(q.len(), 3);
+    assert_eq!(q.retained(), 3);
+    assert_eq!(q.quantile(0.), 1.);
+    assert_eq!(q.quantile(0.5), 2.);
+    assert_eq!(q.estimate(), 2.);
+    assert_eq!(q.quantile(1.), 3.);
+}
+
+#[test]
+fn few_observations_f32() {
+    let q: GkQuantiles<f32> = (1..6).map(|i| i as f32).collect();
+    assert_eq!(q.quantile(0.5), 3f32);
+}
+
+#[test]
+fn nan_is_ignored() {
+    let mut q = GkQuantiles::new();
+    q.add(f64::NAN);
+    q.add(1.);
+    assert_eq!(q.len(), 1);
+    assert_eq!(q.quantile(1.), 1.);
+}
+
+#[test]
+#[should_panic]
+fn invalid_epsilon() {
+    GkQuantiles::with_epsilon(0.);
+}
+
+#[test]
+fn rank_error() {
+    let n = 100_000;
+    let mut samples: Vec<f64> = (0..n).map(f64::from).collect();
+    for &epsilon in &[0.05, 0.01, 0.001] {
+        let ascending: GkQuantiles = {
+            let mut q = GkQuantiles::with_epsilon(epsilon);
+            for &x in &samples {
+                q.add(x);
+            }
+            q
+        };
+        assert_eq!(ascending.epsilon(), epsilon);
+        assert_eq!(ascending.len(), u64::from(n));
+        assert_eq!(ascending.quantile(0.), 0.);
+        assert_eq!(ascending.quantile(1.), f64::from(n - 1));
+        check_rank_error(&ascending, n);
+
+        samples.shuffle(&mut Xoshiro256StarStar::seed_from_u64(42));
+        let mut shuffled = GkQuantiles::with_epsilon(epsilon);
+        for &x in &samples {
+            shuffled.add(x);
+        }
+        check_rank_error(&shuffled, n);
+        samples.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    }
+}
+
+#[test]
+fn space() {
+    let q: GkQuantiles = (0..100_000).map(f64::from).collect();
+    assert!(q.retained() < 1000, "retained {} samples", q.retained());
+}
+
+#[cfg(feature = "serde1")]
+#[test]
+fn simple_serde() {
+    let mut q: GkQuantiles = (1..3).map(f64::from).collect();
+    let b = serde_json::to_string(&q).unwrap();
+    assert_eq!(&b, "{\"epsilon\":0.01,\"tuples\":[{\"v\":1.0,\"g\":1,\"delta\":0},\
+        {\"v\":2.0,\"g\":1,\"delta\":0}],\"len\":2}");
+    let mut r: GkQuantiles = serde_json::from_str(&b).unwrap();
+    q.add(3.);
+    r.add(3.);
+    assert_eq!(r.quantile(0.5), q.quantile(0.5));
+}
@@ -0,0 +1,137 @@
+use average::{IncompatibleRangesError, define_histogram, assert_almost_eq};
+use average::goodness_of_fit::{
+    chi_square, g_test, jensen_shannon, kolmogorov_smirnov, kullback_leibler, TestResult,
+};
+
+define_histogram!(hist3, 3);
+define_histogram!(hist5, 5);
+
+fn hist3_from(counts: &[u64; 3]) -> hist3::Histogram {
+    let mut h = hist3::Histogram::with_const_width(0., 3.);
+    for (i, &n) in counts.iter().enumerate() {
+        for _ in 0..n {
+            h.add(i as f64 + 0.5).unwrap();
+        }
+    }
+    h
+}
+
+#[test]
+fn identical() {
+    let a = hist3_from(&[10, 20, 30]);
+    let identical = TestResult { statistic: 0., p_value: 1. };
+    assert_eq!(chi_square(&a, &a).unwrap(), identical);
+    assert_eq!(g_test(&a, &a).unwrap(), identical);
+    assert_eq!(kolmogorov_smirnov(&a, &a).unwrap(), identical);
+    assert_eq!(kullback_leibler(&a, &a).unwrap(), identical);
+    assert_eq!(jensen_shannon(&a, &a).unwrap(), identical);
+}
+
+#[test]
+fn empty() {
+    let a = hist3_from(&[10, 20, 30]);
+    let b = hist3_from(&[0, 0, 0]);
+    let identical = TestResult { statistic: 0., p_value: 1. };
+    assert_eq!(chi_square(&a, &b).unwrap(), identical);
+    assert_eq!(kolmogorov_smirnov(&b, &a).unwrap(), identical);
+}
+
+#[test]
+fn statistics() {
+    // The chi-square distribution with two degrees of freedom has the
+    // survival function `exp(-x / 2)`.
+    let a = hist3_from(&[10, 20, 30]);
+    let b = hist3_from(&[30, 20, 10]);
+
+    let r = chi_square(&a, &b).unwrap();
+    assert_almost_eq!(r.statistic, 240. / 7., 1e-13);
+    assert_almost_eq!(r.p_value, (-120f64 / 7.).exp(), 1e-20);
+
+    let r = g_test(&a, &b).unwrap();
+    let g = 40. * 0.5f64.ln() + 120. * 1.5f64.ln();
+    assert_almost_eq!(r.statistic, g, 1e-13);
+    assert_almost_eq!(r.p_value, (-g / 2.).exp(), 1e-15);
+
+    let r = kullback_leibler(&a, &b).unwrap();
+    assert_almost_eq!(r.statistic, 3f64.ln() / 3., 1e-15);
+    assert_almost_eq!(r.p_value, 3f64.powi(-20), 1e-20);
+
+    let r = jensen_shannon(&a, &b).unwrap();
+    assert_almost_eq!(r.statistic, 0.5f64.ln() / 6. + 1.5f64.ln() / 2., 1e-15);
+    assert_eq!(r.p_value, g_test(&a, &b).unwrap().p_value);
+
+    let r = kolmogorov_smirnov(&a, &b).unwrap();
+    assert_almost_eq!(r.statistic, 1. / 3., 1e-15);
+    assert_almost_eq!(r.p_value, 0.0018019465088078784, 1e-15);
+}
+
+#[test]
+fn p_values() {
+    // With four degrees of freedom, the survival function is
+    // `exp(-x / 2) (1 + x / 2)`.
+    let mut a = hist5::Histogram::with_const_width(0., 5.);
+    let mut b = a.clone();
+    for (i, &(n_a, n_b)) in [(8, 12), (15, 9), (11, 10), (7, 14), (9, 5)].iter().enumerate() {
+        for _ in 0..n_a {
+            a.add(i as f64).unwrap();
+        }
+        for _ in 0..n_b {
+            b.add(i as f64).unwrap();
+        }
+    }
+    let r = g_test(&a, &b).unwrap();
+    let x = r.statistic / 2.;
+    assert_almost_eq!(r.p_value, (-x).exp() * (1. + x), 1e-14);
+}
+
+#[test]
+fn kullback_leibler_infinite() {
+    let a = hist3_from(&[1, 2, 3]);
+    let b = hist3_from(&[3, 3, 0]);
+    let r = kullback_leibler(&a, &b).unwrap();
+    assert_eq!(r, TestResult { statistic: std::f64::INFINITY, p_value: 0. });
+    let r = kullback_leibler(&b, &a).unwrap();
+    assert!(r.statistic.is_finite());
+    let r = jensen_shannon(&a, &b).unwrap();
+    assert!(r.statistic < 2f64.ln());
+}
+
+#[test]
+fn outliers_are_ignored() {
+    let mut a = hist3_from(&[20, 40, 60]).count_outliers();
+    for &x in &[-1., 5., 10.] {
+        a.add(x).unwrap();
+    }
+    let mut b = hist3_from(&[30, 20, 10]).count_outliers();
+    b.add(7.).unwrap();
+    // The frequencies and their errors are relative to the counts in the
+    // bins.
+    let r = chi_square(&a, &b).unwrap();
+    assert_almost_eq!(r.statistic, 480. / 23. + 480. / 19., 1e-13);
+}
+
+#[test]
+fn incompatible_ranges() {
+    let a = hist3_from(&[1, 2, 3]);
+    let b = hist3::Histogram::with_const_width(0., 6.);
+    let c = hist5::Histogram::with_const_width(0., 3.);
+    assert_eq!(chi_square(&a, &b), Err(IncompatibleRangesError));
+    assert_eq!(g_test(&a, &c), Err(IncompatibleRangesError));
+    assert_eq!(kolmogorov_smirnov(&c, &a), Err(IncompatibleRangesError));
+    assert_eq!(kullback_leibler(&b, &a), Err(IncompatibleRangesError));
+    assert_eq!(jensen_shannon(&a, &b), Err(IncompatibleRangesError));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn different_types() {
+    use average::Histogram;
+
+    let a = hist3_from(&[10, 20, 30]);
+    let mut b = average::DynHistogram::with_const_width(0., 3., 3);
+    for &x in &[0.5, 1.5, 2.5] {
+        b.add(x).unwrap();
+    }
+    assert_eq!(b.total(), 3);
+    assert_eq!(chi_square(&a, &b).unwrap(), chi_square(&b, &a).unwrap());
+}
@@ -0,0 +1,166 @@
+use average::{HdrHistogram, Histogram, Merge, SampleOutOfRangeError};
+
+#[test]
+fn bins() {
+    let h = HdrHistogram::new(1., 10_000., 1);
+    // 20 values can be recorded with single unit resolution, which requires 32
+    // bins below 32. Every following bucket has 16 bins.
+    assert_eq!(h.lowest(), 1.);
+    assert_eq!(h.highest(), 10_000.);
+    assert_eq!(h.significant_digits(), 1);
+    assert_eq!(h.range_min(), 0.);
+    assert_eq!(h.range_max(), 16_384.);
+    assert_eq!(h.bins().len(), 32 + 9 * 16);
+    let ranges: Vec<(f64, f64)> = h.iter().map(|(r, _)| r).collect();
+    assert_eq!(ranges[0], (0., 1.));
+    assert_eq!(ranges[31], (31., 32.));
+    assert_eq!(ranges[32], (32., 34.));
+    assert_eq!(ranges[47], (62., 64.));
+    assert_eq!(ranges[48], (64., 68.));
+    assert_eq!(ranges[ranges.len() - 1], (15_872., 16_384.));
+    for w in ranges.windows(2) {
+        assert_eq!(w[0].1, w[1].0);
+        // The relative width of the bins is bounded above 10.
+        if w[1].0 >= 10. {
+            assert!(w[1].1 - w[1].0 <= 0.1 * w[1].0);
+        }
+    }
+}
+
+#[test]
+fn find() {
+    let h = HdrHistogram::new(0.5, 100., 2);
+    for (i, ((lower, upper), _)) in h.iter().enumerate() {
+        assert_eq!(h.find(lower), Ok(i));
+        assert_eq!(h.find(0.5 * (lower + upper)), Ok(i));
+    }
+    assert_eq!(h.find(-1.), Err(SampleOutOfRangeError));
+    assert_eq!(h.find(h.range_max()), Err(SampleOutOfRangeError));
+    assert_eq!(h.find(f64::NAN), Err(SampleOutOfRangeError));
+}
+
+#[test]
+fn value_at_quantile() {
+    let mut h = HdrHistogram::new(1e-6, 3600., 3);
+    assert!(h.is_empty());
+    assert_eq!(h.value_at_quantile(0.5), 0.);
+    // Latencies spanning six orders of magnitude.
+    let mut samples = Vec::new();
+    for e in -5..1 {
+        for i in 1..1000 {
+            samples.push(f64::from(i) * 10f64.powi(e));
+        }
+    }
+    samples.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    for &x in &samples {
+        h.add(x).unwrap();
+    }
+    assert_eq!(h.len(), samples.len() as u64);
+    for i in 0..=100 {
+        let p = f64::from(i) / 100.;
+        let index = ((p * samples.len() as f64).ceil() as usize).max(1) - 1;
+        let exact = samples[index];
+        let tolerance = (0.5e-6f64).max(0.5e-3 * exact) * (1. + 1e-9);
+        assert!((h.value_at_quantile(p) - exact).abs() <= tolerance,
+            "p = {}: {} differs from {}", p, h.value_at_quantile(p), exact);
+    }
+}
+
+#[test]
+fn count_between() {
+    let mut h = HdrHistogram::new(1., 1000., 2);
+    for i in 0..1000 {
+        h.add(f64::from(i)).unwrap();
+    }
+    assert_eq!(h.count_between(0., 99.), 100);
+    assert_eq!(h.count_between(10., 10.), 1);
+    assert_eq!(h.count_between(-10., 1e9), 1000);
+    assert_eq!(h.count_between(50., 10.), 0);
+    // Bins above 256 are 2 units wide.
+    assert_eq!(h.count_between(300., 301.), 2);
+    assert_eq!(h.count_between(300., 302.), 4);
+
+    h.reset();
+    assert!(h.is_empty());
+    assert_eq!(h.count_between(0., 1000.), 0);
+}
+
+#[test]
+fn merge() {
+    let mut h = HdrHistogram::new(1., 1000., 2);
+    let mut g = h.clone();
+    h.add(1.).unwrap();
+    g.add(1.).unwrap();
+    g.add(500.).unwrap();
+    h.merge(&g);
+    assert_eq!(h.len(), 3);
+    assert_eq!(h.count_between(0., 1.), 2);
+    assert_eq!(h.count_between(499., 501.), 1);
+}
+
+#[test]
+#[should_panic]
+fn merge_different() {
+    let mut h = HdrHistogram::new(1., 1000., 2);
+    h.merge(&HdrHistogram::new(1., 1000., 3));
+}
+
+#[test]
+#[should_panic]
+fn invalid_digits() {
+    HdrHistogram::new(1., 1000., 6);
+}
+
+#[test]
+fn histogram_trait() {
+    let mut h = HdrHistogram::new(1., 100., 1);
+    for i in 0..64 {
+        h.add(f64::from(i)).unwrap();
+    }
+    let normalized: Vec<f64> = h.normalized_bins().take(34).collect();
+    assert!(normalized.iter().all(|&n| n == 1.));
+    assert_eq!(h.widths().nth(40), Some(2.));
+    assert_eq!(h.centers().nth(32), Some(33.));
+}
+
+#[cfg(feature = "serde1")]
+#[test]
+fn simple_serde() {
+    let mut h = HdrHistogram::new(1., 2., 1);
+    h.add(3.).unwrap();
+    let b = serde_json::to_string(&h).unwrap();
+    assert_eq!(&b, "{\"lowest\":1.0,\"highest\":2.0,\"significant_digits\":1,\
+        \"sub_bucket_magnitude\":5,\"bin\":[0,0,0,1,0,0,0,0,0,0,0,0,0,0,0,0,0,\
+        0,0,0,0,0,0,0,0,0,0,0,0,0,0,0]}");
+    let g: HdrHistogram = serde_json::from_str(&b).unwrap();
+    assert_eq!(g.bins(), h.bins());
+}
+
+#[test]
+#[should_panic]
+fn too_large_range() {
+    HdrHistogram::new(1e-300, 1e300, 3);
+}
+
+#[cfg(feature = "serde1")]
+#[test]
+fn serde_invalid() {
+    let valid = "{\"lowest\":1.0,\"highest\":2.0,\"significant_digits\":1,\
+        \"sub_bucket_magnitude\":5,\"bin\":[0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,\
+        0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0]}";
+    assert!(serde_json::from_str::<HdrHistogram>(valid).is_ok());
+    let invalid = [
+        // The magnitude does not match the significant digits.
+        valid.replace("\"sub_bucket_magnitude\":5", "\"sub_bucket_magnitude\":0"),
+        // Too few bins.
+        valid.replace("[0,0,0,0,", "[0,0,0,"),
+        // Invalid parameters.
+        valid.replace("\"significant_digits\":1", "\"significant_digits\":0"),
+        valid.replace("\"lowest\":1.0", "\"lowest\":-1.0"),
+        valid.replace("\"highest\":2.0", "\"highest\":1.0"),
+    ];
+    for json in &invalid {
+        assert!(serde_json::from_str::<HdrHistogram>(json).is_err(), "{}", json);
+    }
+}
+
@@ -248,3 +248,223 @@ fn simple_serde() {
     let c: Histogram10 = serde_json::from_str(&b).unwrap();
     assert_eq!(c.bins(), &[1, 0, 0, 0, 0, 0, 1, 0, 0, 2]);
 }
+
+#[test]
+fn count_outliers() {
+    let mut h = Histogram10::with_const_width(0., 10.).count_outliers();
+    for &x in &[-1., 0., 5., 10., 11., std::f64::NAN, -std::f64::INFINITY] {
+        h.add(x).unwrap();
+    }
+    assert_eq!(h.bins(), &[1, 0, 0, 0, 0, 1, 0, 0, 0, 0]);
+    let outliers = h.outlier_counts().unwrap();
+    assert_eq!(outliers.underflow(), 2);
+    assert_eq!(outliers.overflow(), 2);
+    assert_eq!(outliers.nan(), 1);
+    assert_eq!(h.outliers(), 5);
+    assert_almost_eq!(h.variance(0), 1. * (1. - 1. / 7.), 1e-14);
+
+    let iterated: Vec<((f64, f64), u64)> = h.iter_with_outliers().collect();
+    assert_eq!(iterated.len(), 12);
+    assert_eq!(iterated[0], ((-std::f64::INFINITY, 0.), 2));
+    assert_eq!(iterated[11], ((10., std::f64::INFINITY), 2));
+    assert_eq!(h.iter().count(), 10);
+
+    let mut g = h.clone();
+    g.merge(&h);
+    g *= 2;
+    assert_eq!(g.outlier_counts().unwrap().total(), 20);
+    g.reset();
+    assert_eq!(g.outlier_counts().unwrap().total(), 0);
+}
+
+#[test]
+#[should_panic]
+fn merge_outliers_mismatch() {
+    let mut a = Histogram10::with_const_width(0., 10.);
+    let b = a.clone().count_outliers();
+    a.merge(&b);
+}
+
+#[cfg(feature = "serde1")]
+#[test]
+fn outliers_serde() {
+    let mut a = Histogram10::with_const_width(0., 10.).count_outliers();
+    a.add(-1.).unwrap();
+    let b = serde_json::to_string(&a).unwrap();
+    assert_eq!(&b, "{\"range\":[0.0,1.0,2.0,3.0,4.0,5.0,6.0,7.0,8.0,9.0,10.0],\
+        \"bin\":[0,0,0,0,0,0,0,0,0,0],\
+        \"outliers\":{\"underflow\":1,\"overflow\":0,\"nan\":0}}");
+    let c: Histogram10 = serde_json::from_str(&b).unwrap();
+    assert_eq!(c.outlier_counts(), a.outlier_counts());
+}
+
+#[test]
+fn analytic_find_matches_binary_search() {
+    let mut rng = rand_xoshiro::Xoshiro256StarStar::seed_from_u64(42);
+    let uniform = rand_distr::Uniform::new(-1., 12.);
+    let hists = [
+        Histogram10::with_const_width(0.1, 10.7),
+        Histogram10::with_geometric_ratio(0.1, 1.5),
+        #[cfg(any(feature = "std", feature = "libm"))]
+        Histogram10::with_log_width(0.1, 10.7),
+    ];
+    for h in &hists {
+        let reference = Histogram10::from_ranges(h.ranges().iter().cloned()).unwrap();
+        for &x in h.ranges() {
+            assert_eq!(h.find(x), reference.find(x));
+        }
+        for _ in 0..10000 {
+            let x = uniform.sample(&mut rng);
+            assert_eq!(h.find(x), reference.find(x));
+        }
+    }
+}
+
+#[test]
+fn with_geometric_ratio() {
+    let mut h = Histogram10::with_geometric_ratio(1., 2.);
+    assert_eq!(h.range_max(), 1024.);
+    for &x in &[1., 1.5, 2., 700., 1023.] {
+        h.add(x).unwrap();
+    }
+    assert_eq!(h.add(0.5), Err(SampleOutOfRangeError));
+    assert_eq!(h.add(1024.), Err(SampleOutOfRangeError));
+    assert_eq!(h.bins(), &[2, 1, 0, 0, 0, 0, 0, 0, 0, 2]);
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+#[test]
+fn with_log_width() {
+    let h = Histogram10::with_log_width(1e-3, 1e7);
+    let expected = [1e-3, 1e-2, 1e-1, 1., 1e1, 1e2, 1e3, 1e4, 1e5, 1e6, 1e7];
+    for (a, b) in h.ranges().iter().zip(expected.iter()) {
+        assert_almost_eq!(a / b, 1., 1e-14);
+    }
+    assert_eq!(h.range_max(), 1e7);
+    assert_eq!(h.find(0.5), Ok(2));
+}
+
+#[test]
+fn from_quantile_edges() {
+    let samples: Vec<f64> = (0..=100).map(f64::from).collect();
+    let h = Histogram10::from_quantile_edges(|p| samples[(p * 100.).round() as usize])
+        .unwrap();
+    let mut h = h.count_outliers();
+    for &x in &samples {
+        h.add(x).unwrap();
+    }
+    assert_eq!(h.bins(), &[10, 10, 10, 10, 10, 10, 10, 10, 10, 10]);
+    assert_eq!(h.outlier_counts().unwrap().overflow(), 1);
+    assert_eq!(
+        Histogram10::from_quantile_edges(|p| 1. - p).unwrap_err(),
+        InvalidRangeError::NotSorted
+    );
+}
+
+#[test]
+fn statistics() {
+    let mut h = Histogram10::with_const_width(0., 10.);
+    assert_eq!(h.total(), 0);
+    assert_eq!(h.mean(), 0.);
+    assert_eq!(h.quantile(0.5), 0.);
+    assert_eq!(h.cdf(5.), 0.);
+    assert_eq!(h.mode_bin(), None);
+    for &x in &[1.5, 1.2, 3.5, 7.5] {
+        h.add(x).unwrap();
+    }
+    assert_eq!(h.total(), 4);
+    assert_almost_eq!(h.mean(), 3.5, 1e-14);
+    // Centers 1.5, 1.5, 3.5, 7.5.
+    assert_almost_eq!(h.sample_variance(), (4. + 4. + 0. + 16.) / 3., 1e-14);
+    assert_eq!(h.mode_bin(), Some(1));
+    assert_eq!(h.quantile(0.), 1.);
+    assert_almost_eq!(h.quantile(0.25), 1.5, 1e-14);
+    assert_almost_eq!(h.quantile(0.5), 2., 1e-14);
+    assert_almost_eq!(h.quantile(0.625), 3.5, 1e-14);
+    assert_eq!(h.quantile(1.), 8.);
+    assert_eq!(h.cdf(0.5), 0.);
+    assert_almost_eq!(h.cdf(1.5), 0.25, 1e-14);
+    assert_almost_eq!(h.cdf(2.), 0.5, 1e-14);
+    assert_almost_eq!(h.cdf(7.5), 0.875, 1e-14);
+    assert_eq!(h.cdf(100.), 1.);
+}
+
+#[test]
+fn statistics_normal() {
+    use average::{Estimate, Variance};
+
+    let mut h = hist100::Histogram::with_const_width(-5., 5.);
+    let mut v = Variance::new();
+    let normal = rand_distr::Normal::new(1., 1.).unwrap();
+    let mut rng = rand_xoshiro::Xoshiro256StarStar::seed_from_u64(42);
+    for _ in 0..100_000 {
+        let x = normal.sample(&mut rng);
+        if h.add(x).is_ok() {
+            v.add(x);
+        }
+    }
+    assert_almost_eq!(h.mean(), v.mean(), 1e-3);
+    // Sheppard's correction for the bin width of 0.1.
+    assert_almost_eq!(h.sample_variance() - 0.01 / 12., v.sample_variance(), 1e-3);
+    assert_almost_eq!(h.quantile(0.5), 1., 2e-2);
+    assert_almost_eq!(h.cdf(1.), 0.5, 1e-2);
+    assert_eq!(h.mode_bin().map(|i| (h.centers().nth(i).unwrap() - 1.).abs() < 0.3), Some(true));
+}
+
+#[test]
+fn statistics_infinite_bins() {
+    let inf = std::f64::INFINITY;
+    let mut h = Histogram10::from_ranges(
+        [-inf, 0.1, 0.2, 0.3, 0.4, 0.5, 0.7, 0.8, 0.9, 1.0, inf].iter().cloned()).unwrap();
+    for &x in &[-1., 0.15, 0.15, 0.85] {
+        h.add(x).unwrap();
+    }
+    assert_eq!(h.cdf(0.), 0.);
+    assert_eq!(h.cdf(0.1), 0.25);
+    assert_almost_eq!(h.quantile(0.5), 0.15, 1e-14);
+    assert_eq!(h.quantile(0.1), 0.1);
+    assert_eq!(h.quantile(0.), 0.1);
+}
+
+#[test]
+fn try_merge() {
+    let mut a = Histogram10::with_const_width(0., 10.);
+    let mut b = a.clone();
+    a.add(1.).unwrap();
+    b.add(2.).unwrap();
+    a.try_merge(&b).unwrap();
+    assert_eq!(a.bins(), &[0, 1, 1, 0, 0, 0, 0, 0, 0, 0]);
+
+    let c = Histogram10::with_const_width(0., 20.);
+    assert_eq!(a.try_merge(&c), Err(average::IncompatibleRangesError));
+    assert_eq!(a.try_merge(&b.clone().count_outliers()), Err(average::IncompatibleRangesError));
+    assert_eq!(a.bins(), &[0, 1, 1, 0, 0, 0, 0, 0, 0, 0]);
+
+    // With outliers, the range limits must match, even if the bins are
+    // compatible.
+    let ranges = [0., 1., 2., 3., 4., 5., 6., 7., 8., 9., 20.];
+    let mut d = Histogram10::from_ranges(ranges.iter().cloned()).unwrap().count_outliers();
+    let e = Histogram10::with_const_width(0., 10.).count_outliers();
+    assert_eq!(d.try_merge(&e), Err(average::IncompatibleRangesError));
+    d.try_merge(&d.clone()).unwrap();
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn rebin_and_slice() {
+    let mut h = Histogram10::with_const_width(0., 10.).count_outliers();
+    for i in 0..11 {
+        h.add(f64::from(i)).unwrap();
+    }
+    let r = h.rebin(2);
+    assert_eq!(r.ranges(), &[0., 5., 10.]);
+    assert_eq!(r.bins(), &[5, 5]);
+    assert_eq!(r.outlier_counts(), h.outlier_counts());
+
+    let s = h.slice(2.5, 4.).unwrap();
+    assert_eq!(s.ranges(), &[2., 3., 4., 5.]);
+    assert_eq!(s.bins(), &[1, 1, 1]);
+    assert_eq!(s.outlier_counts().unwrap().underflow(), 2);
+    assert_eq!(s.outlier_counts().unwrap().overflow(), 6);
+    assert_eq!(h.slice(4., 2.).unwrap_err(), InvalidRangeError::NotSorted);
+}
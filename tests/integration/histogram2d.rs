@@ -0,0 +1,126 @@
+use rand::SeedableRng;
+use rand_distr::Distribution;
+
+use average::{Histogram, Merge, define_histogram2d, assert_almost_eq};
+use average::{InvalidRangeError, SampleOutOfRangeError};
+
+define_histogram2d!(hist3x2, 3, 2);
+define_histogram2d!(hist20x20, 20, 20);
+
+use hist3x2::Histogram2d as Histogram3x2;
+
+#[test]
+fn with_const_width() {
+    let mut h = Histogram3x2::with_const_width(0., 3., -1., 1.);
+    for &(x, y) in &[(0.5, -0.5), (1.5, 0.5), (1.2, 0.9), (2.9, -1.)] {
+        h.add(x, y).unwrap();
+    }
+    assert_eq!(h.bins(), &[1, 0, 0, 2, 1, 0]);
+    assert_eq!(h.count(1, 1), 2);
+    assert_eq!(h.find(2.5, 0.), Ok((2, 1)));
+    assert_eq!(h.add(3., 0.), Err(SampleOutOfRangeError));
+    assert_eq!(h.add(0., 1.), Err(SampleOutOfRangeError));
+    assert_eq!(h.add(std::f64::NAN, 0.), Err(SampleOutOfRangeError));
+    h.reset();
+    assert_eq!(h.bins(), &[0; 6]);
+}
+
+#[test]
+fn from_ranges() {
+    let h = Histogram3x2::from_ranges(
+        [0., 1., 10., 100.].iter().cloned(), [0., 0.5, 1.].iter().cloned()).unwrap();
+    assert_eq!(h.x_ranges(), &[0., 1., 10., 100.]);
+    assert_eq!(h.y_ranges(), &[0., 0.5, 1.]);
+    assert_eq!(h.find(50., 0.7), Ok((2, 1)));
+    assert_eq!(
+        Histogram3x2::from_ranges([0., 1., 2.].iter().cloned(), [0., 1., 2.].iter().cloned())
+            .unwrap_err(),
+        InvalidRangeError::NotEnoughRanges
+    );
+    assert_eq!(
+        Histogram3x2::from_ranges([0., 1., 2., 3.].iter().cloned(), [0., 2., 1.].iter().cloned())
+            .unwrap_err(),
+        InvalidRangeError::NotSorted
+    );
+}
+
+#[test]
+fn iter() {
+    let mut h = Histogram3x2::with_const_width(0., 3., 0., 2.);
+    h.add(2.5, 0.5).unwrap();
+    let iterated: Vec<_> = h.iter().collect();
+    assert_eq!(&iterated, &[
+        ((0., 1.), (0., 1.), 0), ((0., 1.), (1., 2.), 0),
+        ((1., 2.), (0., 1.), 0), ((1., 2.), (1., 2.), 0),
+        ((2., 3.), (0., 1.), 1), ((2., 3.), (1., 2.), 0),
+    ]);
+}
+
+#[test]
+fn marginals() {
+    let mut h = hist20x20::Histogram2d::with_const_width(-3., 3., -3., 3.);
+    let mut hx = hist20x20::x::Histogram::with_const_width(-3., 3.);
+    let mut hy = hist20x20::y::Histogram::with_const_width(-3., 3.);
+    let normal = rand_distr::Normal::new(0., 1.).unwrap();
+    let mut rng = rand_xoshiro::Xoshiro256StarStar::seed_from_u64(42);
+    for _ in 0..10000 {
+        let x: f64 = normal.sample(&mut rng);
+        let y = 0.5 * x + normal.sample(&mut rng);
+        if h.add(x, y).is_ok() {
+            hx.add(x).unwrap();
+            hy.add(y).unwrap();
+        }
+    }
+    assert_eq!(h.marginal_x().bins(), hx.bins());
+    assert_eq!(h.marginal_y().bins(), hy.bins());
+    assert_eq!(h.marginal_x().ranges(), hx.ranges());
+    assert_eq!(h.marginal_y().find(0.1), hy.find(0.1));
+}
+
+#[test]
+fn variance() {
+    let mut h = Histogram3x2::with_const_width(0., 3., 0., 2.);
+    for &(x, y) in &[(0.5, 0.5), (0.5, 0.5), (1.5, 1.5), (2.5, 0.5)] {
+        h.add(x, y).unwrap();
+    }
+    assert_almost_eq!(h.variance(0, 0), 2. * (1. - 2. / 4.), 1e-14);
+    assert_almost_eq!(h.variance(1, 1), 1. * (1. - 1. / 4.), 1e-14);
+    assert_eq!(h.variance(2, 1), 0.);
+}
+
+#[test]
+fn merge() {
+    let mut a = Histogram3x2::with_const_width(0., 3., 0., 2.);
+    let mut b = a.clone();
+    a.add(0.5, 0.5).unwrap();
+    b.add(0.5, 0.5).unwrap();
+    b.add(2.5, 1.5).unwrap();
+    let mut c = a.clone();
+    a.merge(&b);
+    c += &b;
+    assert_eq!(a.bins(), &[2, 0, 0, 0, 0, 1]);
+    assert_eq!(c.bins(), a.bins());
+    a *= 3;
+    assert_eq!(a.bins(), &[6, 0, 0, 0, 0, 3]);
+}
+
+#[test]
+#[should_panic]
+fn merge_different_ranges() {
+    let mut a = Histogram3x2::with_const_width(0., 3., 0., 2.);
+    let b = Histogram3x2::with_const_width(0., 3., 0., 4.);
+    a.merge(&b);
+}
+
+#[cfg(feature = "serde1")]
+#[test]
+fn simple_serde() {
+    let mut a = Histogram3x2::with_const_width(0., 3., 0., 2.);
+    a.add(2.5, 1.5).unwrap();
+    let b = serde_json::to_string(&a).unwrap();
+    assert_eq!(&b, "{\"x_range\":[0.0,1.0,2.0,3.0],\"y_range\":[0.0,1.0,2.0],\
+        \"bin\":[0,0,0,0,0,1]}");
+    let c: Histogram3x2 = serde_json::from_str(&b).unwrap();
+    assert_eq!(c.bins(), a.bins());
+    assert_eq!(c.find(0.5, 0.5), Ok((0, 0)));
+}
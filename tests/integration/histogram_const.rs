@@ -232,3 +232,136 @@ fn merge() {
     println!("{:?}", h1.bins());
     assert_eq!(h.bins(), h1.bins());
 }
+
+#[test]
+fn spaced_constructors() {
+    let mut h = Histogram10::with_geometric_ratio(1., 2.);
+    for &x in &[1., 1.5, 2., 700., 1023.] {
+        h.add(x).unwrap();
+    }
+    assert_eq!(h.add(1024.), Err(SampleOutOfRangeError));
+    assert_eq!(h.bins(), &[2, 1, 0, 0, 0, 0, 0, 0, 0, 2]);
+
+    let h = Histogram10::with_log_width(1., 1e10);
+    assert_eq!(h.find(5.), Ok(0));
+    assert_eq!(h.find(5e9), Ok(9));
+    assert_eq!(h.range_max(), 1e10);
+
+    let h = Histogram10::from_quantile_edges(|p| 10. * p).unwrap();
+    assert_eq!(h.find(3.5), Ok(3));
+}
+
+#[test]
+fn histogram2d() {
+    use average::histogram_const::Histogram2d;
+
+    let mut h = Histogram2d::<3, 2>::with_const_width(0., 3., 0., 2.);
+    for &(x, y) in &[(0.5, 0.5), (0.5, 0.5), (1.5, 1.5), (2.5, 0.5)] {
+        h.add(x, y).unwrap();
+    }
+    assert_eq!(h.add(3., 0.), Err(SampleOutOfRangeError));
+    assert_eq!(h.bins(), &[2, 0, 0, 1, 1, 0]);
+    assert_eq!(h.marginal_x().bins(), &[2, 1, 1]);
+    assert_eq!(h.marginal_y().bins(), &[3, 1]);
+    assert_almost_eq!(h.variance(0, 0), 1., 1e-14);
+    assert_eq!(h.iter().nth(3), Some(((1., 2.), (1., 2.), 1)));
+
+    let mut g = Histogram2d::<3, 2>::from_ranges(
+        [0., 1., 2., 3.].iter().cloned(), [0., 1., 2.].iter().cloned()).unwrap();
+    g.add(0.5, 1.5).unwrap();
+    g += &h;
+    assert_eq!(g.bins(), &[2, 1, 0, 1, 1, 0]);
+    assert_eq!(
+        Histogram2d::<3, 2>::from_ranges([0.].iter().cloned(), [0.].iter().cloned())
+            .unwrap_err(),
+        InvalidRangeError::NotEnoughRanges
+    );
+}
+
+#[test]
+fn statistics() {
+    use average::Histogram as _;
+
+    let mut h = Histogram10::with_const_width(0., 10.);
+    for &x in &[1.5, 1.2, 3.5, 7.5] {
+        h.add(x).unwrap();
+    }
+    assert_eq!(h.total(), 4);
+    assert_almost_eq!(h.mean(), 3.5, 1e-14);
+    assert_almost_eq!(h.sample_variance(), 8., 1e-14);
+    assert_almost_eq!(h.quantile(0.5), 2., 1e-14);
+    assert_almost_eq!(h.cdf(2.), 0.5, 1e-14);
+    assert_eq!(h.mode_bin(), Some(1));
+}
+
+#[test]
+fn rebin_and_try_merge() {
+    let mut fine = Histogram10::with_const_width(0., 10.);
+    for i in 0..10 {
+        fine.add(f64::from(i)).unwrap();
+    }
+    let mut coarse: Histogram<2> = fine.rebin::<2>();
+    assert_eq!(coarse.ranges(), &[0., 5., 10.]);
+    assert_eq!(coarse.bins(), &[5, 5]);
+    coarse.try_merge(&fine).unwrap();
+    assert_eq!(coarse.bins(), &[10, 10]);
+    assert_eq!(fine.try_merge(&coarse), Err(average::IncompatibleRangesError));
+
+    // The outliers of the finer histogram would fall into the coarser bins.
+    let mut coarse = Histogram::<5>::with_const_width(0., 10.).count_outliers();
+    let mut fine = Histogram::<2>::with_const_width(2., 4.).count_outliers();
+    fine.add(1.).unwrap();
+    assert_eq!(coarse.try_merge(&fine), Err(average::IncompatibleRangesError));
+    assert_eq!(coarse.outlier_counts().unwrap().total(), 0);
+    let fine = Histogram10::with_const_width(0., 10.).count_outliers();
+    coarse.try_merge(&fine).unwrap();
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn slice() {
+    use average::Histogram as _;
+
+    let mut h = Histogram10::with_const_width(0., 10.);
+    for i in 0..10 {
+        h.add(f64::from(i)).unwrap();
+    }
+    let s = h.slice(2.5, 4.).unwrap();
+    assert_eq!(s.ranges(), &[2., 3., 4., 5.]);
+    assert_eq!(s.bins(), &[1, 1, 1]);
+    assert_eq!(s.outlier_counts(), None);
+    assert_eq!(h.slice(4., 2.).unwrap_err(), InvalidRangeError::NotSorted);
+    assert_eq!(h.slice(10., 11.).unwrap_err(), InvalidRangeError::NotEnoughRanges);
+}
+
+#[test]
+fn count_outliers() {
+    use average::Histogram as _;
+
+    let mut h = Histogram10::with_const_width(0., 10.).count_outliers();
+    for &x in &[-1., 0., 5., 10., 11., std::f64::NAN, -std::f64::INFINITY] {
+        h.add(x).unwrap();
+    }
+    assert_eq!(h.bins(), &[1, 0, 0, 0, 0, 1, 0, 0, 0, 0]);
+    let outliers = h.outlier_counts().unwrap();
+    assert_eq!(outliers.underflow(), 2);
+    assert_eq!(outliers.overflow(), 2);
+    assert_eq!(outliers.nan(), 1);
+    assert_eq!(h.outliers(), 5);
+    assert_almost_eq!(h.variance(0), 1. * (1. - 1. / 7.), 1e-14);
+
+    let iterated: Vec<((f64, f64), u64)> = h.iter_with_outliers().collect();
+    assert_eq!(iterated.len(), 12);
+    assert_eq!(iterated[0], ((-std::f64::INFINITY, 0.), 2));
+    assert_eq!(iterated[11], ((10., std::f64::INFINITY), 2));
+    assert_eq!(h.iter().count(), 10);
+
+    let mut g = h.clone();
+    g.merge(&h);
+    g *= 2;
+    assert_eq!(g.outlier_counts().unwrap().total(), 20);
+    assert_eq!(g.try_merge(&Histogram10::with_const_width(0., 10.)),
+        Err(average::IncompatibleRangesError));
+    g.reset();
+    assert_eq!(g.outlier_counts().unwrap().total(), 0);
+}
@@ -0,0 +1,59 @@
+use average::{DynHistogram, Histogram, define_histogram};
+
+define_histogram!(hist3, 3);
+
+fn example() -> hist3::Histogram {
+    let mut h = hist3::Histogram::with_const_width(0., 3.);
+    for &x in &[0.5, 1.5, 1.5, 2.5, 2.5, 2.5, 2.5] {
+        h.add(x).unwrap();
+    }
+    h
+}
+
+#[test]
+fn linear() {
+    let h = example();
+    assert_eq!(h.display().width(4).to_string(), "\
+[0, 1) 1 |#
+[1, 2) 2 |##
+[2, 3) 4 |####
+");
+    assert_eq!(h.display().to_string().lines().last().unwrap().matches('#').count(), 40);
+}
+
+#[test]
+fn error_bars() {
+    let h = example();
+    assert_eq!(h.display().width(10).error_bars(true).to_string(), "\
+[0, 1) 1 +/- 0.9 |==--
+[1, 2) 2 +/- 1.2 |##==--
+[2, 3) 4 +/- 1.3 |#####===--
+");
+}
+
+#[test]
+fn log_scale_and_precision() {
+    let mut h = DynHistogram::with_const_width(0., 1., 2);
+    for _ in 0..999 {
+        h.add(0.75).unwrap();
+    }
+    h.add(0.25).unwrap();
+    assert_eq!(h.display().width(10).to_string(), "\
+[0, 0.5)   1 |
+[0.5, 1) 999 |##########
+");
+    assert_eq!(h.display().width(10).log_scale(true).precision(2).to_string(), "\
+[0.00, 0.50)   1 |#
+[0.50, 1.00) 999 |##########
+");
+}
+
+#[test]
+fn empty() {
+    let h = hist3::Histogram::with_const_width(0., 3.);
+    assert_eq!(h.display().error_bars(true).to_string(), "\
+[0, 1) 0 +/- 0.0 |
+[1, 2) 0 +/- 0.0 |
+[2, 3) 0 +/- 0.0 |
+");
+}
@@ -0,0 +1,198 @@
+use rand::SeedableRng;
+use rand::seq::SliceRandom;
+use rand_distr::Distribution;
+use rand_xoshiro::Xoshiro256StarStar;
+
+use average::{Estimate, KllSketch, Merge, assert_almost_eq};
+
+/// Calculate the largest error of the normalized ranks of the sketch, given
+/// the sorted sample.
+fn max_rank_error(s: &KllSketch, sorted: &[f64]) -> f64 {
+    let n = sorted.len() as f64;
+    let mut max_error: f64 = 0.;
+    for i in (0..sorted.len()).step_by(sorted.len() / 1000) {
+        let exact = (i + 1) as f64 / n;
+        max_error = max_error.max((s.rank(sorted[i]) - exact).abs());
+    }
+    max_error
+}
+
+#[test]
+fn few_observations() {
+    let mut s = KllSketch::new();
+    assert!(s.is_empty());
+    assert_eq!(s.quantile(0.5), 0.);
+    assert_eq!(s.rank(1.), 0.);
+    assert_eq!(s.cdf(&[1., 2.]), vec![0., 0., 0.]);
+    for i in 1..5 {
+        s.add(f64::from(i));
+    }
+    assert_eq!(s.len(), 4);
+    assert_eq!(s.retained(), 4);
+    assert_eq!(s.quantile(0.), 1.);
+    assert_eq!(s.quantile(0.5), 2.);
+    assert_eq!(s.quantile(0.51), 3.);
+    assert_eq!(s.quantile(1.), 4.);
+    assert_eq!(s.estimate(), 2.);
+    assert_eq!(s.rank(0.), 0.);
+    assert_eq!(s.rank(2.), 0.5);
+    assert_eq!(s.rank(2.5), 0.5);
+    assert_eq!(s.rank(4.), 1.);
+    assert_eq!(s.cdf(&[0., 1.5, 3.]), vec![0., 0.25, 0.75, 1.]);
+}
+
+#[test]
+fn few_observations_f32() {
+    let s: KllSketch<f32> = (1..6).map(|i| i as f32).collect();
+    assert_eq!(s.quantile(0.5), 3f32);
+    assert_eq!(s.rank(3.), 0.6f32);
+}
+
+#[test]
+fn nan_is_ignored() {
+    let mut s = KllSketch::new();
+    s.add(1.);
+    s.add(f64::NAN);
+    assert_eq!(s.len(), 1);
+    assert!(s.rank(f64::NAN).is_nan());
+}
+
+#[test]
+#[should_panic]
+fn cdf_not_increasing() {
+    let s: KllSketch = (1..6).map(f64::from).collect();
+    s.cdf(&[2., 1.]);
+}
+
+#[test]
+fn rank_error() {
+    let mut rng = Xoshiro256StarStar::seed_from_u64(42);
+    let normal = rand_distr::Normal::<f64>::new(0., 1.).unwrap();
+    let mut samples: Vec<f64> = (0..100_000).map(|_| normal.sample(&mut rng)).collect();
+    let s: KllSketch = samples.iter().collect();
+    assert_eq!(s.len(), 100_000);
+    assert!(s.retained() < 3 * 200 + 100);
+    samples.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    assert!(max_rank_error(&s, &samples) < 0.02);
+    assert_eq!(s.quantile(0.), samples[0]);
+    assert_eq!(s.quantile(1.), samples[99_999]);
+    assert_almost_eq!(s.quantile(0.5), 0., 0.05);
+    let cdf = s.cdf(&[-1.2816, 0., 1.2816]);
+    assert_almost_eq!(cdf[0], 0.1, 0.02);
+    assert_almost_eq!(cdf[1], 0.5, 0.02);
+    assert_almost_eq!(cdf[2], 0.9, 0.02);
+    assert_eq!(cdf[3], 1.);
+
+    let small = {
+        let mut s = KllSketch::with_k(50);
+        for &x in &samples {
+            s.add(x);
+        }
+        s
+    };
+    assert_eq!(small.k(), 50);
+    assert!(small.retained() < s.retained());
+    assert!(max_rank_error(&small, &samples) < 0.08);
+}
+
+#[test]
+fn order_independence() {
+    // Sorted input is the worst case for many quantile estimators.
+    let samples: Vec<f64> = (0..100_000).map(f64::from).collect();
+    let ascending: KllSketch = samples.iter().collect();
+    let descending: KllSketch = samples.iter().rev().collect();
+    let mut shuffled = samples.clone();
+    shuffled.shuffle(&mut Xoshiro256StarStar::seed_from_u64(42));
+    let shuffled: KllSketch = shuffled.iter().collect();
+    for s in &[ascending, descending, shuffled] {
+        assert!(max_rank_error(s, &samples) < 0.02);
+    }
+}
+
+#[test]
+#[should_panic]
+fn small_k() {
+    KllSketch::<f64>::with_k(4);
+}
+
+#[test]
+fn merge() {
+    let sequence: &[f64] = &[1., 2., 3., 4., 5., 6., 7., 8., 9.];
+    for mid in 0..sequence.len() {
+        let (left, right) = sequence.split_at(mid);
+        let mut s: KllSketch = left.iter().collect();
+        let t: KllSketch = right.iter().collect();
+        s.merge(&t);
+        assert_eq!(s.len(), 9);
+        assert_eq!(s.quantile(0.5), 5.);
+        assert_eq!(s.rank(3.), 3. / 9.);
+    }
+}
+
+#[test]
+fn merge_tree() {
+    let samples: Vec<f64> = (0..100_000).map(f64::from).collect();
+    let mut parts: Vec<KllSketch> = samples.chunks(1000).map(|c| c.iter().collect()).collect();
+
+    // Merge sequentially.
+    let mut sequential = KllSketch::new();
+    for p in &parts {
+        sequential.merge(p);
+    }
+    assert_eq!(sequential.len(), 100_000);
+    assert!(max_rank_error(&sequential, &samples) < 0.02);
+
+    // Merge pairwise.
+    while parts.len() > 1 {
+        parts = parts.chunks(2).map(|pair| {
+            let mut s = pair[0].clone();
+            if let Some(t) = pair.get(1) {
+                s.merge(t);
+            }
+            s
+        }).collect();
+    }
+    assert_eq!(parts[0].len(), 100_000);
+    assert!(max_rank_error(&parts[0], &samples) < 0.02);
+}
+
+#[test]
+fn seed() {
+    let samples: Vec<f64> = (0..10_000).map(f64::from).collect();
+    let sketch = |seed| {
+        let mut s = KllSketch::with_k_and_seed(8, seed);
+        for &x in &samples {
+            s.add(x);
+        }
+        s
+    };
+    let (s, t) = (sketch(1), sketch(1));
+    assert_eq!(s.cdf(&samples), t.cdf(&samples));
+    let u = sketch(2);
+    assert_ne!(s.cdf(&samples), u.cdf(&samples));
+}
+
+#[test]
+fn merge_different_k() {
+    let mut s: KllSketch = (0..1000).map(f64::from).collect();
+    let mut t = KllSketch::with_k(100);
+    t.add(1000.);
+    s.merge(&t);
+    assert_eq!(s.k(), 100);
+    assert_eq!(s.len(), 1001);
+    assert_eq!(s.quantile(1.), 1000.);
+}
+
+#[cfg(feature = "serde1")]
+#[test]
+fn simple_serde() {
+    let mut s: KllSketch = (1..4).map(f64::from).collect();
+    let b = serde_json::to_string(&s).unwrap();
+    assert_eq!(&b, "{\"k\":200,\"compactors\":[[1.0,2.0,3.0]],\"min\":1.0,\
+        \"max\":3.0,\"len\":3,\"rng\":9600629759793949339}");
+    let mut t: KllSketch = serde_json::from_str(&b).unwrap();
+    s.add(4.);
+    t.add(4.);
+    assert_eq!(t.quantile(0.5), s.quantile(0.5));
+    assert_eq!(t.rank(2.), s.rank(2.));
+}
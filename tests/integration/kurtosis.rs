@@ -1,5 +1,9 @@
 use core::iter::Iterator;
 
+use rand::SeedableRng;
+use rand_distr::Distribution;
+use rand_xoshiro::Xoshiro256StarStar;
+
 use average::{Kurtosis, Estimate, Merge, assert_almost_eq};
 
 #[test]
@@ -35,6 +39,19 @@ fn simple() {
     assert_almost_eq!(a.kurtosis(), -1.365, 1e-15);
 }
 
+#[test]
+fn simple_f32() {
+    let mut a: Kurtosis<f32> = (1..6).map(|x| x as f32).collect();
+    assert_eq!(a.mean(), 3.0f32);
+    assert_eq!(a.len(), 5);
+    assert_eq!(a.sample_variance(), 2.5f32);
+    assert_almost_eq!(a.error_mean(), f32::sqrt(0.5), 1e-7);
+    assert_eq!(a.skewness(), 0.0f32);
+    a.add(1.0);
+    assert_almost_eq!(a.skewness(), 0.2795085f32, 1e-6);
+    assert_almost_eq!(a.kurtosis(), -1.365f32, 1e-6);
+}
+
 #[cfg(feature = "serde1")]
 #[test]
 fn simple_serde() {
@@ -68,3 +85,41 @@ fn merge() {
         assert_almost_eq!(avg_total.kurtosis(), avg_left.kurtosis(), 1e-14);
     }
 }
+
+#[test]
+fn unmerge() {
+    let sequence: &[f64] = &[1., 2., 3., -4., 5.1, 6.3, 7.3, -8., 9., 1.];
+    for mid in 0..=sequence.len() {
+        let (left, right) = sequence.split_at(mid);
+        let mut total: Kurtosis = sequence.iter().collect();
+        let expected: Kurtosis = left.iter().collect();
+        let other: Kurtosis = right.iter().collect();
+        total.unmerge(&other);
+        assert_eq!(total.len(), expected.len());
+        assert_almost_eq!(total.mean(), expected.mean(), 1e-14);
+        assert_almost_eq!(total.sample_variance(), expected.sample_variance(), 1e-13);
+        assert_almost_eq!(total.skewness(), expected.skewness(), 1e-12);
+        assert_almost_eq!(total.kurtosis(), expected.kurtosis(), 1e-11);
+    }
+}
+
+#[test]
+fn remove_drift() {
+    // Slide a window over many samples and compare to recalculating it.
+    let exp = rand_distr::Exp::new(0.1).unwrap();
+    let mut rng = Xoshiro256StarStar::seed_from_u64(42);
+    let samples: Vec<f64> = (0..100_000).map(|_| exp.sample(&mut rng)).collect();
+    let mut total = Kurtosis::new();
+    for (i, &x) in samples.iter().enumerate() {
+        total.add(x);
+        if i >= 50 {
+            total.remove(samples[i - 50]);
+        }
+    }
+    let expected: Kurtosis = samples[samples.len() - 50..].iter().collect();
+    assert_eq!(total.len(), 50);
+    assert_almost_eq!(total.mean(), expected.mean(), 1e-9);
+    assert_almost_eq!(total.sample_variance(), expected.sample_variance(), 1e-7);
+    assert_almost_eq!(total.skewness(), expected.skewness(), 1e-7);
+    assert_almost_eq!(total.kurtosis(), expected.kurtosis(), 1e-6);
+}
@@ -0,0 +1,98 @@
+use core::iter::Iterator;
+
+use average::{LinearRegression, Merge, assert_almost_eq};
+
+#[test]
+fn trivial() {
+    let mut a = LinearRegression::new();
+    assert_eq!(a.len(), 0);
+    assert!(a.is_empty());
+    a.add(1.0, 2.0);
+    a.add(2.0, 4.0);
+    assert_eq!(a.len(), 2);
+    assert_eq!(a.slope(), 2.0);
+    assert_eq!(a.intercept(), 0.0);
+    assert_eq!(a.residual_variance(), 0.0);
+    #[cfg(any(feature = "std", feature = "libm"))]
+    {
+        assert_eq!(a.residual_standard_error(), 0.0);
+        assert_eq!(a.error_slope(), 0.0);
+        assert_eq!(a.error_intercept(), 0.0);
+    }
+}
+
+#[test]
+fn simple() {
+    let a: LinearRegression = [(1., 2.), (2., 4.), (3., 5.), (4., 4.), (5., 5.)]
+        .iter().collect();
+    assert_eq!(a.len(), 5);
+    assert_eq!(a.mean_x(), 3.0);
+    assert_eq!(a.mean_y(), 4.0);
+    assert_almost_eq!(a.slope(), 0.6, 1e-15);
+    assert_almost_eq!(a.intercept(), 2.2, 1e-15);
+    assert_almost_eq!(a.r_squared(), 0.6, 1e-15);
+    assert_almost_eq!(a.residual_variance(), 0.8, 1e-15);
+    #[cfg(any(feature = "std", feature = "libm"))]
+    {
+        assert_almost_eq!(a.residual_standard_error(), 0.8944271909999159, 1e-15);
+        assert_almost_eq!(a.error_slope(), 0.28284271247461906, 1e-15);
+        assert_almost_eq!(a.error_intercept(), 0.938083151964686, 1e-15);
+    }
+}
+
+#[test]
+fn constant_x() {
+    let a: LinearRegression = [(1., 2.), (1., 4.), (1., 5.)].iter().collect();
+    assert!(a.slope().is_nan());
+    assert!(a.intercept().is_nan());
+}
+
+#[cfg(feature = "serde1")]
+#[test]
+fn simple_serde() {
+    let a: LinearRegression = (1..6).map(|x| (f64::from(x), f64::from(2 * x))).collect();
+    let b = serde_json::to_string(&a).unwrap();
+    let c: LinearRegression = serde_json::from_str(&b).unwrap();
+    assert_eq!(c.len(), 5);
+    assert_eq!(c.slope(), 2.0);
+    assert_eq!(c.intercept(), 0.0);
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn simple_rayon() {
+    use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+    let a: LinearRegression = (1..6).into_par_iter()
+        .map(|x| (f64::from(x), f64::from(2 * x + 1))).collect();
+    assert_eq!(a.len(), 5);
+    assert_eq!(a.slope(), 2.0);
+    assert_eq!(a.intercept(), 1.0);
+}
+
+#[test]
+fn simple_f32() {
+    let a: LinearRegression<f32> = [(1., 2.), (2., 4.), (3., 5.), (4., 4.), (5., 5.)]
+        .iter().collect();
+    assert_almost_eq!(a.slope(), 0.6f32, 1e-6);
+    assert_almost_eq!(a.intercept(), 2.2f32, 1e-6);
+}
+
+#[test]
+fn merge() {
+    let sequence: &[(f64, f64)] = &[
+        (1., 2.), (2., 3.), (3., 5.), (4., 4.), (5., 7.),
+        (6., 9.), (7., 8.), (8., 10.), (9., 12.)];
+    for mid in 0..sequence.len() {
+        let (left, right) = sequence.split_at(mid);
+        let reg_total: LinearRegression = sequence.iter().collect();
+        let mut reg_left: LinearRegression = left.iter().collect();
+        let reg_right: LinearRegression = right.iter().collect();
+        reg_left.merge(&reg_right);
+        assert_eq!(reg_total.len(), reg_left.len());
+        assert_almost_eq!(reg_total.slope(), reg_left.slope(), 1e-14);
+        assert_almost_eq!(reg_total.intercept(), reg_left.intercept(), 1e-14);
+        assert_almost_eq!(reg_total.r_squared(), reg_left.r_squared(), 1e-14);
+        assert_almost_eq!(reg_total.residual_variance(), reg_left.residual_variance(), 1e-14);
+    }
+}
@@ -1,4 +1,4 @@
-#![cfg_attr(feature = "cargo-clippy", allow(clippy::float_cmp))]
+#![allow(clippy::float_cmp)]
 
 use average::{Estimate, Min, Max, concatenate};
 
@@ -64,3 +64,95 @@ fn concatenate_moments_quantile() {
     assert_eq!(e.sample_variance(), 2.5);
     assert_eq!(e.quantile(), 3.0);
 }
+
+#[test]
+fn concatenate_merge() {
+    use average::{Merge, Variance};
+
+    concatenate!(Estimator: Merge, Clone, Debug;
+        [Variance, variance, mean, sample_variance],
+        [Max, max, max]);
+
+    let sequence: &[f64] = &[1., 2., 3., 4., 5., 6., 7., 8., 9.];
+    for mid in 0..sequence.len() {
+        let (left, right) = sequence.split_at(mid);
+        let mut e: Estimator = left.iter().collect();
+        let f: Estimator = right.iter().collect();
+        e.merge(&f);
+        assert_eq!(e.mean(), 5.0);
+        assert_eq!(e.sample_variance(), 7.5);
+        assert_eq!(e.max(), 9.0);
+    }
+
+    let e: Estimator = (1..6).map(f64::from).collect();
+    let f = e.clone();
+    assert_eq!(f.mean(), 3.0);
+    assert_eq!(f.max(), 5.0);
+    assert_eq!(format!("{:?}", f),
+        "Estimator { variance: Variance { avg: Mean { avg: 3.0, n: 5 }, sum_2: 10.0 }, \
+         max: Max { x: 5.0 } }");
+}
+
+#[test]
+fn impl_from_iterator_new() {
+    /// Sum of the samples, which has `new()` but does not implement `Default`.
+    struct Sum {
+        sum: f64,
+    }
+
+    impl Sum {
+        fn new() -> Sum {
+            Sum { sum: 0. }
+        }
+
+        fn add(&mut self, x: f64) {
+            self.sum += x;
+        }
+    }
+
+    average::impl_from_iterator!(Sum);
+
+    let s: Sum = (1..6).map(f64::from).collect();
+    assert_eq!(s.sum, 15.);
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn concatenate_rayon() {
+    use rayon::prelude::*;
+
+    concatenate!(MinMaxMerge: Merge; [Min, min], [Max, max]);
+
+    let s: MinMaxMerge = (1..1000).into_par_iter().map(f64::from).collect();
+    assert_eq!(s.min(), 1.0);
+    assert_eq!(s.max(), 999.0);
+}
+
+/// Build a crate without its own `rayon` feature or dependency, to make sure
+/// `concatenate!` does not rely on the features of the calling crate.
+#[test]
+fn concatenate_rayon_downstream() {
+    let root = std::path::Path::new(env!("CARGO_MANIFEST_DIR"));
+    let cargo = std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let status = std::process::Command::new(cargo)
+        .arg("test")
+        .arg("--manifest-path")
+        .arg(root.join("tests/downstream/Cargo.toml"))
+        .env("CARGO_TARGET_DIR", root.join("target/downstream"))
+        .status()
+        .unwrap();
+    assert!(status.success());
+}
+
+#[cfg(feature = "serde1")]
+#[test]
+fn concatenate_serde() {
+    concatenate!(MinMaxSerde: Serialize, Deserialize; [Min, min], [Max, max]);
+
+    let s: MinMaxSerde = (1..6).map(f64::from).collect();
+    let b = serde_json::to_string(&s).unwrap();
+    assert_eq!(&b, "{\"min\":{\"x\":1.0},\"max\":{\"x\":5.0}}");
+    let t: MinMaxSerde = serde_json::from_str(&b).unwrap();
+    assert_eq!(t.min(), 1.0);
+    assert_eq!(t.max(), 5.0);
+}
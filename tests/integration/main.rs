@@ -1,13 +1,38 @@
 #![cfg_attr(feature = "nightly",
    feature(generic_const_exprs))]
+#![cfg_attr(feature = "nightly",
+   allow(incomplete_features))]
 
-#![allow(clippy::float_cmp)]
+#![allow(
+    clippy::float_cmp,
+    clippy::legacy_numeric_constants,
+)]
 
+#[cfg(any(feature = "std", feature = "libm"))]
+mod bin_edges;
+mod covariance;
+#[cfg(all(feature = "alloc", any(feature = "std", feature = "libm")))]
+mod ddsketch;
+#[cfg(feature = "alloc")]
+mod dyn_histogram;
+mod exp_moments;
+#[cfg(any(feature = "std", feature = "libm"))]
+mod goodness_of_fit;
+#[cfg(feature = "alloc")]
+mod gk;
+#[cfg(feature = "alloc")]
+mod hdr_histogram;
 mod histogram;
+mod histogram2d;
+#[cfg(all(feature = "alloc", any(feature = "std", feature = "libm")))]
+mod histogram_display;
 #[cfg(feature = "nightly")]
 mod histogram_const;
+#[cfg(feature = "alloc")]
+mod kll;
 #[cfg(any(feature = "std", feature = "libm"))]
 mod kurtosis;
+mod linear_regression;
 mod macros;
 mod max;
 mod mean;
@@ -18,9 +43,17 @@ mod proptest;
 #[cfg(any(feature = "std", feature = "libm"))]
 mod quantile;
 #[cfg(any(feature = "std", feature = "libm"))]
+mod quantiles;
+#[cfg(any(feature = "std", feature = "libm"))]
 mod random;
 #[cfg(any(feature = "std", feature = "libm"))]
 mod skewness;
 #[cfg(feature = "std")]
 mod streaming_stats;
+#[cfg(all(feature = "alloc", any(feature = "std", feature = "libm")))]
+mod tdigest;
+#[cfg(feature = "alloc")]
+mod weighted_histogram;
 mod weighted_mean;
+mod weighted_moments;
+mod windowed;
@@ -13,6 +13,17 @@ fn trivial() {
     assert_eq!(m.max(), 3.)
 }
 
+#[test]
+fn trivial_f32() {
+    let mut m = Max::<f32>::new_generic();
+    m.add(2.);
+    m.add(1.);
+    assert_eq!(m.max(), 2f32);
+    m.add(3.);
+    m.add(1.);
+    assert_eq!(m.max(), 3f32)
+}
+
 #[cfg(feature = "serde1")]
 #[test]
 fn trivial_serde() {
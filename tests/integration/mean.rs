@@ -1,8 +1,10 @@
 use core::iter::Iterator;
 
-use average::{MeanWithError, Estimate, Merge};
-#[cfg(any(feature = "std", feature = "libm"))]
-use average::assert_almost_eq;
+use rand::SeedableRng;
+use rand_distr::Distribution;
+use rand_xoshiro::Xoshiro256StarStar;
+
+use average::{Mean, MeanWithError, Estimate, Merge, assert_almost_eq};
 
 #[test]
 fn trivial() {
@@ -65,6 +67,17 @@ fn simple_rayon() {
     assert_almost_eq!(a.error(), f64::sqrt(0.5), 1e-16);
 }
 
+#[test]
+fn simple_f32() {
+    let a: MeanWithError<f32> = (1..6).map(|x| x as f32).collect();
+    assert_eq!(a.mean(), 3.0f32);
+    assert_eq!(a.len(), 5);
+    assert_eq!(a.sample_variance(), 2.5f32);
+    assert_eq!(a.variance_of_mean(), 0.5f32);
+    #[cfg(any(feature = "std", feature = "libm"))]
+    assert_almost_eq!(a.error(), f32::sqrt(0.5), 1e-7);
+}
+
 #[test]
 fn numerically_unstable() {
     // The naive algorithm fails for this example due to cancelation.
@@ -88,3 +101,68 @@ fn merge() {
         assert_eq!(avg_total.sample_variance(), avg_left.sample_variance());
     }
 }
+
+#[test]
+fn remove() {
+    let mut a: MeanWithError = [1., 2., 3., 4., 10.].iter().collect();
+    a.remove(10.);
+    assert_eq!(a.len(), 4);
+    assert_eq!(a.mean(), 2.5);
+    assert_eq!(a.sample_variance(), 5. / 3.);
+    a.remove(1.);
+    a.remove(2.);
+    a.remove(3.);
+    assert_eq!(a.len(), 1);
+    assert_eq!(a.mean(), 4.);
+    assert_eq!(a.sample_variance(), 0.);
+    a.remove(4.);
+    assert!(a.is_empty());
+    assert_eq!(a.mean(), 0.);
+    assert_eq!(a.population_variance(), 0.);
+
+    let mut b: Mean = [1., 2., 6.].iter().collect();
+    b.remove(6.);
+    assert_eq!(b.len(), 2);
+    assert_eq!(b.mean(), 1.5);
+}
+
+#[test]
+#[should_panic]
+fn remove_empty() {
+    let mut a = MeanWithError::new();
+    a.remove(1.);
+}
+
+#[test]
+fn unmerge() {
+    let sequence: &[f64] = &[1., 2., 3., -4., 5.1, 6.3, 7.3, -8., 9., 1.];
+    for mid in 0..=sequence.len() {
+        let (left, right) = sequence.split_at(mid);
+        let mut avg_total: MeanWithError = sequence.iter().collect();
+        let avg_left: MeanWithError = left.iter().collect();
+        let avg_right: MeanWithError = right.iter().collect();
+        avg_total.unmerge(&avg_right);
+        assert_eq!(avg_total.len(), avg_left.len());
+        assert_almost_eq!(avg_total.mean(), avg_left.mean(), 1e-14);
+        assert_almost_eq!(avg_total.sample_variance(), avg_left.sample_variance(), 1e-13);
+    }
+}
+
+#[test]
+fn remove_drift() {
+    // Slide a window over many samples and compare to recalculating it.
+    let normal = rand_distr::Normal::new(1e3, 1.).unwrap();
+    let mut rng = Xoshiro256StarStar::seed_from_u64(42);
+    let samples: Vec<f64> = (0..100_000).map(|_| normal.sample(&mut rng)).collect();
+    let mut a = MeanWithError::new();
+    for (i, &x) in samples.iter().enumerate() {
+        a.add(x);
+        if i >= 10 {
+            a.remove(samples[i - 10]);
+        }
+    }
+    let expected: MeanWithError = samples[samples.len() - 10..].iter().collect();
+    assert_eq!(a.len(), 10);
+    assert_almost_eq!(a.mean(), expected.mean(), 1e-9);
+    assert_almost_eq!(a.sample_variance(), expected.sample_variance(), 1e-6);
+}
@@ -13,6 +13,17 @@ fn trivial() {
     assert_eq!(m.min(), -1.)
 }
 
+#[test]
+fn trivial_f32() {
+    let mut m = Min::<f32>::new_generic();
+    m.add(1.);
+    m.add(2.);
+    assert_eq!(m.min(), 1f32);
+    m.add(-1.);
+    m.add(1.);
+    assert_eq!(m.min(), -1f32)
+}
+
 #[cfg(feature = "serde1")]
 #[test]
 fn trivial_serde() {
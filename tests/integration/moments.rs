@@ -1,5 +1,9 @@
 use core::iter::Iterator;
 
+use rand::SeedableRng;
+use rand_distr::Distribution;
+use rand_xoshiro::Xoshiro256StarStar;
+
 use average::{Moments4, Merge, assert_almost_eq};
 
 #[test]
@@ -95,3 +99,42 @@ fn merge() {
         assert_almost_eq!(avg_total.central_moment(4), avg_left.central_moment(4), 1e-12);
     }
 }
+
+#[test]
+fn unmerge() {
+    let sequence: &[f64] = &[1., 2., 3., -4., 5.1, 6.3, 7.3, -8., 9., 1.];
+    for mid in 0..=sequence.len() {
+        let (left, right) = sequence.split_at(mid);
+        let mut total: Moments4 = sequence.iter().collect();
+        let expected: Moments4 = left.iter().collect();
+        let other: Moments4 = right.iter().collect();
+        total.unmerge(&other);
+        assert_eq!(total.len(), expected.len());
+        assert_almost_eq!(total.mean(), expected.mean(), 1e-14);
+        assert_almost_eq!(total.central_moment(2), expected.central_moment(2), 1e-13);
+        assert_almost_eq!(total.central_moment(3), expected.central_moment(3), 1e-12);
+        assert_almost_eq!(total.central_moment(4), expected.central_moment(4), 1e-11);
+    }
+}
+
+#[test]
+fn remove_drift() {
+    // Slide a window over many samples and compare to recalculating it.
+    let exp = rand_distr::Exp::new(0.1).unwrap();
+    let mut rng = Xoshiro256StarStar::seed_from_u64(42);
+    let samples: Vec<f64> = (0..100_000).map(|_| exp.sample(&mut rng)).collect();
+    let mut total = Moments4::new();
+    for (i, &x) in samples.iter().enumerate() {
+        total.add(x);
+        if i >= 50 {
+            total.remove(samples[i - 50]);
+        }
+    }
+    let expected: Moments4 = samples[samples.len() - 50..].iter().collect();
+    assert_eq!(total.len(), 50);
+    let relative = |a: f64, b: f64| ((a - b) / b).abs();
+    assert!(relative(total.mean(), expected.mean()) < 1e-9);
+    for p in 2..5 {
+        assert!(relative(total.central_moment(p), expected.central_moment(p)) < 1e-7);
+    }
+}
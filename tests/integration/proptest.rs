@@ -1,8 +1,8 @@
-#![cfg_attr(feature = "cargo-clippy", allow(clippy::float_cmp, map_clone))]
+#![allow(clippy::float_cmp, clippy::map_clone, clippy::zero_divided_by_zero)]
 
 use core::iter::Iterator;
 
-use average::Mean;
+use average::{Mean, assert_almost_eq};
 use proptest::prelude::*;
 use prop::num::f64;
 
@@ -21,3 +21,48 @@ proptest! {
         assert!(mean <= max);
     }
 }
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(1000))]
+    #[test]
+    fn quantile_merge_vs_single_pass(
+        seed in any::<u64>(),
+        len in 1000..5000usize,
+        split in 0. ..1.,
+        p in 0.1 ..0.9)
+    {
+        use rand::SeedableRng;
+        use rand_distr::{Distribution, Normal};
+        use average::{Estimate, Merge, Quantile};
+
+        let normal = Normal::new(0., 1.).unwrap();
+        let mut rng = rand_xoshiro::Xoshiro256StarStar::seed_from_u64(seed);
+        let s: Vec<f64> = (0..len).map(|_| normal.sample(&mut rng)).collect();
+        let mid = (split * len as f64) as usize;
+        let (left, right) = s.split_at(mid);
+
+        let mut single = Quantile::new(p);
+        for &x in &s {
+            single.add(x);
+        }
+        let mut merged = Quantile::new(p);
+        for &x in left {
+            merged.add(x);
+        }
+        let mut other = Quantile::new(p);
+        for &x in right {
+            other.add(x);
+        }
+        merged.merge(&other);
+
+        assert_eq!(merged.len(), single.len());
+        // The merged estimate should be about as good as the single pass
+        // estimate, which is typically within 0.1 standard deviations of
+        // the true quantile for these sample sizes.
+        assert_almost_eq!(merged.quantile(), single.quantile(), 0.5);
+        let min = s.iter().cloned().fold(f64::INFINITY, f64::min);
+        let max = s.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        assert!(min <= merged.quantile());
+        assert!(merged.quantile() <= max);
+    }
+}
@@ -1,4 +1,4 @@
-use average::{Estimate, Quantile};
+use average::{Estimate, Merge, Quantile, assert_almost_eq};
 
 #[test]
 fn few_observations() {
@@ -19,6 +19,21 @@ fn few_observations() {
     assert_eq!(q.quantile(), 2.5);
 }
 
+#[test]
+fn few_observations_f32() {
+    let mut q = Quantile::<f32>::new_generic(0.5);
+    q.add(1.);
+    q.add(2.);
+    q.add(3.);
+    assert_eq!(q.len(), 3);
+    assert_eq!(q.quantile(), 2f32);
+    q.add(4.);
+    q.add(5.);
+    q.add(6.);
+    assert_eq!(q.len(), 6);
+    assert_eq!(q.quantile(), 3f32);
+}
+
 #[cfg(feature = "serde1")]
 #[test]
 fn few_observations_serde() {
@@ -379,3 +394,60 @@ fn percentile_99() {
     assert!((q.quantile() - 9.).abs() < TOL);
 }
 
+
+#[test]
+fn merge_few_observations() {
+    let sequence: &[f64] = &[1., 2., 3., 4., 5., 6., 7., 8., 9.];
+    for mid in 0..sequence.len() {
+        let (left, right) = sequence.split_at(mid);
+        let mut q_total = Quantile::new(0.5);
+        let mut q_left = Quantile::new(0.5);
+        let mut q_right = Quantile::new(0.5);
+        for &x in sequence {
+            q_total.add(x);
+        }
+        for &x in left {
+            q_left.add(x);
+        }
+        for &x in right {
+            q_right.add(x);
+        }
+        q_left.merge(&q_right);
+        assert_eq!(q_total.len(), q_left.len());
+        assert_almost_eq!(q_left.quantile(), q_total.quantile(), 0.5);
+    }
+}
+
+#[test]
+fn merge() {
+    let sequence: Vec<f64> = (0..1000).map(|i| f64::from((i * 7919) % 1000)).collect();
+    for &p in &[0.1, 0.5, 0.9] {
+        for &mid in &[5, 100, 500, 900, 995] {
+            let (left, right) = sequence.split_at(mid);
+            let mut q_total = Quantile::new(p);
+            let mut q_left = Quantile::new(p);
+            let mut q_right = Quantile::new(p);
+            for &x in &sequence {
+                q_total.add(x);
+            }
+            for &x in left {
+                q_left.add(x);
+            }
+            for &x in right {
+                q_right.add(x);
+            }
+            q_left.merge(&q_right);
+            assert_eq!(q_total.len(), q_left.len());
+            assert_almost_eq!(q_left.quantile(), 1000. * p, 10.);
+            assert_almost_eq!(q_left.quantile(), q_total.quantile(), 10.);
+        }
+    }
+}
+
+#[test]
+#[should_panic]
+fn merge_different_p() {
+    let mut a = Quantile::new(0.5);
+    let b = Quantile::new(0.9);
+    a.merge(&b);
+}
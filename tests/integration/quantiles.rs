@@ -0,0 +1,102 @@
+use rand::SeedableRng;
+use rand_distr::Distribution;
+use rand_xoshiro::Xoshiro256StarStar;
+
+use average::{Estimate, Quantile, Quantiles, assert_almost_eq};
+
+#[test]
+fn few_observations() {
+    let mut q = Quantiles::new([0.25, 0.5, 0.75]);
+    assert_eq!(q.len(), 0);
+    assert!(q.is_empty());
+    assert_eq!(q.quantiles(), [0., 0., 0.]);
+    q.add(4.);
+    assert_eq!(q.len(), 1);
+    assert_eq!(q.quantiles(), [4., 4., 4.]);
+    q.add(1.);
+    assert_eq!(q.quantiles(), [1., 2.5, 4.]);
+    q.add(3.);
+    assert_eq!(q.quantiles(), [1., 3., 4.]);
+    q.add(2.);
+    assert_eq!(q.len(), 4);
+    assert_eq!(q.quantiles(), [1.5, 2.5, 3.5]);
+    assert_eq!(q.p(0), 0.25);
+    assert_eq!(q.p(2), 0.75);
+}
+
+#[test]
+fn few_observations_f32() {
+    let mut q = Quantiles::<2, f32>::new_generic([0.5, 0.9]);
+    for i in 1..5 {
+        q.add(i as f32);
+    }
+    assert_eq!(q.quantile(0), 2.5f32);
+    assert_eq!(q.quantile(1), 4f32);
+}
+
+#[test]
+fn same_as_single_quantile() {
+    // For one quantile, the extended P² algorithm reduces to the P² algorithm
+    // once the markers are initialized.
+    let mut rng = Xoshiro256StarStar::seed_from_u64(42);
+    let exp = rand_distr::Exp::new(1.).unwrap();
+    for &p in &[0., 0.1, 0.5, 0.9, 0.999, 1.] {
+        let mut single = Quantile::new(p);
+        let mut multi = Quantiles::new([p]);
+        for i in 0..10_000 {
+            let x: f64 = exp.sample(&mut rng);
+            single.add(x);
+            multi.add(x);
+            if (4..20).contains(&i) || i % 1000 == 0 {
+                assert_eq!(single.quantile(), multi.quantile(0), "p = {}, i = {}", p, i);
+            }
+        }
+        assert_eq!(single.quantile(), multi.quantile(0), "p = {}", p);
+    }
+}
+
+#[test]
+fn several_quantiles() {
+    let mut rng = Xoshiro256StarStar::seed_from_u64(42);
+    let normal = rand_distr::Normal::<f64>::new(0., 1.).unwrap();
+    let mut q: Quantiles<4> = Quantiles::new([0.1, 0.5, 0.9, 0.99]);
+    for _ in 0..100_000 {
+        q.add(normal.sample(&mut rng));
+    }
+    assert_eq!(q.len(), 100_000);
+    assert_almost_eq!(q.quantile(0), -1.2816, 0.02);
+    assert_almost_eq!(q.quantile(1), 0., 0.02);
+    assert_almost_eq!(q.quantile(2), 1.2816, 0.02);
+    assert_almost_eq!(q.quantile(3), 2.3263, 0.05);
+}
+
+#[test]
+fn nan_is_largest() {
+    let mut q = Quantiles::new([0.2, 0.4]);
+    q.add(f64::NAN);
+    q.add(1.);
+    q.add(2.);
+    assert_eq!(q.quantile(0), 1.);
+}
+
+#[test]
+#[should_panic]
+fn not_increasing() {
+    Quantiles::new([0.5, 0.5]);
+}
+
+#[cfg(feature = "serde1")]
+#[test]
+fn simple_serde() {
+    let mut q = Quantiles::new([0.1, 0.9]);
+    for i in 0..100 {
+        q.add(f64::from(i));
+    }
+    let b = serde_json::to_string(&q).unwrap();
+    let mut c: Quantiles<2> = serde_json::from_str(&b).unwrap();
+    assert_eq!(c.len(), 100);
+    assert_eq!(c.quantiles(), q.quantiles());
+    q.add(100.);
+    c.add(100.);
+    assert_eq!(c.quantiles(), q.quantiles());
+}
@@ -1,5 +1,9 @@
 use core::iter::Iterator;
 
+use rand::SeedableRng;
+use rand_distr::Distribution;
+use rand_xoshiro::Xoshiro256StarStar;
+
 use average::{Skewness, Estimate, Merge, assert_almost_eq};
 
 #[test]
@@ -65,3 +69,39 @@ fn merge() {
         assert_almost_eq!(avg_total.skewness(), avg_left.skewness(), 1e-14);
     }
 }
+
+#[test]
+fn unmerge() {
+    let sequence: &[f64] = &[1., 2., 3., -4., 5.1, 6.3, 7.3, -8., 9., 1.];
+    for mid in 0..=sequence.len() {
+        let (left, right) = sequence.split_at(mid);
+        let mut total: Skewness = sequence.iter().collect();
+        let expected: Skewness = left.iter().collect();
+        let other: Skewness = right.iter().collect();
+        total.unmerge(&other);
+        assert_eq!(total.len(), expected.len());
+        assert_almost_eq!(total.mean(), expected.mean(), 1e-14);
+        assert_almost_eq!(total.sample_variance(), expected.sample_variance(), 1e-13);
+        assert_almost_eq!(total.skewness(), expected.skewness(), 1e-12);
+    }
+}
+
+#[test]
+fn remove_drift() {
+    // Slide a window over many samples and compare to recalculating it.
+    let exp = rand_distr::Exp::new(0.1).unwrap();
+    let mut rng = Xoshiro256StarStar::seed_from_u64(42);
+    let samples: Vec<f64> = (0..100_000).map(|_| exp.sample(&mut rng)).collect();
+    let mut total = Skewness::new();
+    for (i, &x) in samples.iter().enumerate() {
+        total.add(x);
+        if i >= 50 {
+            total.remove(samples[i - 50]);
+        }
+    }
+    let expected: Skewness = samples[samples.len() - 50..].iter().collect();
+    assert_eq!(total.len(), 50);
+    assert_almost_eq!(total.mean(), expected.mean(), 1e-9);
+    assert_almost_eq!(total.sample_variance(), expected.sample_variance(), 1e-7);
+    assert_almost_eq!(total.skewness(), expected.skewness(), 1e-7);
+}
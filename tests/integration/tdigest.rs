@@ -0,0 +1,168 @@
+use rand::SeedableRng;
+use rand_distr::Distribution;
+use rand_xoshiro::Xoshiro256StarStar;
+
+use average::{Estimate, Merge, TDigest, assert_almost_eq, concatenate};
+
+#[test]
+fn few_observations() {
+    let mut d = TDigest::new();
+    assert_eq!(d.len(), 0);
+    assert!(d.is_empty());
+    assert_eq!(d.quantile(0.5), 0.);
+    assert_eq!(d.cdf(0.), 0.);
+    d.add(1.);
+    assert_eq!(d.len(), 1);
+    assert_eq!(d.quantile(0.5), 1.);
+    d.add(2.);
+    assert_eq!(d.quantile(0.5), 1.5);
+    d.add(3.);
+    assert_eq!(d.quantile(0.5), 2.);
+    d.add(4.);
+    assert_eq!(d.len(), 4);
+    assert_eq!(d.quantile(0.), 1.);
+    assert_eq!(d.quantile(0.5), 2.5);
+    assert_eq!(d.quantile(1.), 4.);
+    assert_eq!(d.estimate(), 2.5);
+    assert_eq!(d.cdf(0.), 0.);
+    assert_eq!(d.cdf(2.5), 0.5);
+    assert_eq!(d.cdf(4.), 1.);
+    assert_eq!(d.trimmed_mean(0., 1.), 2.5);
+    assert_eq!(d.trimmed_mean(0.25, 0.75), 2.5);
+}
+
+#[test]
+fn few_observations_f32() {
+    let d: TDigest<f32> = (1..6).map(|i| i as f32).collect();
+    assert_eq!(d.len(), 5);
+    assert_eq!(d.quantile(0.5), 3f32);
+    assert_eq!(d.cdf(5.), 1f32);
+}
+
+#[test]
+fn nan_is_ignored() {
+    let mut d = TDigest::new();
+    d.add(1.);
+    d.add(f64::NAN);
+    d.add(3.);
+    assert_eq!(d.len(), 2);
+    assert_eq!(d.quantile(0.5), 2.);
+    assert!(d.cdf(f64::NAN).is_nan());
+}
+
+#[test]
+fn uniform() {
+    let mut d = TDigest::new();
+    for i in 0..100_000 {
+        d.add(f64::from(i));
+    }
+    assert_eq!(d.quantile(0.), 0.);
+    assert_eq!(d.quantile(1.), 99_999.);
+    for &p in &[0.001, 0.01, 0.1, 0.25, 0.5, 0.75, 0.9, 0.99, 0.999] {
+        assert_almost_eq!(d.quantile(p) / 100_000., p, 1e-3);
+        assert_almost_eq!(d.cdf(p * 100_000.), p, 1e-3);
+    }
+    assert_almost_eq!(d.trimmed_mean(0., 1.), 49_999.5, 1e-8);
+    assert_almost_eq!(d.trimmed_mean(0.1, 0.5), 29_999.5, 30.);
+}
+
+#[test]
+fn tails() {
+    // The relative error is small close to the extremes.
+    let mut rng = Xoshiro256StarStar::seed_from_u64(42);
+    let exp = rand_distr::Exp::<f64>::new(1.).unwrap();
+    let mut samples: Vec<f64> = (0..100_000).map(|_| exp.sample(&mut rng)).collect();
+    let d: TDigest = samples.iter().collect();
+    samples.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    for &p in &[1e-4, 1e-3, 0.5, 0.999, 0.9999] {
+        let exact = samples[(p * 100_000.) as usize];
+        assert_almost_eq!(d.quantile(p) / exact, 1., 0.05);
+    }
+}
+
+#[test]
+fn compression() {
+    let mut rng = Xoshiro256StarStar::seed_from_u64(42);
+    let normal = rand_distr::Normal::<f64>::new(0., 1.).unwrap();
+    let mut samples: Vec<f64> = (0..100_000).map(|_| normal.sample(&mut rng)).collect();
+    let mut coarse = TDigest::with_compression(20.);
+    let mut fine = TDigest::with_compression(500.);
+    assert_eq!(coarse.compression(), 20.);
+    for &x in &samples {
+        coarse.add(x);
+        fine.add(x);
+    }
+    fine.compress();
+    samples.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let exact = samples[90_000];
+    assert_almost_eq!(coarse.quantile(0.9), exact, 0.05);
+    assert_almost_eq!(fine.quantile(0.9), exact, 0.002);
+    assert_almost_eq!(fine.cdf(exact), 0.9, 1e-3);
+}
+
+#[test]
+#[should_panic]
+fn invalid_compression() {
+    TDigest::with_compression(0.);
+}
+
+#[test]
+fn merge() {
+    let sequence: &[f64] = &[1., 2., 3., 4., 5., 6., 7., 8., 9.];
+    for mid in 0..sequence.len() {
+        let (left, right) = sequence.split_at(mid);
+        let mut d: TDigest = left.iter().collect();
+        let e: TDigest = right.iter().collect();
+        d.merge(&e);
+        assert_eq!(d.len(), 9);
+        assert_eq!(d.quantile(0.), 1.);
+        assert_eq!(d.quantile(0.5), 5.);
+        assert_eq!(d.quantile(1.), 9.);
+    }
+}
+
+#[test]
+fn merge_large() {
+    let mut rng = Xoshiro256StarStar::seed_from_u64(42);
+    let normal = rand_distr::Normal::<f64>::new(0., 1.).unwrap();
+    let mut digests: Vec<TDigest> = (0..10).map(|_| TDigest::new()).collect();
+    for i in 0..100_000 {
+        digests[i % 10].add(normal.sample(&mut rng));
+    }
+    let mut d = TDigest::new();
+    for e in &digests {
+        d.merge(e);
+    }
+    assert_eq!(d.len(), 100_000);
+    assert_almost_eq!(d.quantile(0.5), 0., 0.02);
+    assert_almost_eq!(d.quantile(0.99), 2.3263, 0.05);
+    assert_almost_eq!(d.cdf(-1.2816), 0.1, 0.005);
+}
+
+#[test]
+fn concatenate_with_moments() {
+    use average::Variance;
+
+    concatenate!(Estimator: Merge;
+        [Variance, variance, mean],
+        [TDigest, digest, estimate]);
+
+    let e: Estimator = (1..10).map(f64::from).collect();
+    assert_eq!(e.mean(), 5.);
+    assert_eq!(e.estimate(), 5.);
+}
+
+#[cfg(feature = "serde1")]
+#[test]
+fn simple_serde() {
+    let mut d: TDigest = (1..4).map(f64::from).collect();
+    let b = serde_json::to_string(&d).unwrap();
+    assert_eq!(&b, "{\"compression\":100.0,\"centroids\":[],\
+        \"buffer\":[{\"mean\":1.0,\"weight\":1},{\"mean\":2.0,\"weight\":1},\
+        {\"mean\":3.0,\"weight\":1}],\"min\":1.0,\"max\":3.0,\"len\":3}");
+    let mut e: TDigest = serde_json::from_str(&b).unwrap();
+    assert_eq!(e.quantile(0.5), 2.);
+    d.add(4.);
+    e.add(4.);
+    assert_eq!(e.quantile(0.5), d.quantile(0.5));
+}
@@ -0,0 +1,90 @@
+use average::{DynHistogram, Histogram, Merge, WeightedHistogram, assert_almost_eq};
+use average::{InvalidRangeError, SampleOutOfRangeError};
+
+#[test]
+fn unit_weights() {
+    let mut w = WeightedHistogram::with_const_width(0., 10., 5);
+    let mut h = DynHistogram::with_const_width(0., 10., 5);
+    for i in 0..23 {
+        let x = f64::from(i * 7 % 10);
+        w.add(x, 1.).unwrap();
+        h.add(x).unwrap();
+    }
+    let counts: Vec<f64> = h.bins().iter().map(|&c| c as f64).collect();
+    assert_eq!(w.bins(), &counts[..]);
+    assert_eq!(w.sum_weights_sq(), &counts[..]);
+    assert!(w.iter().map(|(r, _)| r).eq(h.iter().map(|(r, _)| r)));
+}
+
+#[test]
+fn weights() {
+    let mut h = WeightedHistogram::from_ranges([0., 1., 3.].iter().cloned()).unwrap();
+    assert!(!h.has_samples());
+    for &(x, w) in &[(0.5, 2.), (0.7, -0.5), (2., 0.25), (2.9, 3.)] {
+        h.add(x, w).unwrap();
+    }
+    assert_eq!(h.add(3., 1.), Err(SampleOutOfRangeError));
+    assert_eq!(h.add(std::f64::NAN, 1.), Err(SampleOutOfRangeError));
+    assert_eq!(h.bins(), &[1.5, 3.25]);
+    assert_eq!(h.variance(0), 4.25);
+    assert_eq!(h.variance(1), 9.0625);
+    let normalized: Vec<f64> = h.normalized_bins().collect();
+    assert_almost_eq!(normalized[1], 1.625, 1e-14);
+    h.reset();
+    assert!(!h.has_samples());
+    assert_eq!(h.num_bins(), 2);
+}
+
+#[test]
+fn from_ranges_invalid() {
+    assert_eq!(
+        WeightedHistogram::from_ranges([1.].iter().cloned()).unwrap_err(),
+        InvalidRangeError::NotEnoughRanges
+    );
+    assert_eq!(
+        WeightedHistogram::from_ranges([1., 0.].iter().cloned()).unwrap_err(),
+        InvalidRangeError::NotSorted
+    );
+}
+
+#[test]
+fn merge_and_scale() {
+    let mut a = WeightedHistogram::with_const_width(0., 2., 2);
+    let mut b = a.clone();
+    a.add(0.5, 2.).unwrap();
+    b.add(0.5, 1.).unwrap();
+    b.add(1.5, 3.).unwrap();
+    let mut c = a.clone();
+    c += &b;
+    a.merge(&b);
+    assert_eq!(a.bins(), &[3., 3.]);
+    assert_eq!(a.sum_weights_sq(), &[5., 9.]);
+    assert_eq!(c.bins(), a.bins());
+    a *= 2.;
+    assert_eq!(a.bins(), &[6., 6.]);
+    assert_eq!(a.variances().collect::<Vec<f64>>(), vec![20., 36.]);
+}
+
+#[cfg(feature = "serde1")]
+#[test]
+fn simple_serde() {
+    let mut a = WeightedHistogram::with_const_width(0., 2., 2);
+    a.add(0.5, 2.).unwrap();
+    let b = serde_json::to_string(&a).unwrap();
+    assert_eq!(&b, "{\"range\":[0.0,1.0,2.0],\"bin\":[2.0,0.0],\"sum_weights_sq\":[4.0,0.0]}");
+    let c: WeightedHistogram = serde_json::from_str(&b).unwrap();
+    assert_eq!(c.bins(), a.bins());
+    assert_eq!(c.find(1.5), Ok(1));
+}
+
+#[cfg(feature = "serde1")]
+#[test]
+fn serde_invalid() {
+    for json in &[
+        "{\"range\":[0.0,1.0],\"bin\":[1.0,2.0],\"sum_weights_sq\":[1.0,4.0]}",
+        "{\"range\":[0.0,1.0],\"bin\":[1.0],\"sum_weights_sq\":[]}",
+        "{\"range\":[1.0,0.0],\"bin\":[1.0],\"sum_weights_sq\":[1.0]}",
+    ] {
+        assert!(serde_json::from_str::<WeightedHistogram>(json).is_err(), "{}", json);
+    }
+}
@@ -43,6 +43,19 @@ fn simple() {
     assert_almost_eq!(a.error(), f64::sqrt(0.5), 1e-16);
 }
 
+#[test]
+fn simple_f32() {
+    let a: WeightedMeanWithError<f32> = (1..6).map(|x| (x as f32, 1.0)).collect();
+    assert_eq!(a.len(), 5);
+    assert_eq!(a.weighted_mean(), 3.0f32);
+    assert_eq!(a.unweighted_mean(), 3.0f32);
+    assert_eq!(a.sum_weights(), 5.0f32);
+    assert_eq!(a.sample_variance(), 2.5f32);
+    assert_eq!(a.variance_of_weighted_mean(), 0.5f32);
+    #[cfg(any(feature = "std", feature = "libm"))]
+    assert_almost_eq!(a.error(), f32::sqrt(0.5), 1e-7);
+}
+
 #[cfg(feature = "serde1")]
 #[test]
 fn simple_serde() {
@@ -0,0 +1,185 @@
+use core::iter::Iterator;
+
+use average::{WeightedVariance, WeightKind, Variance, Merge, assert_almost_eq};
+#[cfg(any(feature = "std", feature = "libm"))]
+use average::{WeightedSkewness, WeightedKurtosis, Skewness, Kurtosis};
+
+#[test]
+fn trivial() {
+    let mut a = WeightedVariance::new();
+    assert!(a.is_empty());
+    assert_eq!(a.sum_weights(), 0.);
+    assert_eq!(a.mean(), 0.);
+    assert_eq!(a.population_variance(), 0.);
+    assert_eq!(a.sample_variance(WeightKind::Frequency), 0.);
+    assert_eq!(a.sample_variance(WeightKind::Reliability), 0.);
+    a.add(1.0, 1.0);
+    assert_eq!(a.mean(), 1.0);
+    assert_eq!(a.sum_weights(), 1.0);
+    assert_eq!(a.population_variance(), 0.0);
+    assert_eq!(a.sample_variance(WeightKind::Frequency), 0.0);
+    assert_eq!(a.sample_variance(WeightKind::Reliability), 0.0);
+    assert_eq!(a.variance_of_mean(WeightKind::Reliability), 0.0);
+    #[cfg(any(feature = "std", feature = "libm"))]
+    assert_eq!(a.error_mean(WeightKind::Frequency), 0.0);
+    a.add(1.0, 1.0);
+    assert_eq!(a.mean(), 1.0);
+    assert_eq!(a.sum_weights(), 2.0);
+    assert_eq!(a.sum_weights_sq(), 2.0);
+    assert_eq!(a.population_variance(), 0.0);
+    assert_eq!(a.sample_variance(WeightKind::Frequency), 0.0);
+}
+
+#[test]
+fn simple() {
+    let a: WeightedVariance = (1..6).map(|x| (f64::from(x), 1.0)).collect();
+    let b: Variance = (1..6).map(f64::from).collect();
+    assert_eq!(a.mean(), 3.0);
+    assert_eq!(a.effective_len(), 5.0);
+    assert_eq!(a.population_variance(), b.population_variance());
+    assert_eq!(a.sample_variance(WeightKind::Frequency), 2.5);
+    assert_eq!(a.sample_variance(WeightKind::Reliability), 2.5);
+    assert_eq!(a.variance_of_mean(WeightKind::Frequency), 0.5);
+    assert_eq!(a.variance_of_mean(WeightKind::Reliability), 0.5);
+    #[cfg(any(feature = "std", feature = "libm"))]
+    assert_almost_eq!(a.error_mean(WeightKind::Frequency), b.error(), 1e-16);
+}
+
+#[test]
+fn simple_f32() {
+    let a: WeightedVariance<f32> = (1..6).map(|x| (x as f32, 1.0)).collect();
+    assert_eq!(a.mean(), 3.0f32);
+    assert_eq!(a.sample_variance(WeightKind::Frequency), 2.5f32);
+    assert_eq!(a.variance_of_mean(WeightKind::Reliability), 0.5f32);
+}
+
+#[cfg(feature = "serde1")]
+#[test]
+fn simple_serde() {
+    let a: WeightedVariance = (1..6).map(|x| (f64::from(x), 1.0)).collect();
+    let b = serde_json::to_string(&a).unwrap();
+    assert_eq!(&b, "{\"weight_sum\":5.0,\"weight_sum_sq\":5.0,\"avg\":3.0,\"sum_2\":10.0}");
+    let c: WeightedVariance = serde_json::from_str(&b).unwrap();
+    assert_eq!(c.mean(), 3.0);
+    assert_eq!(c.sample_variance(WeightKind::Frequency), 2.5);
+}
+
+#[test]
+fn frequency_weights() {
+    // Integer weights are equivalent to repeated observations.
+    let weighted: &[(f64, f64)] = &[(1., 2.), (3., 1.), (4., 3.), (7., 1.)];
+    let repeated = [1., 1., 3., 4., 4., 4., 7.];
+    let a: WeightedVariance = weighted.iter().collect();
+    let b: Variance = repeated.iter().collect();
+    assert_almost_eq!(a.mean(), b.mean(), 1e-15);
+    assert_almost_eq!(a.population_variance(), b.population_variance(), 1e-14);
+    assert_almost_eq!(a.sample_variance(WeightKind::Frequency), b.sample_variance(), 1e-14);
+    assert_almost_eq!(a.variance_of_mean(WeightKind::Frequency), b.variance_of_mean(), 1e-14);
+}
+
+#[test]
+fn reliability_weights() {
+    // Reliability weights do not depend on the scale of the weights.
+    let weighted: &[(f64, f64)] = &[(1., 0.2), (3., 0.1), (4., 0.3), (7., 0.1)];
+    let a: WeightedVariance = weighted.iter().collect();
+    let b: WeightedVariance = weighted.iter().map(|&(x, w)| (x, 10. * w)).collect();
+    assert_almost_eq!(a.mean(), b.mean(), 1e-15);
+    assert_almost_eq!(a.population_variance(), b.population_variance(), 1e-14);
+    assert_almost_eq!(a.sample_variance(WeightKind::Reliability),
+                      b.sample_variance(WeightKind::Reliability), 1e-14);
+    assert_almost_eq!(a.variance_of_mean(WeightKind::Reliability),
+                      b.variance_of_mean(WeightKind::Reliability), 1e-14);
+    // Compare to the two-pass calculation.
+    let sum_w: f64 = weighted.iter().map(|&(_, w)| w).sum();
+    let sum_w2: f64 = weighted.iter().map(|&(_, w)| w * w).sum();
+    let mean = weighted.iter().map(|&(x, w)| w * x).sum::<f64>() / sum_w;
+    let sum_2: f64 = weighted.iter().map(|&(x, w)| w * (x - mean) * (x - mean)).sum();
+    assert_almost_eq!(a.mean(), mean, 1e-15);
+    assert_almost_eq!(a.sample_variance(WeightKind::Reliability),
+                      sum_2 / (sum_w - sum_w2 / sum_w), 1e-14);
+    assert_almost_eq!(a.effective_len(), sum_w * sum_w / sum_w2, 1e-14);
+}
+
+#[test]
+fn zero_weight() {
+    let a: WeightedVariance = [(1., 0.), (2., 1.), (5., 0.), (4., 1.)].iter().collect();
+    assert_eq!(a.mean(), 3.);
+    assert_eq!(a.sum_weights(), 2.);
+    assert_eq!(a.population_variance(), 1.);
+}
+
+#[test]
+fn merge() {
+    let sequence: &[(f64, f64)] = &[
+        (1., 0.1), (2., 0.2), (3., 0.3), (4., 0.4), (5., 0.5),
+        (6., 0.6), (7., 0.7), (8., 0.8), (9., 0.9)];
+    for mid in 0..sequence.len() {
+        let (left, right) = sequence.split_at(mid);
+        let avg_total: WeightedVariance = sequence.iter().collect();
+        let mut avg_left: WeightedVariance = left.iter().collect();
+        let avg_right: WeightedVariance = right.iter().collect();
+        avg_left.merge(&avg_right);
+        assert_almost_eq!(avg_total.sum_weights(), avg_left.sum_weights(), 1e-14);
+        assert_almost_eq!(avg_total.sum_weights_sq(), avg_left.sum_weights_sq(), 1e-14);
+        assert_almost_eq!(avg_total.mean(), avg_left.mean(), 1e-14);
+        assert_almost_eq!(avg_total.sample_variance(WeightKind::Reliability),
+                          avg_left.sample_variance(WeightKind::Reliability), 1e-14);
+    }
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+#[test]
+fn skewness_kurtosis_frequency_weights() {
+    let weighted: &[(f64, f64)] = &[(1., 2.), (3., 1.), (4., 3.), (7., 1.), (-2., 4.)];
+    let repeated = [1., 1., 3., 4., 4., 4., 7., -2., -2., -2., -2.];
+    let a: WeightedSkewness = weighted.iter().collect();
+    let b: Skewness = repeated.iter().collect();
+    assert_almost_eq!(a.mean(), b.mean(), 1e-15);
+    assert_almost_eq!(a.sample_variance(WeightKind::Frequency), b.sample_variance(), 1e-14);
+    assert_almost_eq!(a.skewness(), b.skewness(), 1e-14);
+    let a: WeightedKurtosis = weighted.iter().collect();
+    let b: Kurtosis = repeated.iter().collect();
+    assert_almost_eq!(a.mean(), b.mean(), 1e-15);
+    assert_almost_eq!(a.sample_variance(WeightKind::Frequency), b.sample_variance(), 1e-14);
+    assert_almost_eq!(a.skewness(), b.skewness(), 1e-14);
+    assert_almost_eq!(a.kurtosis(), b.kurtosis(), 1e-13);
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+#[test]
+fn skewness_kurtosis_trivial() {
+    let mut a = WeightedKurtosis::new();
+    assert!(a.is_empty());
+    assert_eq!(a.skewness(), 0.);
+    assert_eq!(a.kurtosis(), 0.);
+    a.add(1., 0.5);
+    a.add(1., 2.);
+    assert_eq!(a.mean(), 1.);
+    assert_eq!(a.skewness(), 0.);
+    assert_eq!(a.kurtosis(), 0.);
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+#[test]
+fn skewness_kurtosis_merge() {
+    let sequence: &[(f64, f64)] = &[
+        (1., 0.1), (2., 0.5), (3., 0.3), (8., 0.4), (5., 0.5),
+        (6., 0.2), (7., 0.7), (4., 0.8), (9., 0.9)];
+    for mid in 0..sequence.len() {
+        let (left, right) = sequence.split_at(mid);
+        let total: WeightedKurtosis = sequence.iter().collect();
+        let mut merged: WeightedKurtosis = left.iter().collect();
+        let b: WeightedKurtosis = right.iter().collect();
+        merged.merge(&b);
+        assert_almost_eq!(total.mean(), merged.mean(), 1e-14);
+        assert_almost_eq!(total.population_variance(), merged.population_variance(), 1e-14);
+        assert_almost_eq!(total.skewness(), merged.skewness(), 1e-14);
+        assert_almost_eq!(total.kurtosis(), merged.kurtosis(), 1e-14);
+
+        let total: WeightedSkewness = sequence.iter().collect();
+        let mut merged: WeightedSkewness = left.iter().collect();
+        let b: WeightedSkewness = right.iter().collect();
+        merged.merge(&b);
+        assert_almost_eq!(total.skewness(), merged.skewness(), 1e-14);
+    }
+}
@@ -0,0 +1,187 @@
+use average::{
+    WindowedMean, WindowedVariance, WindowedMin, WindowedMax, Mean, Variance,
+    Estimate, assert_almost_eq,
+};
+
+#[test]
+fn trivial() {
+    let mut a: WindowedVariance<3> = WindowedVariance::new();
+    assert!(a.is_empty());
+    assert!(!a.is_full());
+    assert_eq!(a.len(), 0);
+    assert_eq!(a.mean(), 0.);
+    assert_eq!(a.sample_variance(), 0.);
+    assert_eq!(a.population_variance(), 0.);
+    a.add(1.);
+    assert_eq!(a.len(), 1);
+    assert_eq!(a.mean(), 1.);
+    assert_eq!(a.sample_variance(), 0.);
+    for _ in 0..10 {
+        a.add(1.);
+    }
+    assert!(a.is_full());
+    assert_eq!(a.len(), 3);
+    assert_eq!(a.mean(), 1.);
+    assert_eq!(a.sample_variance(), 0.);
+
+    let mut b: WindowedMin<3> = WindowedMin::new();
+    assert!(b.is_empty());
+    assert_eq!(b.min(), f64::INFINITY);
+    b.add(1.);
+    assert_eq!(b.len(), 1);
+    assert_eq!(b.min(), 1.);
+
+    let mut c: WindowedMax<3> = WindowedMax::new();
+    assert!(c.is_empty());
+    assert_eq!(c.max(), f64::NEG_INFINITY);
+    c.add(1.);
+    assert_eq!(c.len(), 1);
+    assert_eq!(c.max(), 1.);
+}
+
+#[test]
+fn simple() {
+    let a: WindowedMean<5> = (1..11).map(f64::from).collect();
+    assert_eq!(a.len(), 5);
+    assert_eq!(a.mean(), 8.);
+    let a: WindowedVariance<5> = (1..11).map(f64::from).collect();
+    assert_eq!(a.mean(), 8.);
+    assert_eq!(a.sample_variance(), 2.5);
+    assert_eq!(a.population_variance(), 2.);
+    assert_eq!(a.variance_of_mean(), 0.5);
+    #[cfg(any(feature = "std", feature = "libm"))]
+    assert_almost_eq!(a.error(), f64::sqrt(0.5), 1e-16);
+    let a: WindowedMin<5> = (1..11).map(f64::from).collect();
+    assert_eq!(a.min(), 6.);
+    let a: WindowedMax<5> = (1..11).rev().map(f64::from).collect();
+    assert_eq!(a.max(), 5.);
+}
+
+#[test]
+fn simple_f32() {
+    let a: WindowedVariance<5, f32> = (1..11).map(|x| x as f32).collect();
+    assert_eq!(a.mean(), 8.0f32);
+    assert_eq!(a.sample_variance(), 2.5f32);
+    let a: WindowedMin<5, f32> = (1..11).map(|x| x as f32).collect();
+    assert_eq!(a.min(), 6.0f32);
+}
+
+#[test]
+fn window_of_one() {
+    let mut a: WindowedVariance<1> = WindowedVariance::new();
+    let mut b: WindowedMin<1> = WindowedMin::new();
+    let mut c: WindowedMax<1> = WindowedMax::new();
+    for &x in &[3., -1., 4., 1.] {
+        a.add(x);
+        b.add(x);
+        c.add(x);
+        assert_eq!(a.mean(), x);
+        assert_eq!(a.population_variance(), 0.);
+        assert_eq!(b.min(), x);
+        assert_eq!(c.max(), x);
+    }
+}
+
+#[test]
+fn nan() {
+    let mut a: WindowedMin<2> = WindowedMin::new();
+    let mut b: WindowedMax<2> = WindowedMax::new();
+    for &x in &[1., f64::NAN] {
+        a.add(x);
+        b.add(x);
+    }
+    assert_eq!(a.min(), 1.);
+    assert_eq!(b.max(), 1.);
+    a.add(f64::NAN);
+    b.add(f64::NAN);
+    assert_eq!(a.len(), 2);
+    assert_eq!(a.min(), f64::INFINITY);
+    assert_eq!(b.max(), f64::NEG_INFINITY);
+}
+
+#[test]
+fn sliding() {
+    // Compare to recalculating the estimates from the window.
+    let samples: Vec<f64> = (0..1000_u32)
+        .map(|i| f64::from((i * 7919) % 211) * 1.5 - 100.)
+        .collect();
+    let mut mean: WindowedMean<7> = WindowedMean::new();
+    let mut var: WindowedVariance<7> = WindowedVariance::new();
+    let mut min: WindowedMin<7> = WindowedMin::new();
+    let mut max: WindowedMax<7> = WindowedMax::new();
+    for (i, &x) in samples.iter().enumerate() {
+        mean.add(x);
+        var.add(x);
+        min.add(x);
+        max.add(x);
+        let window = &samples[(i + 1).saturating_sub(7)..=i];
+        let expected_var: Variance = window.iter().collect();
+        let expected_mean: Mean = window.iter().collect();
+        assert_almost_eq!(mean.mean(), expected_mean.mean(), 1e-12);
+        assert_almost_eq!(var.mean(), expected_var.mean(), 1e-12);
+        assert_almost_eq!(var.sample_variance(), expected_var.sample_variance(), 1e-9);
+        assert_eq!(min.min(), window.iter().cloned().fold(f64::INFINITY, f64::min));
+        assert_eq!(max.max(), window.iter().cloned().fold(f64::NEG_INFINITY, f64::max));
+        assert_eq!(min.len(), window.len() as u64);
+    }
+}
+
+#[test]
+fn numerically_unstable() {
+    // Rounding errors from evicting huge values are discarded once the ring
+    // buffer wraps around.
+    let mut a: WindowedVariance<4> = WindowedVariance::new();
+    for _ in 0..4 {
+        a.add(1e9);
+    }
+    for &x in &[4., 7., 13., 16.] {
+        a.add(x);
+    }
+    assert_eq!(a.mean(), 10.);
+    assert_eq!(a.sample_variance(), 30.);
+}
+
+#[test]
+#[should_panic]
+fn empty_window() {
+    let _: WindowedMean<0> = WindowedMean::new();
+}
+
+#[cfg(feature = "serde1")]
+#[test]
+fn simple_serde() {
+    let a: WindowedVariance<3> = (1..5).map(f64::from).collect();
+    let b = serde_json::to_string(&a).unwrap();
+    assert_eq!(&b, "{\"window\":{\"buf\":[4.0,2.0,3.0],\"next\":1,\"len\":3},\"avg\":3.0,\"sum_2\":2.0}");
+    let c: WindowedVariance<3> = serde_json::from_str(&b).unwrap();
+    assert_eq!(c.mean(), 3.);
+    assert_eq!(c.sample_variance(), 1.);
+
+    let a: WindowedMax<3> = (1..5).rev().map(f64::from).collect();
+    let b = serde_json::to_string(&a).unwrap();
+    let mut c: WindowedMax<3> = serde_json::from_str(&b).unwrap();
+    assert_eq!(c.max(), 3.);
+    c.add(0.);
+    assert_eq!(c.max(), 2.);
+}
+
+#[cfg(feature = "serde1")]
+#[test]
+fn serde_invalid() {
+    for json in &[
+        "{\"window\":{\"buf\":[4.0,2.0,3.0],\"next\":3,\"len\":3},\"avg\":3.0,\"sum_2\":2.0}",
+        "{\"window\":{\"buf\":[4.0,2.0,3.0],\"next\":1,\"len\":4},\"avg\":3.0,\"sum_2\":2.0}",
+        "{\"window\":{\"buf\":[4.0,2.0,3.0],\"next\":0,\"len\":2},\"avg\":3.0,\"sum_2\":2.0}",
+    ] {
+        assert!(serde_json::from_str::<WindowedVariance<3>>(json).is_err(), "{}", json);
+    }
+
+    for json in &[
+        "{\"deque\":{\"values\":[1.0,3.0,2.0],\"positions\":[3,1,2],\"head\":3,\"len\":3,\"n\":4}}",
+        "{\"deque\":{\"values\":[1.0,3.0,2.0],\"positions\":[3,1,2],\"head\":1,\"len\":4,\"n\":4}}",
+        "{\"deque\":{\"values\":[1.0,3.0,2.0],\"positions\":[3,1,2],\"head\":1,\"len\":3,\"n\":3}}",
+        "{\"deque\":{\"values\":[1.0,3.0,2.0],\"positions\":[3,1,2],\"head\":1,\"len\":3,\"n\":9}}",
+    ] {
+        assert!(serde_json::from_str::<WindowedMax<3>>(json).is_err(), "{}", json);
+    }
+}